@@ -0,0 +1,17 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tai_stuff::{CachedTaiClock, TaiDateTime};
+use time::Duration;
+
+fn now(c: &mut Criterion) {
+    let mut group = c.benchmark_group("now");
+    group.bench_function("now", |b| b.iter(|| black_box(TaiDateTime::now())));
+    group.bench_function("now_coarse", |b| {
+        b.iter(|| black_box(TaiDateTime::now_coarse()))
+    });
+    let clock = CachedTaiClock::start(Duration::milliseconds(1));
+    group.bench_function("cached", |b| b.iter(|| black_box(clock.now())));
+    group.finish();
+}
+
+criterion_group!(benches, now);
+criterion_main!(benches);
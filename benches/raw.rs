@@ -0,0 +1,131 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tai_stuff::{raw, utc_bucket_indices, TaiDateTime, UtcBucket};
+use time::OffsetDateTime;
+
+const COUNT: usize = 1_000_000;
+
+// UNIX time stamps from 1970 to 2030, either sorted or shuffled.
+fn time_stamps(shuffled: bool) -> Vec<i64> {
+    let step = 1_900_000_000 / COUNT as i64;
+    let mut time_stamps: Vec<i64> = (0..COUNT as i64).map(|i| i * step).collect();
+    if shuffled {
+        // A fixed xorshift, so every run shuffles the same way.
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        for i in (1..time_stamps.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            time_stamps.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+    }
+    time_stamps
+}
+
+fn utc_to_tai(c: &mut Criterion) {
+    for (name, shuffled) in [("sorted", false), ("shuffled", true)] {
+        let input = time_stamps(shuffled);
+        let mut group = c.benchmark_group(format!("utc_to_tai/{name}"));
+        group.bench_function("from", |b| {
+            b.iter(|| {
+                for &t in &input {
+                    let utc = OffsetDateTime::from_unix_timestamp(t).unwrap();
+                    black_box(TaiDateTime::from(utc));
+                }
+            })
+        });
+        group.bench_function("in_place", |b| {
+            b.iter_batched_ref(
+                || input.clone(),
+                |values| raw::apply_tai_offset_in_place(values),
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+fn tai_to_utc(c: &mut Criterion) {
+    for (name, shuffled) in [("sorted", false), ("shuffled", true)] {
+        let mut input = time_stamps(shuffled);
+        raw::apply_tai_offset_in_place(&mut input);
+        let mut group = c.benchmark_group(format!("tai_to_utc/{name}"));
+        group.bench_function("from", |b| {
+            b.iter(|| {
+                for &t in &input {
+                    black_box(OffsetDateTime::from(TaiDateTime::from_timespec(t, 0)));
+                }
+            })
+        });
+        group.bench_function("in_place", |b| {
+            b.iter_batched_ref(
+                || input.clone(),
+                |values| raw::remove_tai_offset_in_place(values),
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+fn unix_timestamp(c: &mut Criterion) {
+    let input = time_stamps(true);
+    let mut group = c.benchmark_group("unix_timestamp");
+    group.bench_function("from", |b| {
+        b.iter(|| {
+            for &t in &input {
+                let utc = OffsetDateTime::from_unix_timestamp(t).unwrap();
+                black_box(OffsetDateTime::from(TaiDateTime::from(utc)).unix_timestamp());
+            }
+        })
+    });
+    group.bench_function("direct", |b| {
+        b.iter(|| {
+            for &t in &input {
+                black_box(TaiDateTime::from_unix_utc_timestamp(t).to_unix_utc_timestamp());
+            }
+        })
+    });
+    group.finish();
+}
+
+fn utc_day_index(c: &mut Criterion) {
+    for (name, shuffled) in [("sorted", false), ("shuffled", true)] {
+        let input: Vec<TaiDateTime> = time_stamps(shuffled)
+            .into_iter()
+            .map(TaiDateTime::from_unix_utc_timestamp)
+            .collect();
+        let mut group = c.benchmark_group(format!("utc_day_index/{name}"));
+        group.bench_function("calendar", |b| {
+            b.iter(|| {
+                for t in &input {
+                    black_box(t.to_utc_date_time().0.to_julian_day() - 2_440_588);
+                }
+            })
+        });
+        group.bench_function("direct", |b| {
+            b.iter(|| {
+                for t in &input {
+                    black_box(t.utc_day_index());
+                }
+            })
+        });
+        group.bench_function("batch", |b| {
+            let mut out = Vec::with_capacity(input.len());
+            b.iter(|| {
+                out.clear();
+                utc_bucket_indices(&input, UtcBucket::Day, &mut out);
+                black_box(&out);
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(
+    benches,
+    utc_to_tai,
+    tai_to_utc,
+    unix_timestamp,
+    utc_day_index
+);
+criterion_main!(benches);
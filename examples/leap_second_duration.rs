@@ -0,0 +1,16 @@
+use tai_stuff::TaiDateTime;
+use time::{macros::datetime, OffsetDateTime};
+
+// Compares the time elapsed since right before the first leap second in 1972
+// on the UTC and on the TAI time scale. The difference is the number of leap
+// seconds since then.
+fn main() {
+    let before = TaiDateTime::from(datetime!(1972-06-30 23:59:59 UTC));
+    let after = TaiDateTime::now();
+
+    println!(
+        "UTC: {}",
+        OffsetDateTime::from(after) - OffsetDateTime::from(before)
+    );
+    println!("TAI: {}", after - before);
+}
@@ -0,0 +1,9 @@
+use tai_stuff::tracing::TaiTime;
+
+fn main() {
+    tracing_subscriber::fmt()
+        .with_timer(TaiTime::rfc3339())
+        .init();
+
+    tracing::info!("this event is stamped on the TAI time scale");
+}
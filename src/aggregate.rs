@@ -0,0 +1,140 @@
+use crate::TaiDateTime;
+
+/// Returns the mean of the instants, or `None` if there are none. It's rounded
+/// to the nearest nanosecond, with ties rounded towards the earlier instant.
+///
+/// The offsets from the first instant are summed up as nanoseconds in an
+/// `i128`, so this is exact and can't overflow for fewer than about 9 billion
+/// instants, even if they span the whole range of [`TaiDateTime`].
+pub fn average<I: IntoIterator<Item = TaiDateTime>>(iter: I) -> Option<TaiDateTime> {
+    let mut iter = iter.into_iter();
+    let first = iter.next()?;
+    let (mut sum, mut count) = (0i128, 1i128);
+    for time in iter {
+        sum += time.signed_nanos_since(first);
+        count += 1;
+    }
+    let (mean, remainder) = (sum.div_euclid(count), sum.rem_euclid(count));
+    let mean = if 2 * remainder > count {
+        mean + 1
+    } else {
+        mean
+    };
+    // The mean is between the earliest and the latest instant, so it's in
+    // range.
+    TaiDateTime::from_unix_tai_nanos(first.unix_tai_nanos() + mean).ok()
+}
+
+/// Returns the earliest and the latest of the instants in a single pass, or
+/// `None` if there are none.
+pub fn min_max<I: IntoIterator<Item = TaiDateTime>>(iter: I) -> Option<(TaiDateTime, TaiDateTime)> {
+    let mut iter = iter.into_iter();
+    let first = iter.next()?;
+    Some(iter.fold((first, first), |(min, max), time| {
+        (min.min(time), max.max(time))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+
+    use super::*;
+
+    fn nanos(nanos: i128) -> TaiDateTime {
+        TaiDateTime::from_unix_tai_nanos(nanos).unwrap()
+    }
+
+    // Instants anywhere in the range of `TaiDateTime`, close to its ends or
+    // close to each other.
+    fn random_nanos(rng: &mut fastrand::Rng) -> i128 {
+        let (min, max) = (
+            TaiDateTime::MIN.unix_tai_nanos(),
+            TaiDateTime::MAX.unix_tai_nanos(),
+        );
+        match rng.u8(0..4) {
+            0 => rng.i128(min..=max),
+            1 => min + rng.i128(0..10),
+            2 => max - rng.i128(0..10),
+            _ => rng.i128(-10..10),
+        }
+    }
+
+    // The mean rounded to the nearest integer with ties towards negative
+    // infinity, i.e. `ceil(sum / count - 1/2)`.
+    fn reference_mean(values: &[i128]) -> i128 {
+        let count = values.len() as i128;
+        let sum: i128 = values.iter().sum();
+        -(count - 2 * sum).div_euclid(2 * count)
+    }
+
+    #[test]
+    fn average_matches_i128() {
+        let mut rng = fastrand::Rng::with_seed(0x3a7e);
+        let mut values = [0; 8];
+        for _ in 0..10_000 {
+            let values = &mut values[..rng.usize(1..=8)];
+            for value in values.iter_mut() {
+                *value = random_nanos(&mut rng);
+            }
+            let times = values.iter().map(|&value| nanos(value));
+            assert_eq!(
+                average(times).map(|time| time.unix_tai_nanos()),
+                Some(reference_mean(values)),
+                "{values:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn midpoint_matches_i128() {
+        let mut rng = fastrand::Rng::with_seed(0x41d);
+        for _ in 0..10_000 {
+            let (a, b) = (random_nanos(&mut rng), random_nanos(&mut rng));
+            let (earlier, later) = (a.min(b), a.max(b));
+            let expected = nanos(earlier + (later - earlier) / 2);
+            assert_eq!(nanos(a).midpoint(nanos(b)), expected, "{a} {b}");
+            assert_eq!(nanos(b).midpoint(nanos(a)), expected, "{a} {b}");
+            assert_eq!(average([nanos(a), nanos(b)]), Some(expected), "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn ties_round_towards_the_earlier_instant() {
+        for t in [-1_000_000_001, -1, 0, 999_999_999] {
+            assert_eq!(nanos(t).midpoint(nanos(t + 1)), nanos(t));
+            assert_eq!(nanos(t + 1).midpoint(nanos(t)), nanos(t));
+            assert_eq!(average([nanos(t + 1), nanos(t)]), Some(nanos(t)));
+            assert_eq!(average([nanos(t), nanos(t + 3)]), Some(nanos(t + 1)));
+            // Only actual ties round down.
+            assert_eq!(
+                average([nanos(t), nanos(t + 1), nanos(t + 1)]),
+                Some(nanos(t + 1)),
+            );
+            assert_eq!(average([nanos(t), nanos(t), nanos(t + 1)]), Some(nanos(t)),);
+        }
+    }
+
+    #[test]
+    fn extremes() {
+        let (min, max) = (TaiDateTime::MIN, TaiDateTime::MAX);
+        // Half a nanosecond before the epoch, rounded down.
+        assert_eq!(min.midpoint(max), nanos(-1));
+        assert_eq!(max.midpoint(min), nanos(-1));
+        assert_eq!(average([min, max]), Some(nanos(-1)));
+        assert_eq!(min.midpoint(min), min);
+        assert_eq!(max.midpoint(max), max);
+        assert_eq!(average([max; 5]), Some(max));
+        assert_eq!(average([min; 5]), Some(min));
+        assert_eq!(average([max, max, max - Duration::NANOSECOND]), Some(max),);
+        assert_eq!(average([min, min, min + Duration::NANOSECOND]), Some(min),);
+        let spread = [min, min, max].map(|time| time.unix_tai_nanos());
+        assert_eq!(
+            average([min, min, max]),
+            Some(nanos(reference_mean(&spread))),
+        );
+        assert_eq!(average(core::iter::empty()), None);
+        assert_eq!(min_max([max, min, nanos(0)]), Some((min, max)));
+        assert_eq!(min_max(core::iter::empty()), None);
+    }
+}
@@ -0,0 +1,171 @@
+use core::{error::Error, fmt};
+
+use rkyv::{
+    bytecheck::{CheckBytes, Verify},
+    rancor::{fail, Fallible, Source},
+    Archive, Archived, Deserialize, Place, Portable, Serialize,
+};
+use time::Duration;
+
+use crate::TaiDateTime;
+
+/// An archived [`TaiDateTime`], which can be accessed directly in an rkyv
+/// archive, e.g. in a memory mapped file, without deserializing it.
+///
+/// Just like with [`TaiDateTime::as_timespec`], it consists of the whole TAI
+/// seconds since 1970-01-01 00:00:00 TAI, rounded down, and the nanoseconds
+/// within that second. Both are stored with the endianness rkyv is configured
+/// to use.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[rkyv(crate = rkyv)]
+#[repr(C)]
+pub struct ArchivedTaiDateTime {
+    secs: Archived<i64>,
+    nanos: Archived<u32>,
+}
+
+impl ArchivedTaiDateTime {
+    /// The whole TAI seconds since 1970-01-01 00:00:00 TAI and the nanoseconds
+    /// within that second, like [`TaiDateTime::as_timespec`].
+    pub fn as_timespec(&self) -> (i64, i32) {
+        (self.secs.to_native(), self.nanos.to_native() as i32)
+    }
+
+    /// Converts to the native [`TaiDateTime`].
+    pub fn to_native(&self) -> TaiDateTime {
+        let (secs, nanos) = self.as_timespec();
+        TaiDateTime(Duration::new(secs, nanos))
+    }
+}
+
+impl Archive for TaiDateTime {
+    type Archived = ArchivedTaiDateTime;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        let (secs, nanos) = self.as_timespec();
+        // SAFETY: The place points to memory suitable for holding the archived
+        // date time and both fields get written in full.
+        unsafe {
+            let out = out.ptr();
+            core::ptr::addr_of_mut!((*out).secs).write(Archived::<i64>::from_native(secs));
+            core::ptr::addr_of_mut!((*out).nanos).write(Archived::<u32>::from_native(nanos as u32));
+        }
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for TaiDateTime {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<TaiDateTime, D> for ArchivedTaiDateTime {
+    fn deserialize(&self, _: &mut D) -> Result<TaiDateTime, D::Error> {
+        Ok(self.to_native())
+    }
+}
+
+impl PartialEq<TaiDateTime> for ArchivedTaiDateTime {
+    fn eq(&self, other: &TaiDateTime) -> bool {
+        self.as_timespec() == other.as_timespec()
+    }
+}
+
+impl PartialEq<ArchivedTaiDateTime> for TaiDateTime {
+    fn eq(&self, other: &ArchivedTaiDateTime) -> bool {
+        other == self
+    }
+}
+
+// The nanoseconds of an archived date time are not within a second.
+#[derive(Debug)]
+struct InvalidNanoseconds(u32);
+
+impl fmt::Display for InvalidNanoseconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the nanoseconds of the archived date time are out of range: {}",
+            self.0
+        )
+    }
+}
+
+impl Error for InvalidNanoseconds {}
+
+// SAFETY: Only the nanoseconds are checked, which `CheckBytes` already
+// ensured to be a valid integer.
+unsafe impl<C> Verify<C> for ArchivedTaiDateTime
+where
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        let nanos = self.nanos.to_native();
+        if nanos >= 1_000_000_000 {
+            fail!(InvalidNanoseconds(nanos));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use rkyv::{rancor, vec::ArchivedVec};
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn times() -> Vec<TaiDateTime> {
+        let midnight = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        vec![
+            TaiDateTime::MIN,
+            TaiDateTime(-Duration::milliseconds(500)),
+            TaiDateTime(Duration::ZERO),
+            midnight - Duration::milliseconds(750),
+            midnight,
+            TaiDateTime::MAX,
+        ]
+    }
+
+    #[test]
+    fn slice_round_trip() {
+        let times = times();
+        let bytes = rkyv::to_bytes::<rancor::Error>(&times).unwrap();
+        let archived =
+            rkyv::access::<ArchivedVec<ArchivedTaiDateTime>, rancor::Error>(&bytes).unwrap();
+
+        // The archived date times are read right out of the bytes.
+        let range = bytes.as_ptr_range();
+        assert!(range.contains(&archived.as_ptr().cast()));
+        assert_eq!(archived.len(), times.len());
+        for (archived, &time) in archived.iter().zip(&times) {
+            assert_eq!(*archived, time);
+            assert_eq!(time, *archived);
+            assert_eq!(archived.as_timespec(), time.as_timespec());
+            assert_eq!(archived.to_native(), time);
+        }
+        // They sort the same as the native ones.
+        assert!(archived.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let deserialized = rkyv::deserialize::<Vec<TaiDateTime>, rancor::Error>(archived).unwrap();
+        assert_eq!(deserialized, times);
+    }
+
+    #[test]
+    fn invalid_nanoseconds() {
+        let mut bytes = rkyv::to_bytes::<rancor::Error>(&times()).unwrap();
+        let archived =
+            rkyv::access::<ArchivedVec<ArchivedTaiDateTime>, rancor::Error>(&bytes).unwrap();
+        // The nanoseconds of `TaiDateTime::MAX`, which are 999999999.
+        let offset = archived.as_ptr() as usize - bytes.as_ptr() as usize
+            + (times().len() - 1) * core::mem::size_of::<ArchivedTaiDateTime>()
+            + 8;
+        bytes[offset..offset + 4].copy_from_slice(&1_000_000_000u32.to_le_bytes());
+        assert!(rkyv::access::<ArchivedVec<ArchivedTaiDateTime>, rancor::Error>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use time::{Duration, OffsetDateTime};
+
+#[cfg(feature = "alloc")]
+use crate::leap_seconds_at_utc;
+use crate::{diff_before, floor_seconds, leap_table, seek_utc, TaiDateTime};
+
+/// Converts a slice of UTC date times to TAI, appending the results to `out`.
+/// Each element ends up the same as with the [`From`] conversion.
+///
+/// If the input is sorted, which is checked with a single pass over it, the
+/// leap second table is walked alongside the input, so the whole conversion
+/// takes linear time. Otherwise each element is looked up on its own.
+#[cfg(feature = "alloc")]
+pub fn utc_slice_to_tai(input: &[OffsetDateTime], out: &mut Vec<TaiDateTime>) {
+    out.reserve(input.len());
+    let since_epoch = input.iter().map(|&time| time - OffsetDateTime::UNIX_EPOCH);
+
+    if input.windows(2).all(|pair| pair[0] <= pair[1]) {
+        let table = leap_table();
+        let mut index = 0;
+        out.extend(since_epoch.map(|unix_time_stamp| {
+            index = seek_utc(&table, index, floor_seconds(unix_time_stamp));
+            TaiDateTime(unix_time_stamp + Duration::new(diff_before(&table, index), 0))
+        }));
+    } else {
+        out.extend(since_epoch.map(|unix_time_stamp| {
+            let diff = leap_seconds_at_utc(floor_seconds(unix_time_stamp));
+            TaiDateTime(unix_time_stamp + Duration::new(diff, 0))
+        }));
+    }
+}
+
+/// Lazily converts UTC date times to TAI as they are pulled from the iterator,
+/// without allocating. Each element ends up the same as with the [`From`]
+/// conversion.
+///
+/// The position in the leap second table is kept from one element to the next
+/// and only moved as far as needed, so for ascending input the whole
+/// conversion takes linear time. Input in any other order is converted
+/// correctly as well, it's just that the position may have to move further.
+pub fn tai_conversions<I: Iterator<Item = OffsetDateTime>>(
+    iter: I,
+) -> impl Iterator<Item = TaiDateTime> {
+    let table = leap_table();
+    let mut index = 0;
+    iter.map(move |time| {
+        let unix_time_stamp = time - OffsetDateTime::UNIX_EPOCH;
+        index = seek_utc(&table, index, floor_seconds(unix_time_stamp));
+        TaiDateTime(unix_time_stamp + Duration::new(diff_before(&table, index), 0))
+    })
+}
+
+/// The UTC buckets of [`utc_bucket_indices`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UtcBucket {
+    /// Days, like [`TaiDateTime::utc_day_index`].
+    Day,
+    /// Hours, like [`TaiDateTime::utc_hour_index`].
+    Hour,
+    /// Minutes, like [`TaiDateTime::utc_minute_index`].
+    Minute,
+}
+
+impl UtcBucket {
+    // The number of UTC seconds in a bucket, not counting leap seconds.
+    pub(crate) const fn seconds(self) -> i64 {
+        match self {
+            Self::Day => 86_400,
+            Self::Hour => 3_600,
+            Self::Minute => 60,
+        }
+    }
+}
+
+/// Computes the UTC bucket of each instant, like
+/// [`TaiDateTime::utc_day_index`], appending the results to `out`. The
+/// position in the leap second table is kept from one element to the next, so
+/// for sorted input the whole computation takes linear time.
+#[cfg(feature = "alloc")]
+pub fn utc_bucket_indices(input: &[TaiDateTime], bucket: UtcBucket, out: &mut Vec<i64>) {
+    let seconds = bucket.seconds();
+    let mut hint = usize::MAX;
+    out.extend(
+        input
+            .iter()
+            .map(|time| time.utc_label_seconds(&mut hint).div_euclid(seconds)),
+    );
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::LEAP_SECONDS;
+
+    // UTC date times between 1960 and 2030, a lot of them close to leap
+    // seconds.
+    fn random_utc(rng: &mut fastrand::Rng) -> OffsetDateTime {
+        let unix_time_stamp = if rng.bool() {
+            let (leap, _) = LEAP_SECONDS[rng.usize(..LEAP_SECONDS.len())];
+            Duration::seconds(leap) + Duration::nanoseconds(rng.i64(-2_000_000_000..2_000_000_000))
+        } else {
+            Duration::new(
+                rng.i64(-315_619_200..1_893_456_000),
+                rng.i32(0..1_000_000_000),
+            )
+        };
+        OffsetDateTime::UNIX_EPOCH + unix_time_stamp
+    }
+
+    #[test]
+    fn utc_slice_matches_from() {
+        let mut rng = fastrand::Rng::with_seed(0x5117);
+        let mut input: Vec<_> = (0..2_000).map(|_| random_utc(&mut rng)).collect();
+        for sorted in [false, true] {
+            if sorted {
+                input.sort();
+            } else {
+                rng.shuffle(&mut input);
+            }
+            // The results get appended.
+            let first = TaiDateTime::MIN;
+            let mut out = alloc::vec![first];
+            utc_slice_to_tai(&input, &mut out);
+            assert_eq!(out[0], first);
+            assert_eq!(out.len(), input.len() + 1);
+            for (&utc, &tai) in input.iter().zip(&out[1..]) {
+                assert_eq!(tai, TaiDateTime::from(utc), "{utc}");
+            }
+        }
+
+        let mut out = Vec::new();
+        utc_slice_to_tai(&[], &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn conversions_match_from() {
+        let mut rng = fastrand::Rng::with_seed(0xa5c);
+        let mut ascending: Vec<_> = (0..2_000).map(|_| random_utc(&mut rng)).collect();
+        ascending.sort();
+        let descending = ascending.iter().rev().copied().collect();
+        // Back and forth, so the position in the table keeps moving both ways.
+        let zigzag = ascending
+            .iter()
+            .zip(ascending.iter().rev())
+            .flat_map(|(&a, &b)| [a, b])
+            .collect();
+        let mut shuffled = ascending.clone();
+        rng.shuffle(&mut shuffled);
+
+        for input in [ascending, descending, zigzag, shuffled] {
+            let mut count = 0;
+            for (tai, &utc) in tai_conversions(input.iter().copied()).zip(&input) {
+                assert_eq!(tai, TaiDateTime::from(utc), "{utc}");
+                count += 1;
+            }
+            assert_eq!(count, input.len());
+        }
+    }
+
+    #[test]
+    fn bucket_indices_match_the_calendar() {
+        let mut rng = fastrand::Rng::with_seed(0xb0c);
+        let mut input: Vec<_> = (0..2_000)
+            .map(|_| TaiDateTime::from(random_utc(&mut rng)))
+            .collect();
+        // Inside of and right around the last leap second.
+        let midnight = TaiDateTime::from(time::macros::datetime!(2017-01-01 0:00 UTC));
+        input.extend([-1001, -1000, -500, -1, 0].map(|ms| midnight + Duration::milliseconds(ms)));
+
+        for sorted in [false, true] {
+            if sorted {
+                input.sort();
+            }
+            for bucket in [UtcBucket::Day, UtcBucket::Hour, UtcBucket::Minute] {
+                let mut out = Vec::new();
+                utc_bucket_indices(&input, bucket, &mut out);
+                assert_eq!(out.len(), input.len());
+                for (&time, &index) in input.iter().zip(&out) {
+                    // The leap second is labeled 23:59:59, so it belongs to the
+                    // day it ends.
+                    let (date, time_of_day) = time.to_utc_date_time();
+                    let day = i64::from(date.to_julian_day() - 2_440_588);
+                    let hour = day * 24 + i64::from(time_of_day.hour());
+                    let minute = hour * 60 + i64::from(time_of_day.minute());
+                    let (expected, single) = match bucket {
+                        UtcBucket::Day => (day, time.utc_day_index()),
+                        UtcBucket::Hour => (hour, time.utc_hour_index()),
+                        UtcBucket::Minute => (minute, time.utc_minute_index()),
+                    };
+                    assert_eq!(index, expected, "{time:?} {bucket:?}");
+                    assert_eq!(single, expected, "{time:?} {bucket:?}");
+                }
+            }
+        }
+
+        let day = (midnight - Duration::milliseconds(500)).utc_day_index();
+        assert_eq!(
+            TaiDateTime::from_utc_day_index(day),
+            TaiDateTime::from(time::macros::datetime!(2016-12-31 0:00 UTC)),
+        );
+        assert_eq!(TaiDateTime::from_utc_day_index(day + 1), midnight);
+    }
+}
@@ -0,0 +1,40 @@
+//! Conversions between days since 1970-01-01 and dates in the proleptic
+//! Gregorian calendar that only need `core` arithmetic, so they work without
+//! going through `time` and in `const` contexts.
+//!
+//! <https://howardhinnant.github.io/date_algorithms.html>
+
+pub(crate) const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+// The number of days since 1970-01-01 of the given date in the proleptic
+// Gregorian calendar.
+#[cfg(feature = "dense-table")]
+pub(crate) const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// The year, month and day of the given number of days since 1970-01-01 in the
+// proleptic Gregorian calendar.
+pub(crate) const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,132 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    thread::JoinHandle,
+};
+
+use time::Duration;
+
+use crate::TaiDateTime;
+
+// The time is stored as nanoseconds since 1970-01-01 00:00:00 TAI, which lasts
+// until the year 2554. Earlier times are stored as 0.
+fn pack(time: TaiDateTime) -> u64 {
+    let nanos = time.0.whole_nanoseconds();
+    nanos.clamp(0, u64::MAX as i128) as u64
+}
+
+fn unpack(nanos: u64) -> TaiDateTime {
+    TaiDateTime(Duration::new(
+        (nanos / 1_000_000_000) as i64,
+        (nanos % 1_000_000_000) as i32,
+    ))
+}
+
+/// A clock that gets updated by a background thread, so reading it is just an
+/// atomic load. This is meant for hot paths that timestamp millions of events
+/// per second, where even [`TaiDateTime::now_coarse`] is too slow.
+///
+/// The time read is at most the update interval, plus however long the
+/// operating system delays the background thread, behind
+/// [`TaiDateTime::now`]. Reads never go backwards, even if the system clock
+/// does or the background thread misses updates. The thread stops when the
+/// clock gets dropped.
+pub struct CachedTaiClock {
+    time: Arc<AtomicU64>,
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CachedTaiClock {
+    /// Starts a thread updating the clock every `update_interval`. A
+    /// non-positive interval updates it as often as possible.
+    pub fn start(update_interval: Duration) -> Self {
+        let time = Arc::new(AtomicU64::new(pack(TaiDateTime::now())));
+        let (stop, stopped) = mpsc::channel::<()>();
+        let update_interval = update_interval.clamp(Duration::ZERO, Duration::MAX);
+
+        let thread = std::thread::spawn({
+            let time = time.clone();
+            move || loop {
+                // `fetch_max` keeps the clock from going backwards.
+                time.fetch_max(pack(TaiDateTime::now()), Ordering::Relaxed);
+                match stopped.recv_timeout(update_interval.unsigned_abs()) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Ok(()) | Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+
+        Self {
+            time,
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns the time of the last update.
+    pub fn now(&self) -> TaiDateTime {
+        unpack(self.time.load(Ordering::Relaxed))
+    }
+
+    /// Stops the background thread. This is the same as dropping the clock.
+    pub fn stop(self) {}
+}
+
+impl Drop for CachedTaiClock {
+    fn drop(&mut self) {
+        // Disconnecting the channel wakes up the thread.
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing() {
+        for time in [
+            TaiDateTime(Duration::ZERO),
+            TaiDateTime(Duration::new(1_483_228_836, 123_456_789)),
+            unpack(u64::MAX),
+        ] {
+            assert_eq!(unpack(pack(time)), time);
+        }
+        assert_eq!(pack(TaiDateTime(-Duration::NANOSECOND)), 0);
+        assert_eq!(pack(TaiDateTime::MIN), 0);
+        assert_eq!(pack(TaiDateTime::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn monotonic_under_concurrent_readers() {
+        let clock = CachedTaiClock::start(Duration::ZERO);
+        let start = clock.now();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let mut last = clock.now();
+                    for _ in 0..100_000 {
+                        let now = clock.now();
+                        assert!(now >= last, "{now:?} < {last:?}");
+                        last = now;
+                    }
+                });
+            }
+        });
+
+        // The background thread keeps updating the clock.
+        let mut now = clock.now();
+        while now == start {
+            std::thread::yield_now();
+            now = clock.now();
+        }
+        assert!(now > start);
+        clock.stop();
+    }
+}
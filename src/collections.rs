@@ -0,0 +1,200 @@
+//! Helpers for slices of records that are sorted by a [`TaiDateTime`] key, so
+//! they don't have to be searched with hand written closures.
+//!
+//! The key is extracted from each record with a function, so the records don't
+//! need to store a [`TaiDateTime`] themselves, but may e.g. convert one of
+//! their fields on the fly.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ops::Range;
+
+#[cfg(feature = "alloc")]
+use time::Duration;
+
+use crate::TaiDateTime;
+
+/// Returns whether the records are sorted by their key in ascending order.
+/// Records with equal keys may appear in any order. Empty slices and slices
+/// with a single record are sorted.
+pub fn is_sorted_by_time<T>(slice: &[T], key: impl Fn(&T) -> TaiDateTime) -> bool {
+    slice.windows(2).all(|pair| key(&pair[0]) <= key(&pair[1]))
+}
+
+/// Returns the number of records whose key is before `at`, i.e. the index of
+/// the first record at or after it, with a binary search. The records have to
+/// be [sorted](is_sorted_by_time) by their key, which is checked in debug
+/// builds. Otherwise the result is unspecified.
+pub fn partition_by_time<T>(
+    slice: &[T],
+    key: impl Fn(&T) -> TaiDateTime,
+    at: TaiDateTime,
+) -> usize {
+    debug_assert!(
+        is_sorted_by_time(slice, &key),
+        "the records are not sorted by time"
+    );
+    slice.partition_point(|record| key(record) < at)
+}
+
+/// Returns the records whose key is within the range, i.e. at or after its
+/// start and before its end, with a binary search. The range is empty if its
+/// end isn't after its start. The records have to be
+/// [sorted](is_sorted_by_time) by their key, which is checked in debug builds.
+/// Otherwise the result is unspecified.
+pub fn range_by_time<T>(
+    slice: &[T],
+    key: impl Fn(&T) -> TaiDateTime,
+    range: Range<TaiDateTime>,
+) -> &[T] {
+    let start = partition_by_time(slice, &key, range.start);
+    let end = start + slice[start..].partition_point(|record| key(record) < range.end);
+    &slice[start..end]
+}
+
+/// Removes the records whose key is within `tolerance` of the key of the last
+/// record that was kept, so of each cluster of records close to each other in
+/// time, only the first one is kept. The differences are compared inclusively,
+/// so with a tolerance of zero, only records with equal keys get removed. The
+/// records don't have to be sorted, but only neighboring ones are compared.
+#[cfg(feature = "alloc")]
+pub fn dedup_within<T>(vec: &mut Vec<T>, key: impl Fn(&T) -> TaiDateTime, tolerance: Duration) {
+    vec.dedup_by(|record, kept| key(record).abs_diff(key(kept)) <= tolerance);
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+
+    // Records of a key in seconds and their position, so reordering them can
+    // be told apart.
+    type Record = (i64, usize);
+
+    fn key(record: &Record) -> TaiDateTime {
+        TaiDateTime(time::Duration::seconds(record.0))
+    }
+
+    fn at(secs: i64) -> TaiDateTime {
+        TaiDateTime(time::Duration::seconds(secs))
+    }
+
+    // Fills the buffer with sorted records, whose keys are drawn from a small
+    // range so there are plenty of equal ones.
+    fn sorted_records<'a>(
+        rng: &mut fastrand::Rng,
+        buffer: &'a mut [Record; 32],
+        keys: i64,
+    ) -> &'a [Record] {
+        let len = rng.usize(..=buffer.len());
+        let mut keys: [i64; 32] = core::array::from_fn(|_| rng.i64(0..keys));
+        keys[..len].sort_unstable();
+        for (index, record) in buffer[..len].iter_mut().enumerate() {
+            *record = (keys[index], index);
+        }
+        &buffer[..len]
+    }
+
+    #[test]
+    fn sorted() {
+        assert!(is_sorted_by_time(&[] as &[Record], key));
+        assert!(is_sorted_by_time(&[(5, 0)], key));
+        assert!(is_sorted_by_time(&[(5, 0), (5, 1), (5, 2)], key));
+        assert!(is_sorted_by_time(&[(5, 1), (5, 0), (6, 0)], key));
+        assert!(!is_sorted_by_time(&[(5, 0), (6, 0), (5, 1)], key));
+    }
+
+    #[test]
+    fn partition_matches_linear_search() {
+        let mut rng = fastrand::Rng::with_seed(347);
+        let mut buffer = [(0, 0); 32];
+        for _ in 0..1000 {
+            let keys = rng.i64(1..40);
+            let slice = sorted_records(&mut rng, &mut buffer, keys);
+            for secs in -1..=keys {
+                let expected = slice.iter().filter(|r| key(r) < at(secs)).count();
+                assert_eq!(partition_by_time(slice, key, at(secs)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn range_matches_linear_search() {
+        let mut rng = fastrand::Rng::with_seed(347);
+        let mut buffer = [(0, 0); 32];
+        for _ in 0..1000 {
+            let keys = rng.i64(1..40);
+            let slice = sorted_records(&mut rng, &mut buffer, keys);
+            let (start, end) = (rng.i64(-1..=keys), rng.i64(-1..=keys));
+            let range = range_by_time(slice, key, at(start)..at(end));
+            let mut expected = slice
+                .iter()
+                .filter(|r| at(start) <= key(r) && key(r) < at(end));
+            assert!(range.iter().eq(&mut expected));
+            if end <= start {
+                assert!(range.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn range_edge_cases() {
+        let empty: &[Record] = &[];
+        assert_eq!(partition_by_time(empty, key, at(0)), 0);
+        assert_eq!(range_by_time(empty, key, at(0)..at(1)), empty);
+
+        let equal = [(5, 0), (5, 1), (5, 2)];
+        assert_eq!(partition_by_time(&equal, key, at(5)), 0);
+        assert_eq!(partition_by_time(&equal, key, at(6)), 3);
+        assert_eq!(range_by_time(&equal, key, at(5)..at(6)), equal);
+        assert_eq!(range_by_time(&equal, key, at(5)..at(5)), empty);
+        assert_eq!(range_by_time(&equal, key, at(4)..at(5)), empty);
+
+        // Inverted ranges are empty, even if records lie between their ends.
+        let slice = [(1, 0), (2, 1), (3, 2), (4, 3)];
+        assert_eq!(range_by_time(&slice, key, at(4)..at(1)), empty);
+        assert_eq!(range_by_time(&slice, key, at(9)..at(-9)), empty);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn dedup_matches_linear_scan() {
+        let mut rng = fastrand::Rng::with_seed(347);
+        for _ in 0..1000 {
+            // The records don't have to be sorted for this.
+            let records: Vec<Record> = (0..rng.usize(..32))
+                .map(|index| (rng.i64(0..20), index))
+                .collect();
+            let tolerance = rng.i64(0..4);
+            let mut expected: Vec<Record> = Vec::new();
+            for &record in &records {
+                match expected.last() {
+                    Some(&(kept, _)) if (record.0 - kept).abs() <= tolerance => {}
+                    _ => expected.push(record),
+                }
+            }
+            let mut deduped = records.clone();
+            dedup_within(&mut deduped, key, Duration::seconds(tolerance));
+            assert_eq!(deduped, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn dedup_edge_cases() {
+        let mut empty: Vec<Record> = Vec::new();
+        dedup_within(&mut empty, key, Duration::ZERO);
+        assert!(empty.is_empty());
+
+        let mut equal = vec![(5, 0), (5, 1), (5, 2)];
+        dedup_within(&mut equal, key, Duration::ZERO);
+        assert_eq!(equal, [(5, 0)]);
+
+        // Each record is compared with the last one kept, not its neighbor.
+        let mut chain = vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)];
+        dedup_within(&mut chain, key, Duration::seconds(1));
+        assert_eq!(chain, [(0, 0), (2, 2), (4, 4)]);
+    }
+}
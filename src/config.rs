@@ -0,0 +1,276 @@
+use core::fmt;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    floor_seconds, leap_seconds, leap_seconds_at_tai, leap_table_expiry, ConversionError,
+    TaiDateTime, UTC_ERA_START,
+};
+
+/// How to treat UTC instants before 1972-01-01 00:00:00 UTC, from which on TAI
+/// and UTC differ by a whole number of seconds.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Era {
+    /// The initial difference of 10 seconds also applies to earlier instants,
+    /// just like with the [`From`] conversions.
+    #[default]
+    Extend,
+    /// Earlier instants are rejected with [`ConversionError::BeforeTaiEpoch`].
+    Reject,
+}
+
+/// The policy for converting between UTC and TAI with
+/// [`TaiDateTime::from_utc_with`] and [`TaiDateTime::to_utc_with`].
+///
+/// The [default](Default) matches the [`From`] conversions, which always use
+/// it, regardless of the [global configuration](Self::global). A configuration
+/// is created with a [builder](Self::builder).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Config {
+    strict_expiry: bool,
+    pre_1972: Era,
+}
+
+impl Config {
+    /// Returns a builder that starts out with the default configuration.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder {
+            config: Self::default(),
+        }
+    }
+
+    /// Whether instants at or after the [expiry](leap_seconds::expires_at) of
+    /// the leap second table are rejected.
+    pub fn strict_expiry(&self) -> bool {
+        self.strict_expiry
+    }
+
+    /// How instants before 1972 are treated.
+    pub fn pre_1972(&self) -> Era {
+        self.pre_1972
+    }
+
+    // Checks whether the UTC date time may be converted.
+    fn check(&self, time: OffsetDateTime) -> Result<(), ConversionError> {
+        let unix_time_stamp = time.unix_timestamp();
+        if self.pre_1972 == Era::Reject && unix_time_stamp < UTC_ERA_START {
+            return Err(ConversionError::BeforeTaiEpoch);
+        }
+        if self.strict_expiry && unix_time_stamp >= leap_table_expiry() {
+            return Err(ConversionError::BeyondTableExpiry {
+                expiry: leap_seconds::expires_at(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A builder for a [`Config`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Sets whether instants at or after the
+    /// [expiry](leap_seconds::expires_at) of the leap second table are rejected
+    /// with [`ConversionError::BeyondTableExpiry`], as a leap second may have
+    /// been announced since. The default is `false`, i.e. they get converted
+    /// with the last known difference between TAI and UTC.
+    pub fn strict_expiry(mut self, strict_expiry: bool) -> Self {
+        self.config.strict_expiry = strict_expiry;
+        self
+    }
+
+    /// Sets how instants before 1972 are treated. The default is
+    /// [`Era::Extend`].
+    pub fn pre_1972(mut self, era: Era) -> Self {
+        self.config.pre_1972 = era;
+        self
+    }
+
+    /// Creates the configuration.
+    pub fn build(self) -> Config {
+        self.config
+    }
+}
+
+/// The reason the global configuration could not be set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The global configuration was already set or already used, at which
+    /// point the default configuration got locked in.
+    AlreadyInitialized,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyInitialized => {
+                f.write_str("the global configuration was already set or used")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+static GLOBAL_CONFIG: once_cell::sync::OnceCell<Config> = once_cell::sync::OnceCell::new();
+
+/// Sets the configuration returned by [`Config::global`]. This can only happen
+/// once and only before the global configuration is first used, so every part
+/// of the process sees the same one. Otherwise this fails with
+/// [`ConfigError::AlreadyInitialized`] and the configuration stays unchanged.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub fn set_global_config(config: Config) -> Result<(), ConfigError> {
+    GLOBAL_CONFIG
+        .set(config)
+        .map_err(|_| ConfigError::AlreadyInitialized)
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+impl Config {
+    /// Returns the process wide configuration set with [`set_global_config`],
+    /// or the default one if none was set. Once this got called, the
+    /// configuration can't be set anymore. Reading it is lock-free.
+    pub fn global() -> &'static Config {
+        GLOBAL_CONFIG.get_or_init(Config::default)
+    }
+}
+
+impl TaiDateTime {
+    /// Converts a UTC date time to TAI, failing if the configuration rejects
+    /// it. With the default configuration this never fails and is the same as
+    /// the [`From`] conversion. Pass [`Config::global`] to use the process
+    /// wide configuration.
+    pub fn from_utc_with(time: OffsetDateTime, config: &Config) -> Result<Self, ConversionError> {
+        config.check(time)?;
+        Ok(time.into())
+    }
+
+    /// Converts to UTC, failing if the configuration rejects the result. An
+    /// instant inside of an inserted leap second is converted to the same time
+    /// of day in the second after it, just like with the [`From`] conversion.
+    /// Unlike that, this never panics, but fails with
+    /// [`ConversionError::TargetOutOfRange`] for instants outside of the range
+    /// of [`OffsetDateTime`]. Pass [`Config::global`] to use the process wide
+    /// configuration.
+    pub fn to_utc_with(self, config: &Config) -> Result<OffsetDateTime, ConversionError> {
+        let diff = leap_seconds_at_tai(floor_seconds(self.0));
+        let utc = self
+            .0
+            .checked_sub(Duration::new(diff, 0))
+            .and_then(|unix_time_stamp| OffsetDateTime::UNIX_EPOCH.checked_add(unix_time_stamp))
+            .ok_or(ConversionError::TargetOutOfRange)?;
+        config.check(utc)?;
+        Ok(utc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn builder() {
+        let config = Config::builder().build();
+        assert_eq!(config, Config::default());
+        assert!(!config.strict_expiry());
+        assert_eq!(config.pre_1972(), Era::Extend);
+
+        let config = Config::builder()
+            .strict_expiry(true)
+            .pre_1972(Era::Reject)
+            .build();
+        assert!(config.strict_expiry());
+        assert_eq!(config.pre_1972(), Era::Reject);
+
+        // Later calls override earlier ones.
+        let config = Config::builder()
+            .strict_expiry(true)
+            .pre_1972(Era::Reject)
+            .strict_expiry(false)
+            .pre_1972(Era::Extend)
+            .build();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn default_matches_from() {
+        let config = Config::default();
+        for utc in [
+            datetime!(1960-01-01 0:00 UTC),
+            datetime!(2016-12-31 23:59:59.5 UTC),
+            datetime!(2100-01-01 0:00 UTC),
+        ] {
+            let tai = TaiDateTime::from(utc);
+            assert_eq!(TaiDateTime::from_utc_with(utc, &config), Ok(tai));
+            assert_eq!(tai.to_utc_with(&config), Ok(utc));
+        }
+        let leap_second = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC)) - Duration::SECOND;
+        assert_eq!(
+            leap_second.to_utc_with(&config),
+            Ok(datetime!(2017-01-01 0:00 UTC)),
+        );
+        assert_eq!(
+            TaiDateTime::MAX.to_utc_with(&config),
+            Err(ConversionError::TargetOutOfRange),
+        );
+    }
+
+    #[test]
+    fn reject_pre_1972() {
+        let config = Config::builder().pre_1972(Era::Reject).build();
+        let start = datetime!(1972-01-01 0:00 UTC);
+        let before = start - Duration::NANOSECOND;
+        assert_eq!(TaiDateTime::from_utc_with(start, &config), Ok(start.into()),);
+        assert_eq!(
+            TaiDateTime::from_utc_with(before, &config),
+            Err(ConversionError::BeforeTaiEpoch),
+        );
+        assert_eq!(
+            TaiDateTime::from(before).to_utc_with(&config),
+            Err(ConversionError::BeforeTaiEpoch),
+        );
+        assert_eq!(TaiDateTime::from(start).to_utc_with(&config), Ok(start),);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn strict_expiry() {
+        let _lock = crate::tests::lock_global_table();
+        let config = Config::builder().strict_expiry(true).build();
+        let expiry = leap_seconds::expires_at();
+        let before = expiry - Duration::NANOSECOND;
+        assert_eq!(
+            TaiDateTime::from_utc_with(before, &config),
+            Ok(before.into()),
+        );
+        assert_eq!(TaiDateTime::from(before).to_utc_with(&config), Ok(before));
+        for utc in [expiry, expiry + Duration::DAY] {
+            let error = ConversionError::BeyondTableExpiry { expiry };
+            assert_eq!(TaiDateTime::from_utc_with(utc, &config), Err(error));
+            assert_eq!(TaiDateTime::from(utc).to_utc_with(&config), Err(error));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn global_after_use() {
+        // Nothing else in the tests sets the global configuration, so using it
+        // locks in the default one.
+        assert_eq!(*Config::global(), Config::default());
+        let config = Config::builder().strict_expiry(true).build();
+        assert_eq!(
+            set_global_config(config),
+            Err(ConfigError::AlreadyInitialized),
+        );
+        assert_eq!(*Config::global(), Config::default());
+    }
+}
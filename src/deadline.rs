@@ -0,0 +1,192 @@
+//! Deadlines given as [`TaiDateTime`], translated to the monotonic [`Instant`]
+//! that timers and async runtimes expect.
+//!
+//! The translation relies on a calibration, i.e. a pair of readings of
+//! [`TaiDateTime::now`] and [`Instant::now`] taken at the same moment. It's
+//! taken the first time it's needed and taken again once it's older than the
+//! [maximum age](set_max_calibration_age), or whenever [`recalibrate`] is
+//! called. A translated deadline is only as good as the calibration at the
+//! moment it got translated: if the system's clock is stepped afterwards, the
+//! [`Instant`] doesn't move along with it, so the deadline fires early or late
+//! by the size of the step.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration as StdDuration, Instant},
+};
+
+use crate::TaiDateTime;
+
+// Calibrations older than this many nanoseconds get taken again.
+static MAX_CALIBRATION_AGE: AtomicU64 = AtomicU64::new(60_000_000_000);
+
+static CALIBRATION: Mutex<Option<(TaiDateTime, Instant)>> = Mutex::new(None);
+
+fn take_calibration() -> (TaiDateTime, Instant) {
+    (TaiDateTime::now(), Instant::now())
+}
+
+// Returns the calibration, taking it again if it's stale. With a mock clock
+// installed, which only applies to the current thread, a fresh one is taken
+// every time instead, so it doesn't leak into other threads.
+fn calibration() -> (TaiDateTime, Instant) {
+    #[cfg(feature = "test-util")]
+    if crate::mock::now().is_some() {
+        return take_calibration();
+    }
+    let max_age = StdDuration::from_nanos(MAX_CALIBRATION_AGE.load(Ordering::Relaxed));
+    let mut calibration = CALIBRATION.lock().unwrap_or_else(|e| e.into_inner());
+    match *calibration {
+        Some(taken) if taken.1.elapsed() <= max_age => taken,
+        _ => *calibration.insert(take_calibration()),
+    }
+}
+
+/// Takes the calibration again right away, e.g. after the system's clock got
+/// stepped. Deadlines that were already translated aren't affected.
+pub fn recalibrate() {
+    let taken = take_calibration();
+    *CALIBRATION.lock().unwrap_or_else(|e| e.into_inner()) = Some(taken);
+}
+
+/// Sets how old the calibration may get before it's taken again. The default
+/// is one minute. Durations of more than about 584 years are capped.
+pub fn set_max_calibration_age(max_age: StdDuration) {
+    let nanos = max_age.as_nanos().min(u64::MAX as u128) as u64;
+    MAX_CALIBRATION_AGE.store(nanos, Ordering::Relaxed);
+}
+
+// Returns how long it is until the deadline, as of the returned instant, or
+// `None` if the deadline has passed.
+fn time_until(deadline: TaiDateTime) -> Option<(StdDuration, Instant)> {
+    let (tai, instant) = calibration();
+    let now = Instant::now();
+    let tai_now = tai.checked_add_std(now.saturating_duration_since(instant))?;
+    Some((deadline.duration_since(tai_now).ok()?, now))
+}
+
+impl TaiDateTime {
+    /// Translates the deadline to an [`Instant`], e.g. for `async-std` or
+    /// `smol` timers. Returns `None` if the deadline has passed or is too far
+    /// in the future to be represented. See the [module](self) documentation
+    /// for how accurate this is.
+    pub fn to_instant(&self) -> Option<Instant> {
+        let (until, now) = time_until(*self)?;
+        now.checked_add(until)
+    }
+
+    /// Translates the deadline to a [`tokio::time::Instant`]. Returns `None`
+    /// if the deadline has passed or is too far in the future to be
+    /// represented. The result is relative to Tokio's clock, so it works with
+    /// a paused clock as well. See the [module](self) documentation for how
+    /// accurate this is. Available with the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub fn to_tokio_instant(&self) -> Option<tokio::time::Instant> {
+        let (until, _) = time_until(*self)?;
+        tokio::time::Instant::now().checked_add(until)
+    }
+}
+
+/// Requires the future to complete before the deadline, like
+/// [`tokio::time::timeout_at`]. A deadline that has passed times out right
+/// away, unless the future is ready on the first poll. A deadline too far in
+/// the future to be represented never times out. Available with the `tokio`
+/// feature.
+#[cfg(feature = "tokio")]
+pub fn timeout_at_tai<F: core::future::Future>(
+    deadline: TaiDateTime,
+    future: F,
+) -> tokio::time::Timeout<F> {
+    let deadline = match time_until(deadline) {
+        Some((until, _)) => tokio::time::Instant::now()
+            .checked_add(until)
+            // Roughly 30 years, like Tokio uses for sleeping "forever".
+            .unwrap_or_else(|| {
+                tokio::time::Instant::now() + StdDuration::from_secs(86_400 * 365 * 30)
+            }),
+        None => tokio::time::Instant::now(),
+    };
+    tokio::time::timeout_at(deadline, future)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // How far a translated deadline may be off, as the calibration and the
+    // translation don't happen at the exact same moment.
+    const TOLERANCE: StdDuration = StdDuration::from_millis(100);
+
+    fn assert_close(actual: StdDuration, expected: StdDuration) {
+        assert!(
+            actual.abs_diff(expected) <= TOLERANCE,
+            "{actual:?} is not close to {expected:?}",
+        );
+    }
+
+    #[test]
+    fn to_instant() {
+        let deadline = TaiDateTime::now() + StdDuration::from_secs(10);
+        let instant = deadline.to_instant().unwrap();
+        assert_close(instant - Instant::now(), StdDuration::from_secs(10));
+
+        assert_eq!(
+            (TaiDateTime::now() - StdDuration::from_secs(1)).to_instant(),
+            None,
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn timeout_fires_at_deadline() {
+        let start = tokio::time::Instant::now();
+        let deadline = TaiDateTime::now() + StdDuration::from_secs(10);
+        let translated = deadline.to_tokio_instant().unwrap();
+        assert_close(translated - start, StdDuration::from_secs(10));
+
+        let timeout = timeout_at_tai(deadline, core::future::pending::<()>());
+        assert!(timeout.await.is_err());
+        assert_close(start.elapsed(), StdDuration::from_secs(10));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn future_completes_before_deadline() {
+        let start = tokio::time::Instant::now();
+        let deadline = TaiDateTime::now() + StdDuration::from_secs(10);
+        let future = async {
+            tokio::time::sleep(StdDuration::from_secs(5)).await;
+            5
+        };
+        assert_eq!(timeout_at_tai(deadline, future).await, Ok(5));
+        assert_eq!(start.elapsed(), StdDuration::from_secs(5));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn passed_deadline() {
+        let start = tokio::time::Instant::now();
+        let deadline = TaiDateTime::now() - StdDuration::from_secs(1);
+        assert_eq!(deadline.to_tokio_instant(), None);
+
+        // It times out right away, unless the future is ready on the first
+        // poll.
+        assert_eq!(timeout_at_tai(deadline, async { 5 }).await, Ok(5));
+        let timeout = timeout_at_tai(deadline, core::future::pending::<()>());
+        assert!(timeout.await.is_err());
+        assert_eq!(start.elapsed(), StdDuration::ZERO);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn distant_deadline() {
+        // Whether or not the deadline can be represented as an instant, it
+        // doesn't time out for decades.
+        let timeout = timeout_at_tai(TaiDateTime::MAX, core::future::pending::<()>());
+        let year = StdDuration::from_secs(86_400 * 365);
+        assert!(tokio::time::timeout(10 * year, timeout).await.is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! The built-in leap second table expanded into the difference between TAI and
+//! UTC for every month of a bounded range of years, so a lookup is a single
+//! index computation rather than a search.
+//!
+//! Leap seconds only ever take effect at the start of a month, so the
+//! difference is the same for every instant of a month and the expanded table
+//! gives exactly the same results as the sparse one.
+
+use crate::{
+    civil::{civil_from_days, days_from_civil, SECONDS_PER_DAY},
+    leap_table, FIRST_LEAP_SECONDS_DIFF, LEAP_SECONDS, UTC_ERA_START,
+};
+
+// The first year covered by the expanded table.
+const FIRST_YEAR: i64 = 1972;
+
+// The number of years covered by the expanded table.
+const YEARS: usize = 128;
+
+const fn expand() -> [u8; YEARS * 12] {
+    // The expanded table relies on every leap second taking effect at the
+    // start of a month.
+    let mut index = 0;
+    while index < LEAP_SECONDS.len() {
+        let time_stamp = LEAP_SECONDS[index].0;
+        let (year, _, day) = civil_from_days(time_stamp.div_euclid(SECONDS_PER_DAY));
+        assert!(time_stamp.rem_euclid(SECONDS_PER_DAY) == 0 && day == 1);
+        assert!(time_stamp >= UTC_ERA_START && year < FIRST_YEAR + YEARS as i64);
+        index += 1;
+    }
+
+    let mut offsets = [0; YEARS * 12];
+    let mut month = 0;
+    let mut diff = FIRST_LEAP_SECONDS_DIFF;
+    let mut next = 0;
+    while month < offsets.len() {
+        let start = days_from_civil(FIRST_YEAR + month as i64 / 12, month as i64 % 12 + 1, 1)
+            * SECONDS_PER_DAY;
+        while next < LEAP_SECONDS.len() && LEAP_SECONDS[next].0 <= start {
+            diff = LEAP_SECONDS[next].1;
+            next += 1;
+        }
+        assert!(diff >= 0 && diff <= u8::MAX as i64);
+        offsets[month] = diff as u8;
+        month += 1;
+    }
+    offsets
+}
+
+// The difference between TAI and UTC in seconds during every month, starting
+// with January of `FIRST_YEAR`.
+static OFFSETS: [u8; YEARS * 12] = expand();
+
+// The difference between TAI and UTC in seconds at the given UTC time stamp,
+// if it's covered by the expanded table. The expanded table is only used as
+// long as the leap second table used for conversions is the built-in one, as
+// it may have been extended at runtime.
+pub(crate) fn leap_seconds_at_utc(unix_time_stamp: i64) -> Option<i64> {
+    // Entries are only ever added to the built-in table, so it's unchanged as
+    // long as it has the same length.
+    if leap_table().len() != LEAP_SECONDS.len() || unix_time_stamp < UTC_ERA_START {
+        return None;
+    }
+    // A mock clock's table may differ from the built-in one despite having the
+    // same length.
+    #[cfg(feature = "test-util")]
+    if crate::mock::leap_table().is_some() {
+        return None;
+    }
+    builtin_leap_seconds_at_utc(unix_time_stamp)
+}
+
+// The difference between TAI and UTC in seconds at the given UTC time stamp
+// according to the built-in table, if it's covered by the expanded table.
+fn builtin_leap_seconds_at_utc(unix_time_stamp: i64) -> Option<i64> {
+    let (year, month, _) = civil_from_days(unix_time_stamp.div_euclid(SECONDS_PER_DAY));
+    let index = usize::try_from((year - FIRST_YEAR) * 12 + month - 1).ok()?;
+    OFFSETS.get(index).map(|&diff| diff as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{diff_before, seek_utc};
+
+    fn sparse(unix_time_stamp: i64) -> i64 {
+        diff_before(
+            LEAP_SECONDS,
+            seek_utc(LEAP_SECONDS, LEAP_SECONDS.len(), unix_time_stamp),
+        )
+    }
+
+    #[test]
+    fn matches_sparse_table() {
+        for &(t, _) in LEAP_SECONDS {
+            for t in [t - 1, t, t + 1] {
+                assert_eq!(builtin_leap_seconds_at_utc(t), Some(sparse(t)), "{t}");
+                // Unless other tests extended the table used for conversions.
+                if let Some(diff) = leap_seconds_at_utc(t) {
+                    assert_eq!(diff, sparse(t), "{t}");
+                }
+            }
+        }
+        for t in [UTC_ERA_START, UTC_ERA_START + 1, crate::EXPIRES_AT_UTC] {
+            assert_eq!(builtin_leap_seconds_at_utc(t), Some(sparse(t)), "{t}");
+        }
+    }
+
+    #[test]
+    fn bounded_range() {
+        let end = days_from_civil(FIRST_YEAR + YEARS as i64, 1, 1) * SECONDS_PER_DAY;
+        assert_eq!(builtin_leap_seconds_at_utc(end - 1), Some(sparse(end - 1)));
+        assert_eq!(builtin_leap_seconds_at_utc(end), None);
+        assert_eq!(leap_seconds_at_utc(UTC_ERA_START - 1), None);
+    }
+}
@@ -0,0 +1,83 @@
+use core::fmt;
+
+use time::Duration;
+
+use crate::{
+    civil::{civil_from_days, SECONDS_PER_DAY},
+    floor_seconds, TaiDateTime,
+};
+
+/// Formats the instant as a date time on the TAI time scale with nanosecond
+/// precision, such as `2017-01-01T00:00:36.250000000 TAI`, which
+/// [`parse_auto`](TaiDateTime::parse_auto) parses again. Years before 0000 or
+/// after 9999 get a sign and as many digits as they need, such as `-0001` or
+/// `+10000`, as in the expanded format of ISO 8601. The calendar fields
+/// are computed with plain integer arithmetic and written directly to the
+/// formatter, so this works without `std` and doesn't allocate.
+impl fmt::Display for TaiDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_date_time(f, self.0, "TAI")
+    }
+}
+
+// Writes a time since 1970-01-01 00:00:00 on a time scale as a date time on that
+// scale with nanosecond precision, followed by the name of the scale.
+pub(crate) fn write_date_time(
+    f: &mut fmt::Formatter<'_>,
+    since_epoch: Duration,
+    scale: &str,
+) -> fmt::Result {
+    let secs = floor_seconds(since_epoch);
+    let nanos = since_epoch.subsec_nanoseconds().rem_euclid(1_000_000_000);
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECONDS_PER_DAY));
+    let second_of_day = secs.rem_euclid(SECONDS_PER_DAY);
+    // Years outside of 0000 to 9999 are written in the expanded format of ISO
+    // 8601, with a sign and at least four digits.
+    if (0..=9999).contains(&year) {
+        write!(f, "{year:04}")?;
+    } else {
+        write!(f, "{year:+05}")?;
+    }
+    write!(
+        f,
+        "-{:02}-{:02}T{:02}:{:02}:{:02}.{:09} {scale}",
+        month,
+        day,
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60,
+        nanos,
+    )
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::ToString;
+
+    use time::{Date, Month};
+
+    use super::*;
+
+    fn check_year(year: i32, text: &str) {
+        let date = Date::from_calendar_date(year, Month::January, 1).unwrap();
+        let tai = TaiDateTime(date.midnight().assume_utc() - time::OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(tai.to_string(), text);
+        assert_eq!(TaiDateTime::parse_auto(text), Ok(tai));
+    }
+
+    #[test]
+    fn years() {
+        check_year(2017, "2017-01-01T00:00:00.000000000 TAI");
+        check_year(0, "0000-01-01T00:00:00.000000000 TAI");
+        check_year(9999, "9999-01-01T00:00:00.000000000 TAI");
+        check_year(-1, "-0001-01-01T00:00:00.000000000 TAI");
+        check_year(-9999, "-9999-01-01T00:00:00.000000000 TAI");
+    }
+
+    #[cfg(feature = "large-dates")]
+    #[test]
+    fn large_years() {
+        check_year(10_000, "+10000-01-01T00:00:00.000000000 TAI");
+        check_year(-10_000, "-10000-01-01T00:00:00.000000000 TAI");
+    }
+}
@@ -0,0 +1,448 @@
+use core::fmt;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    floor_seconds,
+    leap_seconds::{self, LeapEvent, LeapSign},
+    leap_seconds_at_tai, TaiDateTime,
+};
+
+/// The actual elapsed time between two UTC instants, alongside the number of
+/// leap seconds that make it differ from naively subtracting the two.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeapAwareDuration {
+    duration: Duration,
+    leap_seconds: i64,
+}
+
+impl LeapAwareDuration {
+    /// The elapsed time in SI seconds, including the leap seconds. This is
+    /// negative if the end is before the start.
+    pub fn duration(self) -> Duration {
+        self.duration
+    }
+
+    /// The number of leap seconds inserted between the two instants, minus the
+    /// number of removed ones. This doesn't depend on the order of the two
+    /// instants.
+    pub fn leap_seconds(self) -> i64 {
+        self.leap_seconds
+    }
+
+    /// The difference of the two UTC instants, ignoring any leap seconds.
+    pub fn naive_duration(self) -> Duration {
+        let leap_seconds = Duration::seconds(self.leap_seconds);
+        if self.duration.is_negative() {
+            self.duration + leap_seconds
+        } else {
+            self.duration - leap_seconds
+        }
+    }
+}
+
+/// Formats the duration in seconds, e.g. `61s (includes 1 leap second)`.
+impl fmt::Display for LeapAwareDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.duration.as_seconds_f64())?;
+        let (verb, count) = if self.leap_seconds < 0 {
+            ("skips", self.leap_seconds.unsigned_abs())
+        } else {
+            ("includes", self.leap_seconds.unsigned_abs())
+        };
+        match count {
+            0 => Ok(()),
+            1 => write!(f, " ({verb} 1 leap second)"),
+            _ => write!(f, " ({verb} {count} leap seconds)"),
+        }
+    }
+}
+
+/// Returns how much time actually elapsed from `start` to `end`, taking the
+/// leap seconds in between into account.
+///
+/// An [`OffsetDateTime`] can't refer to an instant inside of a leap second, so
+/// neither endpoint is ever inside of one. A UTC time of 23:59:59.5 right
+/// before an inserted leap second is before it, while 00:00:00 of the next day
+/// is after it.
+pub fn tai_duration_between(start: OffsetDateTime, end: OffsetDateTime) -> LeapAwareDuration {
+    let duration = TaiDateTime::from(end) - TaiDateTime::from(start);
+    let leap_seconds = (duration - (end - start)).whole_seconds();
+    LeapAwareDuration {
+        duration,
+        leap_seconds: if end < start {
+            -leap_seconds
+        } else {
+            leap_seconds
+        },
+    }
+}
+
+/// Returns how many SI seconds actually elapsed from `a` to `b`, taking the
+/// leap seconds in between into account. This is negative if `b` is before
+/// `a`. It's the same as [`tai_duration_between`], without the number of leap
+/// seconds.
+pub fn tai_duration_between_utc(a: OffsetDateTime, b: OffsetDateTime) -> Duration {
+    TaiDateTime::from(b) - TaiDateTime::from(a)
+}
+
+/// The time between two instants, explained in terms of the leap seconds in
+/// between, as returned by [`TaiDateTime::diff_explained`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DiffExplain {
+    start: TaiDateTime,
+    end: TaiDateTime,
+}
+
+impl DiffExplain {
+    /// The elapsed time in SI seconds, including the leap seconds. This is
+    /// negative if the end is before the start.
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// The difference of the UTC labels of the two instants, ignoring any leap
+    /// seconds, i.e. what subtracting two [`OffsetDateTime`]s results in.
+    pub fn naive_duration(&self) -> Duration {
+        let (earlier, later) = self.ordered();
+        let leap_seconds = Duration::seconds(
+            leap_seconds_at_tai(floor_seconds(later.0))
+                - leap_seconds_at_tai(floor_seconds(earlier.0)),
+        );
+        if self.end < self.start {
+            self.duration() + leap_seconds
+        } else {
+            self.duration() - leap_seconds
+        }
+    }
+
+    /// The leap events between the two instants, in order, as determined by
+    /// [`events_between`](leap_seconds::events_between). This doesn't depend
+    /// on the order of the two instants.
+    pub fn leap_events(&self) -> impl Iterator<Item = LeapEvent> {
+        let (earlier, later) = self.ordered();
+        leap_seconds::events_between(earlier, later)
+    }
+
+    fn ordered(&self) -> (TaiDateTime, TaiDateTime) {
+        if self.end < self.start {
+            (self.end, self.start)
+        } else {
+            (self.start, self.end)
+        }
+    }
+}
+
+// Formats the UTC label of the leap second itself, e.g. `2016-12-31T23:59:60Z`
+// for an inserted one and the second that got skipped for a removed one.
+fn write_leap_second(f: &mut fmt::Formatter<'_>, event: &LeapEvent) -> fmt::Result {
+    let date = event
+        .utc_instant
+        .date()
+        .previous_day()
+        .unwrap_or(event.utc_instant.date());
+    match event.sign {
+        LeapSign::Positive => write!(f, "{date}T23:59:60Z"),
+        LeapSign::Negative => write!(f, "{date}T23:59:59Z"),
+    }
+}
+
+// Formats the leap seconds with the given sign, e.g. `includes leap seconds at
+// 2015-06-30T23:59:60Z, 2016-12-31T23:59:60Z`, returning whether there were
+// any.
+fn write_leap_seconds(
+    f: &mut fmt::Formatter<'_>,
+    explain: &DiffExplain,
+    sign: LeapSign,
+    verb: &str,
+) -> Result<bool, fmt::Error> {
+    let count = explain
+        .leap_events()
+        .filter(|event| event.sign == sign)
+        .count();
+    if count == 0 {
+        return Ok(false);
+    }
+    let plural = if count == 1 { "" } else { "s" };
+    write!(f, "{verb} leap second{plural} at ")?;
+    for (i, event) in explain
+        .leap_events()
+        .filter(|event| event.sign == sign)
+        .enumerate()
+    {
+        if i != 0 {
+            f.write_str(", ")?;
+        }
+        write_leap_second(f, &event)?;
+    }
+    Ok(true)
+}
+
+/// Formats the duration in seconds, followed by the leap seconds in between,
+/// e.g. `86401s [includes leap second at 2016-12-31T23:59:60Z]`. Without any
+/// leap seconds in between, it's just the duration.
+impl fmt::Display for DiffExplain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.duration().as_seconds_f64())?;
+        if self.leap_events().next().is_none() {
+            return Ok(());
+        }
+        f.write_str(" [")?;
+        if write_leap_seconds(f, self, LeapSign::Positive, "includes")?
+            && self
+                .leap_events()
+                .any(|event| event.sign == LeapSign::Negative)
+        {
+            f.write_str("; ")?;
+        }
+        write_leap_seconds(f, self, LeapSign::Negative, "skips")?;
+        f.write_str("]")
+    }
+}
+
+impl TaiDateTime {
+    /// Returns the time elapsed since `earlier`, alongside the leap seconds in
+    /// between, to explain why it differs from the difference of the UTC
+    /// labels.
+    pub fn diff_explained(self, earlier: Self) -> DiffExplain {
+        DiffExplain {
+            start: earlier,
+            end: self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn si_seconds_between_utc() {
+        let before = datetime!(2016-12-31 23:59:59 UTC);
+        let after = datetime!(2017-01-01 0:00 UTC);
+        assert_eq!(
+            tai_duration_between_utc(before, after),
+            Duration::seconds(2)
+        );
+        assert_eq!(
+            tai_duration_between_utc(after, before),
+            Duration::seconds(-2)
+        );
+        assert_eq!(tai_duration_between_utc(after, after), Duration::ZERO);
+        // 27 leap seconds from 1972 to 2017, none before.
+        assert_eq!(
+            tai_duration_between_utc(datetime!(1972-01-01 0:00 UTC), after),
+            (after - datetime!(1972-01-01 0:00 UTC)) + Duration::seconds(27),
+        );
+        assert_eq!(
+            tai_duration_between_utc(
+                datetime!(1960-01-01 0:00 UTC),
+                datetime!(1972-01-01 0:00 UTC)
+            ),
+            datetime!(1972-01-01 0:00 UTC) - datetime!(1960-01-01 0:00 UTC),
+        );
+    }
+
+    #[test]
+    fn across_leap_seconds() {
+        for (start, end, leap_seconds) in [
+            (
+                datetime!(2012-06-30 23:59:59 UTC),
+                datetime!(2012-07-01 0:00 UTC),
+                1,
+            ),
+            (
+                datetime!(2015-06-30 12:00 UTC),
+                datetime!(2015-07-01 12:00 UTC),
+                1,
+            ),
+            (
+                datetime!(2016-12-31 23:59:59.5 UTC),
+                datetime!(2017-01-01 0:00:00.5 UTC),
+                1,
+            ),
+            (
+                datetime!(2012-01-01 0:00 UTC),
+                datetime!(2017-01-01 0:00 UTC),
+                3,
+            ),
+            (
+                datetime!(1992-01-01 0:00 UTC),
+                datetime!(1994-01-01 0:00 UTC),
+                2,
+            ),
+            (
+                datetime!(1992-01-01 0:00 UTC),
+                datetime!(1994-07-01 0:00 UTC),
+                3,
+            ),
+            // Right before and right after the leap seconds.
+            (
+                datetime!(2012-07-01 0:00 UTC),
+                datetime!(2015-06-30 23:59:59.999999999 UTC),
+                0,
+            ),
+            (
+                datetime!(2017-01-01 0:00 UTC),
+                datetime!(2017-12-31 0:00 UTC),
+                0,
+            ),
+        ] {
+            let naive = end - start;
+            let forward = tai_duration_between(start, end);
+            assert_eq!(forward.leap_seconds(), leap_seconds, "{start} {end}");
+            assert_eq!(forward.duration(), naive + Duration::seconds(leap_seconds));
+            assert_eq!(forward.naive_duration(), naive);
+            assert_eq!(tai_duration_between_utc(start, end), forward.duration());
+
+            let backward = tai_duration_between(end, start);
+            assert_eq!(backward.leap_seconds(), leap_seconds, "{end} {start}");
+            assert_eq!(backward.duration(), -forward.duration());
+            assert_eq!(backward.naive_duration(), -naive);
+            assert_eq!(tai_duration_between_utc(end, start), backward.duration());
+        }
+
+        let same = datetime!(2016-12-31 23:59:59 UTC);
+        let zero = tai_duration_between(same, same);
+        assert_eq!(
+            (zero.duration(), zero.leap_seconds(), zero.naive_duration()),
+            (Duration::ZERO, 0, Duration::ZERO),
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn leap_aware_display() {
+        use alloc::string::ToString;
+
+        let start = datetime!(2016-12-31 23:59 UTC);
+        let end = datetime!(2017-01-01 0:00 UTC);
+        assert_eq!(
+            tai_duration_between(start, end).to_string(),
+            "61s (includes 1 leap second)",
+        );
+        assert_eq!(
+            tai_duration_between(end, start).to_string(),
+            "-61s (includes 1 leap second)",
+        );
+        assert_eq!(
+            tai_duration_between(datetime!(2012-01-01 0:00 UTC), end).to_string(),
+            "157852803s (includes 3 leap seconds)",
+        );
+        assert_eq!(tai_duration_between(end, end).to_string(), "0s");
+    }
+
+    // The UTC instants of the leap events between the instants, in order.
+    fn leap_instants(explain: &DiffExplain) -> impl Iterator<Item = OffsetDateTime> {
+        explain.leap_events().map(|event| event.utc_instant)
+    }
+
+    #[test]
+    fn explain_single_leap_second() {
+        let start = TaiDateTime::from(datetime!(2016-12-31 23:59 UTC));
+        let end = TaiDateTime::from(datetime!(2017-01-01 0:01 UTC));
+        let midnight = [datetime!(2017-01-01 0:00 UTC)];
+
+        let explain = end.diff_explained(start);
+        assert_eq!(explain.duration(), Duration::seconds(121));
+        assert_eq!(explain.naive_duration(), Duration::seconds(120));
+        assert!(leap_instants(&explain).eq(midnight));
+        let event = explain.leap_events().next().unwrap();
+        assert_eq!(
+            (event.sign, event.tai_offset_after),
+            (LeapSign::Positive, 37)
+        );
+
+        let explain = start.diff_explained(end);
+        assert_eq!(explain.duration(), Duration::seconds(-121));
+        assert_eq!(explain.naive_duration(), Duration::seconds(-120));
+        assert!(leap_instants(&explain).eq(midnight));
+
+        // Without any leap seconds in between.
+        let explain = start.diff_explained(start - Duration::DAY);
+        assert_eq!(explain.duration(), Duration::DAY);
+        assert_eq!(explain.naive_duration(), Duration::DAY);
+        assert_eq!(explain.leap_events().next(), None);
+    }
+
+    #[test]
+    fn explain_multiple_leap_seconds() {
+        let start = TaiDateTime::from(datetime!(2012-01-01 0:00 UTC));
+        let end = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        let naive = datetime!(2017-01-01 0:00 UTC) - datetime!(2012-01-01 0:00 UTC);
+        let instants = [
+            datetime!(2012-07-01 0:00 UTC),
+            datetime!(2015-07-01 0:00 UTC),
+            datetime!(2017-01-01 0:00 UTC),
+        ];
+
+        for explain in [end.diff_explained(start), start.diff_explained(end)] {
+            assert_eq!(explain.duration().abs(), naive + Duration::seconds(3));
+            assert_eq!(explain.naive_duration().abs(), naive);
+            assert!(leap_instants(&explain).eq(instants));
+        }
+    }
+
+    #[test]
+    fn explain_inside_leap_second() {
+        let midnight = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        let inside = midnight - Duration::milliseconds(500);
+        let before = midnight - Duration::seconds(10);
+        let after = midnight + Duration::seconds(10);
+
+        // The leap second is only over once the interval ends after it.
+        let explain = inside.diff_explained(before);
+        assert_eq!(explain.duration(), Duration::milliseconds(9_500));
+        assert_eq!(explain.naive_duration(), Duration::milliseconds(9_500));
+        assert_eq!(explain.leap_events().next(), None);
+
+        let explain = after.diff_explained(inside);
+        assert_eq!(explain.duration(), Duration::milliseconds(10_500));
+        assert_eq!(explain.naive_duration(), Duration::milliseconds(9_500));
+        assert!(leap_instants(&explain).eq([datetime!(2017-01-01 0:00 UTC)]));
+
+        let explain = inside.diff_explained(inside - Duration::milliseconds(250));
+        assert_eq!(explain.duration(), Duration::milliseconds(250));
+        assert_eq!(explain.naive_duration(), Duration::milliseconds(250));
+        assert_eq!(explain.leap_events().next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn explain_display() {
+        use alloc::string::ToString;
+
+        let utc = |utc: OffsetDateTime| TaiDateTime::from(utc);
+        let midnight = utc(datetime!(2017-01-01 0:00 UTC));
+        let day_before = utc(datetime!(2016-12-31 0:00 UTC));
+        assert_eq!(
+            midnight.diff_explained(day_before).to_string(),
+            "86401s [includes leap second at 2016-12-31T23:59:60Z]",
+        );
+        assert_eq!(
+            day_before.diff_explained(midnight).to_string(),
+            "-86401s [includes leap second at 2016-12-31T23:59:60Z]",
+        );
+        assert_eq!(
+            midnight
+                .diff_explained(utc(datetime!(2012-01-01 0:00 UTC)))
+                .to_string(),
+            "157852803s [includes leap seconds at 2012-06-30T23:59:60Z, \
+             2015-06-30T23:59:60Z, 2016-12-31T23:59:60Z]",
+        );
+        assert_eq!(
+            midnight
+                .diff_explained(midnight - Duration::milliseconds(1_500))
+                .to_string(),
+            "1.5s [includes leap second at 2016-12-31T23:59:60Z]",
+        );
+        assert_eq!(
+            (midnight - Duration::milliseconds(500))
+                .diff_explained(midnight - Duration::seconds(2))
+                .to_string(),
+            "1.5s",
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use core::{fmt, time::Duration as StdDuration};
+
+use time::OffsetDateTime;
+
+/// The error returned when a value is outside of the range representable by
+/// the target type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value out of range")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OutOfRange {}
+
+/// The error returned by [`TaiDateTime::duration_since`](crate::TaiDateTime::duration_since)
+/// if the supposedly earlier instant is actually later.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Negative(pub(crate) StdDuration);
+
+impl Negative {
+    /// How much later the supposedly earlier instant is.
+    pub fn duration(self) -> StdDuration {
+        self.0
+    }
+}
+
+impl fmt::Display for Negative {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the earlier instant is later than this one")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Negative {}
+
+/// The reason [`TaiDateTime::try_now_kernel`](crate::TaiDateTime::try_now_kernel)
+/// couldn't read the kernel's TAI clock.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ClockError {
+    /// The target has no TAI clock or reading it failed.
+    Unavailable,
+    /// The TAI clock is the same as the UTC clock, as the kernel's TAI offset
+    /// was never set, e.g. by a PTP or NTP daemon.
+    Unconfigured,
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable => f.write_str("the kernel's TAI clock is unavailable"),
+            Self::Unconfigured => f.write_str("the kernel's TAI offset is not set up"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClockError {}
+
+/// The reason a conversion between UTC and TAI can't be relied upon.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConversionError {
+    /// The instant is at or after the expiry of the leap second table, so a
+    /// leap second may have been announced that the table doesn't know about.
+    BeyondTableExpiry {
+        /// The expiry of the leap second table.
+        expiry: OffsetDateTime,
+    },
+    /// The instant is before 1972-01-01 00:00:00 UTC. Only from then on do TAI
+    /// and UTC differ by a whole number of seconds, so any earlier conversion
+    /// is just an approximation.
+    BeforeTaiEpoch,
+    /// The result is outside of the range of the target type.
+    TargetOutOfRange,
+    /// The instant is inside of an inserted leap second, which can't be
+    /// represented as an [`OffsetDateTime`].
+    InsideLeapSecond,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BeyondTableExpiry { expiry } => {
+                write!(f, "the leap second table expired at {expiry}")
+            }
+            Self::BeforeTaiEpoch => f.write_str("the instant is before 1972-01-01 UTC"),
+            Self::TargetOutOfRange => f.write_str("the result is out of range"),
+            Self::InsideLeapSecond => f.write_str("the instant is inside of a leap second"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConversionError {}
@@ -0,0 +1,365 @@
+use core::fmt;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    entry_before, floor_seconds, leap_seconds::LeapSource, leap_table, leap_table_expiry,
+    leap_table_source, seek_tai, seek_utc, tai_to_utc, utc_to_tai, TaiDateTime, TaiUtcOffset,
+    FIRST_LEAP_SECONDS_DIFF, LEAP_SECONDS,
+};
+
+/// Where the entry of the leap second table that a conversion used comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LeapOrigin {
+    /// The entry is built into the crate.
+    BuiltIn,
+    /// The entry isn't built into the crate, i.e. it was loaded from the
+    /// operating system or another source, or is part of a [mock
+    /// table](crate::MockTaiClock::with_table).
+    Added,
+    /// The entry was announced at runtime, e.g. with
+    /// [`announce`](crate::leap_seconds::announce), and isn't confirmed by any
+    /// source yet.
+    Announced,
+    /// The instant is before the first entry of the table, so the difference of
+    /// 10 seconds from 1972 applied, just like it does all the way back.
+    BeforeUtcEra,
+}
+
+/// The entry of the leap second table that a conversion used, as returned by
+/// [`TaiDateTime::from_utc_explained`], e.g. for bug reports about an
+/// unexpected offset.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LeapMatch {
+    /// The UTC instant from which on the entry applies, or `None` if the
+    /// instant is before the first entry.
+    pub boundary: Option<OffsetDateTime>,
+    /// The difference between TAI and UTC the entry sets, which the conversion
+    /// applied.
+    pub offset: TaiUtcOffset,
+    /// Where the entry comes from.
+    pub origin: LeapOrigin,
+}
+
+impl LeapMatch {
+    fn new(entry: Option<(i64, i64)>, provisional: &[i64]) -> Self {
+        match entry {
+            Some(entry) => Self {
+                boundary: Some(OffsetDateTime::UNIX_EPOCH + Duration::seconds(entry.0)),
+                offset: TaiUtcOffset::from_whole_seconds(entry.1),
+                origin: if provisional.contains(&entry.0) {
+                    LeapOrigin::Announced
+                } else if LEAP_SECONDS.contains(&entry) {
+                    LeapOrigin::BuiltIn
+                } else {
+                    LeapOrigin::Added
+                },
+            },
+            None => Self {
+                boundary: None,
+                offset: TaiUtcOffset::from_whole_seconds(FIRST_LEAP_SECONDS_DIFF),
+                origin: LeapOrigin::BeforeUtcEra,
+            },
+        }
+    }
+}
+
+/// The direction of a traced conversion.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConversionDirection {
+    /// From UTC to TAI.
+    UtcToTai,
+    /// From TAI to UTC.
+    TaiToUtc,
+}
+
+/// Everything that went into a conversion between UTC and TAI, as returned by
+/// [`TaiDateTime::explain_from_utc`] and [`TaiDateTime::explain_to_utc`], e.g.
+/// to find out why a conversion applied a difference of 37 rather than 36
+/// seconds. The lookup is the very same one the conversions do, so the trace
+/// always matches their result.
+///
+/// Its [`Display`](fmt::Display) implementation lists each part on a line of
+/// its own.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ConversionTrace {
+    /// Which way the conversion went.
+    pub direction: ConversionDirection,
+    /// The UTC date time, i.e. the input when converting from UTC and the
+    /// result when converting to UTC.
+    pub utc: OffsetDateTime,
+    /// The TAI date time, i.e. the result when converting from UTC and the
+    /// input when converting to UTC.
+    pub tai: TaiDateTime,
+    /// The entry of the leap second table that matched.
+    pub matched: LeapMatch,
+    /// Where the leap seconds used for conversions come from, like
+    /// [`leap_source`](crate::leap_seconds::leap_source).
+    pub table_source: LeapSource,
+    /// The UTC instant at which the leap second table expires, like
+    /// [`expires_at`](crate::leap_seconds::expires_at).
+    pub expires_at: OffsetDateTime,
+    /// Whether the instant is at or after the expiry of the leap second table,
+    /// so a leap second announced after the table was published may be
+    /// missing.
+    pub past_expiry: bool,
+    /// Whether the instant is inside of an inserted leap second, which UTC
+    /// can't represent, so it was converted to the same time of day in the
+    /// second after it. This is never the case when converting from UTC.
+    pub in_leap_second: bool,
+}
+
+impl TaiDateTime {
+    /// Converts a UTC date time to TAI just like the [`From`] conversion, but
+    /// also returns which entry of the leap second table it used.
+    pub fn from_utc_explained(time: OffsetDateTime) -> (Self, LeapMatch) {
+        let trace = Self::explain_from_utc(time);
+        (trace.tai, trace.matched)
+    }
+
+    /// Converts a UTC date time to TAI just like the [`From`] conversion, but
+    /// returns a trace of everything that went into it.
+    pub fn explain_from_utc(time: OffsetDateTime) -> ConversionTrace {
+        let table = leap_table();
+        let unix_time_stamp = floor_seconds(time - OffsetDateTime::UNIX_EPOCH);
+        let index = seek_utc(&table, table.len(), unix_time_stamp);
+        let matched = LeapMatch::new(entry_before(&table, index), table.provisional());
+        let expires_at = leap_table_expiry();
+        ConversionTrace {
+            direction: ConversionDirection::UtcToTai,
+            utc: time,
+            tai: utc_to_tai(time, |_| matched.offset.whole_seconds()),
+            matched,
+            table_source: leap_table_source(),
+            expires_at: OffsetDateTime::UNIX_EPOCH + Duration::seconds(expires_at),
+            past_expiry: unix_time_stamp >= expires_at,
+            in_leap_second: false,
+        }
+    }
+
+    /// Converts to UTC just like the [`From`] conversion, but returns a trace
+    /// of everything that went into it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting UTC date time is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn explain_to_utc(&self) -> ConversionTrace {
+        let table = leap_table();
+        let tai_time_stamp = floor_seconds(self.0);
+        let index = seek_tai(&table, table.len(), tai_time_stamp);
+        let matched = LeapMatch::new(entry_before(&table, index), table.provisional());
+        let diff = matched.offset.whole_seconds();
+        let utc = tai_to_utc(*self, |_| diff);
+        let expires_at = leap_table_expiry();
+        ConversionTrace {
+            direction: ConversionDirection::TaiToUtc,
+            utc,
+            tai: *self,
+            matched,
+            table_source: leap_table_source(),
+            expires_at: OffsetDateTime::UNIX_EPOCH + Duration::seconds(expires_at),
+            past_expiry: floor_seconds(utc - OffsetDateTime::UNIX_EPOCH) >= expires_at,
+            in_leap_second: table
+                .get(index)
+                .is_some_and(|&(t, _)| tai_time_stamp >= t + diff),
+        }
+    }
+}
+
+impl fmt::Display for ConversionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let utc = format_args!("{} {} UTC", self.utc.date(), self.utc.time());
+        match self.direction {
+            ConversionDirection::UtcToTai => writeln!(f, "{utc} -> {}", self.tai)?,
+            ConversionDirection::TaiToUtc => writeln!(f, "{} -> {utc}", self.tai)?,
+        }
+        let origin = match self.matched.origin {
+            LeapOrigin::BuiltIn => "built-in",
+            LeapOrigin::Added => "added",
+            LeapOrigin::Announced => "announced",
+            LeapOrigin::BeforeUtcEra => "before 1972",
+        };
+        match self.matched.boundary {
+            Some(boundary) => write!(f, "entry: from {}", boundary.date())?,
+            None => f.write_str("entry: none")?,
+        }
+        writeln!(f, ", TAI - UTC = {} ({origin})", self.matched.offset)?;
+        writeln!(
+            f,
+            "table: {}, expires on {}",
+            self.table_source.description(),
+            self.expires_at.date(),
+        )?;
+        let yes_no = |value| if value { "yes" } else { "no" };
+        writeln!(f, "past expiry: {}", yes_no(self.past_expiry))?;
+        write!(f, "inside a leap second: {}", yes_no(self.in_leap_second))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn built_in(boundary: OffsetDateTime, offset: i64) -> LeapMatch {
+        LeapMatch {
+            boundary: Some(boundary),
+            offset: TaiUtcOffset::from_whole_seconds(offset),
+            origin: LeapOrigin::BuiltIn,
+        }
+    }
+
+    #[test]
+    fn matched_entries_around_boundaries() {
+        #[cfg(feature = "std")]
+        let _lock = crate::tests::lock_global_table();
+        let cases = [
+            (
+                datetime!(2016-12-31 23:59:59.999 UTC),
+                built_in(datetime!(2015-07-01 0:00 UTC), 36),
+            ),
+            (
+                datetime!(2017-01-01 0:00 UTC),
+                built_in(datetime!(2017-01-01 0:00 UTC), 37),
+            ),
+            (
+                datetime!(1972-07-01 0:00 UTC),
+                built_in(datetime!(1972-07-01 0:00 UTC), 11),
+            ),
+        ];
+        for (utc, matched) in cases {
+            let trace = TaiDateTime::explain_from_utc(utc);
+            assert_eq!(trace.direction, ConversionDirection::UtcToTai);
+            assert_eq!((trace.utc, trace.tai), (utc, TaiDateTime::from(utc)));
+            assert_eq!(trace.matched, matched);
+            assert!(!trace.past_expiry);
+            assert!(!trace.in_leap_second);
+            assert_eq!(TaiDateTime::from_utc_explained(utc), (trace.tai, matched));
+
+            let trace = trace.tai.explain_to_utc();
+            assert_eq!(trace.direction, ConversionDirection::TaiToUtc);
+            assert_eq!(trace.utc, utc);
+            assert_eq!(trace.matched, matched);
+            assert!(!trace.past_expiry);
+            assert!(!trace.in_leap_second);
+        }
+
+        let utc = datetime!(1972-06-30 23:59:59 UTC);
+        let trace = TaiDateTime::explain_from_utc(utc);
+        assert_eq!(
+            trace.matched,
+            LeapMatch {
+                boundary: None,
+                offset: TaiUtcOffset::from_whole_seconds(10),
+                origin: LeapOrigin::BeforeUtcEra,
+            },
+        );
+        assert_eq!(trace.tai.explain_to_utc().matched, trace.matched);
+    }
+
+    #[test]
+    fn explained_origins() {
+        #[cfg(feature = "std")]
+        let _lock = crate::tests::lock_global_table();
+        for (utc, leap_match) in [
+            (
+                datetime!(1971-12-31 23:59:59.999 UTC),
+                LeapMatch {
+                    boundary: None,
+                    offset: TaiUtcOffset::from_whole_seconds(10),
+                    origin: LeapOrigin::BeforeUtcEra,
+                },
+            ),
+            (
+                datetime!(1972-01-01 0:00 UTC),
+                LeapMatch {
+                    boundary: None,
+                    offset: TaiUtcOffset::from_whole_seconds(10),
+                    origin: LeapOrigin::BeforeUtcEra,
+                },
+            ),
+            (
+                datetime!(1980-01-06 0:00 UTC),
+                built_in(datetime!(1980-01-01 0:00 UTC), 19),
+            ),
+            (
+                datetime!(2017-01-01 0:00 UTC),
+                built_in(datetime!(2017-01-01 0:00 UTC), 37),
+            ),
+        ] {
+            let (tai, matched) = TaiDateTime::from_utc_explained(utc);
+            assert_eq!(tai, TaiDateTime::from(utc), "{utc}");
+            assert_eq!(matched, leap_match, "{utc}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "test-util")]
+    fn explained_added_entry() {
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let table = crate::tests::builtin_table_with(&[(utc, 38)], datetime!(2030-06-28 0:00 UTC));
+        let clock = crate::MockTaiClock::new(TaiDateTime::MIN).with_table(table);
+        clock.install();
+
+        let (tai, matched) = TaiDateTime::from_utc_explained(utc);
+        assert_eq!(tai, TaiDateTime::from(utc));
+        assert_eq!(
+            matched,
+            LeapMatch {
+                boundary: Some(utc),
+                offset: TaiUtcOffset::from_whole_seconds(38),
+                origin: LeapOrigin::Added,
+            },
+        );
+        let (_, matched) = TaiDateTime::from_utc_explained(utc - Duration::NANOSECOND);
+        assert_eq!(matched, built_in(datetime!(2017-01-01 0:00 UTC), 37));
+    }
+
+    #[test]
+    fn past_expiry() {
+        #[cfg(feature = "std")]
+        let _lock = crate::tests::lock_global_table();
+        let utc = datetime!(2200-01-01 0:00 UTC);
+        let trace = TaiDateTime::explain_from_utc(utc);
+        assert!(trace.past_expiry);
+        assert!(trace.expires_at <= utc);
+        assert_eq!(trace.table_source, crate::leap_seconds::leap_source());
+        let trace = trace.tai.explain_to_utc();
+        assert!(trace.past_expiry);
+        assert_eq!(trace.utc, utc);
+
+        // The expiry itself already counts as past it.
+        let trace = TaiDateTime::explain_from_utc(trace.expires_at);
+        assert!(trace.past_expiry);
+        let trace = TaiDateTime::explain_from_utc(trace.expires_at - Duration::NANOSECOND);
+        assert!(!trace.past_expiry);
+    }
+
+    #[test]
+    fn in_leap_second() {
+        #[cfg(feature = "std")]
+        let _lock = crate::tests::lock_global_table();
+        let midnight = datetime!(2017-01-01 0:00 UTC);
+        let leap_second = TaiDateTime::from(midnight) - Duration::milliseconds(750);
+        let trace = leap_second.explain_to_utc();
+        assert!(trace.in_leap_second);
+        assert_eq!(trace.utc, midnight + Duration::milliseconds(250));
+        assert_eq!(trace.utc, OffsetDateTime::from(leap_second));
+        assert_eq!(trace.matched, built_in(datetime!(2015-07-01 0:00 UTC), 36));
+
+        // The seconds right before and after it are regular ones.
+        for tai in [
+            leap_second - Duration::SECOND,
+            leap_second + Duration::SECOND,
+        ] {
+            let trace = tai.explain_to_utc();
+            assert!(!trace.in_leap_second);
+            assert_eq!(TaiDateTime::from(trace.utc), tai);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Reading and writing the modification times of files, on either time scale.
+//!
+//! A file system stores a modification time as seconds and nanoseconds since
+//! 1970, without saying which time scale they are on. Usually that's UTC, i.e.
+//! UNIX time, which is what [`utc_mtime`] and [`set_utc_mtime`] expect and
+//! convert from and to. A system that sets the times from `CLOCK_TAI` stores
+//! TAI instead, which is what [`tai_mtime`] and [`set_tai_mtime`] expect. They
+//! store the instant as is, without any conversion. Mixing the two up shifts
+//! the times by the difference between TAI and UTC, i.e. 37 seconds as of
+//! 2017.
+
+use std::{io, os::unix::fs::MetadataExt, path::Path};
+
+use time::{Duration, OffsetDateTime};
+
+use crate::{floor_seconds, leap_seconds_at_tai, platform, OutOfRange, TaiDateTime};
+
+impl TaiDateTime {
+    /// Interprets a `timespec` as TAI seconds and nanoseconds since
+    /// 1970-01-01 00:00:00 TAI, e.g. one read from `CLOCK_TAI`. There is no
+    /// conversion, as the value already is on the TAI time scale. Nanoseconds
+    /// outside of `0..1_000_000_000` carry into the seconds.
+    pub fn from_timespec_tai(time: libc::timespec) -> Self {
+        // `time_t` is only 32 bits wide on some targets.
+        #[allow(clippy::unnecessary_cast)]
+        let (secs, nanos) = (time.tv_sec as i64, time.tv_nsec as i64);
+        Self::saturating(Duration::seconds(secs).saturating_add(Duration::nanoseconds(nanos)))
+    }
+
+    /// Returns the instant as a `timespec` of TAI seconds and nanoseconds since
+    /// 1970-01-01 00:00:00 TAI, e.g. to compare it with `CLOCK_TAI`. Just like
+    /// with [`as_timespec`](Self::as_timespec), the seconds are rounded down, so
+    /// the nanoseconds are never negative.
+    ///
+    /// Fails if the seconds don't fit into a `time_t`, which on some targets is
+    /// only 32 bits wide.
+    pub fn to_timespec_tai(&self) -> Result<libc::timespec, OutOfRange> {
+        to_timespec(self.0)
+    }
+}
+
+// Splits a duration since 1970 into a `timespec`, rounding the seconds down.
+fn to_timespec(since_epoch: Duration) -> Result<libc::timespec, OutOfRange> {
+    let (secs, nanos) = TaiDateTime(since_epoch).as_timespec();
+    // SAFETY: A `timespec` is plain old data, so all zeros are valid. It has
+    // padding fields on some targets, so it can't be created with a struct
+    // expression.
+    let mut time: libc::timespec = unsafe { core::mem::zeroed() };
+    // `time_t` is only 32 bits wide on some targets.
+    #[allow(clippy::useless_conversion)]
+    let secs = secs.try_into().map_err(|_| OutOfRange)?;
+    time.tv_sec = secs;
+    time.tv_nsec = nanos as _;
+    Ok(time)
+}
+
+// The error returned when a time can't be stored as a modification time.
+fn out_of_range() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "the time is out of range for a file time",
+    )
+}
+
+// The modification time of the file as stored, i.e. on an unknown time scale.
+fn stored_mtime(path: &Path) -> io::Result<Duration> {
+    let metadata = std::fs::metadata(path)?;
+    Ok(Duration::new(
+        metadata.mtime(),
+        metadata.mtime_nsec() as i32,
+    ))
+}
+
+/// Returns the modification time of the file, interpreting the stored value as
+/// TAI, as set by [`set_tai_mtime`] or from `CLOCK_TAI`. There is no
+/// conversion. Use [`utc_mtime`] for files with ordinary UNIX times instead.
+/// Symbolic links are followed.
+pub fn tai_mtime(path: impl AsRef<Path>) -> io::Result<TaiDateTime> {
+    stored_mtime(path.as_ref()).map(TaiDateTime)
+}
+
+/// Sets the modification time of the file, storing the instant as TAI, without
+/// any conversion. Only [`tai_mtime`] reads it back correctly, other tools
+/// interpret it as UTC and are off by the difference between TAI and UTC. Use
+/// [`set_utc_mtime`] to store an ordinary UNIX time instead. Symbolic links are
+/// followed and the access time is left unchanged.
+pub fn set_tai_mtime(path: impl AsRef<Path>, time: TaiDateTime) -> io::Result<()> {
+    let time = time.to_timespec_tai().map_err(|_| out_of_range())?;
+    platform::set_mtime(path.as_ref(), time)
+}
+
+/// Returns the modification time of the file, interpreting the stored value as
+/// an ordinary UNIX time, i.e. UTC, and converting it to TAI. Use
+/// [`tai_mtime`] for files whose times were set from `CLOCK_TAI` instead.
+/// Symbolic links are followed.
+pub fn utc_mtime(path: impl AsRef<Path>) -> io::Result<TaiDateTime> {
+    let since_epoch = stored_mtime(path.as_ref())?;
+    let time = OffsetDateTime::UNIX_EPOCH
+        .checked_add(since_epoch)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file time is out of range for a UTC date time",
+            )
+        })?;
+    Ok(TaiDateTime::from(time))
+}
+
+/// Sets the modification time of the file to the instant converted to UTC,
+/// stored as an ordinary UNIX time. An instant inside of an inserted leap
+/// second is stored as the same time of day in the second after it, like with
+/// the [`From`] conversion. Use [`set_tai_mtime`] to store the instant as TAI
+/// instead. Symbolic links are followed and the access time is left unchanged.
+pub fn set_utc_mtime(path: impl AsRef<Path>, time: TaiDateTime) -> io::Result<()> {
+    let diff = leap_seconds_at_tai(floor_seconds(time.0));
+    let since_epoch = time
+        .0
+        .checked_sub(Duration::seconds(diff))
+        .ok_or_else(out_of_range)?;
+    let time = to_timespec(since_epoch).map_err(|_| out_of_range())?;
+    platform::set_mtime(path.as_ref(), time)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn timespec(secs: i64, nanos: i64) -> libc::timespec {
+        // SAFETY: See `to_timespec`.
+        let mut time: libc::timespec = unsafe { core::mem::zeroed() };
+        time.tv_sec = secs as _;
+        time.tv_nsec = nanos as _;
+        time
+    }
+
+    fn parts(time: libc::timespec) -> (i64, i64) {
+        #[allow(clippy::unnecessary_cast)]
+        (time.tv_sec as i64, time.tv_nsec as i64)
+    }
+
+    #[test]
+    fn timespec_conversions() {
+        for (secs, nanos, since_epoch) in [
+            (0, 0, Duration::ZERO),
+            (1_483_228_837, 5, Duration::new(1_483_228_837, 5)),
+            (-1, 0, Duration::SECOND * -1),
+            (-2, 750_000_000, Duration::milliseconds(-1250)),
+            (-1, 999_999_999, Duration::NANOSECOND * -1),
+        ] {
+            let time = TaiDateTime(since_epoch);
+            assert_eq!(TaiDateTime::from_timespec_tai(timespec(secs, nanos)), time);
+            assert_eq!(time.to_timespec_tai().map(parts), Ok((secs, nanos)));
+        }
+
+        // Nanoseconds outside of a second carry into the seconds.
+        assert_eq!(
+            TaiDateTime::from_timespec_tai(timespec(-1, -250_000_000)),
+            TaiDateTime(Duration::milliseconds(-1250)),
+        );
+        assert_eq!(
+            TaiDateTime::from_timespec_tai(timespec(1, 1_500_000_000)),
+            TaiDateTime(Duration::milliseconds(2500)),
+        );
+    }
+
+    #[test]
+    fn timespec_out_of_range() {
+        let time = TaiDateTime(Duration::seconds(i64::from(i32::MAX) + 1));
+        if core::mem::size_of::<libc::time_t>() == 4 {
+            assert_eq!(time.to_timespec_tai().map(parts), Err(OutOfRange));
+        } else {
+            assert_eq!(time.to_timespec_tai().map(parts), Ok((1 << 31, 0)));
+        }
+    }
+
+    #[test]
+    fn mtime_round_trips() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path();
+        let utc = datetime!(2017-01-01 0:00:00.25 UTC);
+        let tai = TaiDateTime::from(utc);
+
+        // Stored as TAI, the time is 37 seconds ahead of the UNIX time.
+        set_tai_mtime(path, tai).unwrap();
+        assert_eq!(tai_mtime(path).unwrap(), tai);
+        let stored = std::fs::metadata(path).unwrap().modified().unwrap();
+        assert_eq!(OffsetDateTime::from(stored), utc + Duration::seconds(37),);
+        assert_eq!(
+            utc_mtime(path).unwrap(),
+            TaiDateTime::from(utc + Duration::seconds(37)),
+        );
+
+        // Stored as UTC, it's the ordinary UNIX time.
+        set_utc_mtime(path, tai).unwrap();
+        assert_eq!(utc_mtime(path).unwrap(), tai);
+        let stored = std::fs::metadata(path).unwrap().modified().unwrap();
+        assert_eq!(OffsetDateTime::from(stored), utc);
+        assert_eq!(
+            tai_mtime(path).unwrap(),
+            TaiDateTime(utc - OffsetDateTime::UNIX_EPOCH)
+        );
+
+        // An instant inside of a leap second is stored as the second after it.
+        set_utc_mtime(path, tai - Duration::SECOND).unwrap();
+        assert_eq!(utc_mtime(path).unwrap(), tai);
+    }
+
+    #[test]
+    fn mtime_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing");
+        assert_eq!(
+            tai_mtime(&path).unwrap_err().kind(),
+            io::ErrorKind::NotFound,
+        );
+        assert_eq!(
+            set_tai_mtime(&path, TaiDateTime(Duration::ZERO))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound,
+        );
+        // The earliest instant has no UNIX time.
+        let file = tempfile::NamedTempFile::new().unwrap();
+        assert_eq!(
+            set_utc_mtime(file.path(), TaiDateTime::MIN)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput,
+        );
+    }
+}
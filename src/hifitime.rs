@@ -0,0 +1,96 @@
+use ::hifitime::{Duration as HifiDuration, Epoch};
+use time::Duration;
+
+use crate::{OutOfRange, TaiDateTime, LEAP_BASE_OFFSET};
+
+// The nanoseconds from 1900-01-01 00:00:00 TAI, the reference epoch of
+// hifitime, to 1970-01-01 00:00:00 TAI. Both are on the TAI time scale, so
+// there are no leap seconds in between.
+const J1900_TO_UNIX_EPOCH_NANOS: i128 = LEAP_BASE_OFFSET as i128 * 1_000_000_000;
+
+/// Converts a hifitime epoch, regardless of the time scale it was created on.
+/// Both store the instant on the TAI time scale, so the conversion doesn't go
+/// through UTC and is exact to the nanosecond. Every epoch is in range, so this
+/// never panics.
+impl From<Epoch> for TaiDateTime {
+    fn from(epoch: Epoch) -> Self {
+        let nanos = epoch.to_tai_duration().total_nanoseconds() - J1900_TO_UNIX_EPOCH_NANOS;
+        // The range of hifitime's durations is about 3 million years, so the
+        // seconds always fit.
+        Self(Duration::new(
+            (nanos / 1_000_000_000) as i64,
+            (nanos % 1_000_000_000) as i32,
+        ))
+    }
+}
+
+/// Converts to a hifitime epoch on the TAI time scale, without going through
+/// UTC, so the conversion is exact to the nanosecond. Fails if the instant is
+/// more than about 3 million years away from 1900, which is the range of
+/// hifitime's durations.
+impl TryFrom<TaiDateTime> for Epoch {
+    type Error = OutOfRange;
+
+    fn try_from(time: TaiDateTime) -> Result<Self, Self::Error> {
+        let nanos = time.unix_tai_nanos() + J1900_TO_UNIX_EPOCH_NANOS;
+        // Durations out of range saturate, so they don't survive the round
+        // trip.
+        let duration = HifiDuration::from_total_nanoseconds(nanos);
+        if duration.total_nanoseconds() != nanos {
+            return Err(OutOfRange);
+        }
+        Ok(Epoch::from_tai_duration(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, OffsetDateTime};
+
+    use super::*;
+
+    // The starts of the days after a few of the leap seconds.
+    const LEAP_SECONDS: [OffsetDateTime; 4] = [
+        datetime!(1972-07-01 0:00 UTC),
+        datetime!(1990-01-01 0:00 UTC),
+        datetime!(2009-01-01 0:00 UTC),
+        datetime!(2017-01-01 0:00 UTC),
+    ];
+
+    #[test]
+    fn utc_matches_hifitime() {
+        for leap_second in LEAP_SECONDS {
+            // hifitime looks up the leap seconds by the time of day on the
+            // other time scale, so it's off by one within the current number
+            // of leap seconds of a boundary. A minute away it's exact.
+            for offset in [-86400, -60, 60, 86400] {
+                let utc = leap_second + Duration::seconds(offset);
+                let epoch = Epoch::from_gregorian_utc(
+                    utc.year(),
+                    utc.month() as u8,
+                    utc.day(),
+                    utc.hour(),
+                    utc.minute(),
+                    utc.second(),
+                    utc.nanosecond(),
+                );
+                let time = TaiDateTime::from(utc);
+                assert_eq!(TaiDateTime::from(epoch), time, "{utc}");
+                let (year, month, day, hour, minute, second, _) =
+                    Epoch::try_from(time).unwrap().to_gregorian_utc();
+                assert_eq!(
+                    (year, month, day, hour, minute, second),
+                    (
+                        utc.year(),
+                        utc.month() as u8,
+                        utc.day(),
+                        utc.hour(),
+                        utc.minute(),
+                        utc.second()
+                    ),
+                    "{utc}",
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,421 @@
+//! Instants and durations on the TAI time scale with attosecond resolution.
+//!
+//! [`TaiDateTime`] and [`Duration`] bottom out at nanoseconds, which isn't
+//! enough when several sub-nanosecond corrections get applied one after
+//! another. [`HighResTai`] and [`FineDuration`] store the seconds as an `i64`
+//! and the fraction of the second as a `u64` number of attoseconds, so they
+//! cover the range of [`TaiDateTime`] with a billion times the resolution.
+//! [`Duration`] goes almost a second further in either direction, so
+//! converting one to a [`FineDuration`] can fail.
+
+use core::ops::{Add, Neg, Sub};
+
+use time::{Duration, OffsetDateTime};
+
+use crate::{OutOfRange, TaiDateTime};
+
+const ATTOS_PER_SEC: i128 = 1_000_000_000_000_000_000;
+const ATTOS_PER_NANO: i128 = 1_000_000_000;
+
+// Splits a number of attoseconds into whole seconds, rounded down, and the
+// remaining non-negative attoseconds.
+fn split(attos: i128) -> Option<(i64, u64)> {
+    let secs = i64::try_from(attos.div_euclid(ATTOS_PER_SEC)).ok()?;
+    Some((secs, attos.rem_euclid(ATTOS_PER_SEC) as u64))
+}
+
+// Rounds a number of attoseconds to nanoseconds, with ties going away from
+// zero.
+fn round_to_nanos(attos: i128) -> i128 {
+    let (nanos, rem) = (
+        attos.div_euclid(ATTOS_PER_NANO),
+        attos.rem_euclid(ATTOS_PER_NANO),
+    );
+    let half = ATTOS_PER_NANO / 2;
+    if rem > half || (rem == half && attos >= 0) {
+        nanos + 1
+    } else {
+        nanos
+    }
+}
+
+fn nanos_to_duration(nanos: i128) -> Duration {
+    let secs = i64::try_from(nanos.div_euclid(1_000_000_000))
+        .expect("overflow when converting to nanosecond resolution");
+    Duration::new(secs, nanos.rem_euclid(1_000_000_000) as i32)
+}
+
+/// A signed duration with attosecond resolution.
+///
+/// The value is `seconds + attoseconds / 10^18`, where the attoseconds are
+/// always within `0..10^18`, so -0.25 s is stored as -1 s and 0.75 s.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FineDuration {
+    seconds: i64,
+    attoseconds: u64,
+}
+
+impl FineDuration {
+    /// A duration of zero length.
+    pub const ZERO: Self = Self {
+        seconds: 0,
+        attoseconds: 0,
+    };
+
+    /// Creates a duration from whole seconds and additional attoseconds.
+    /// Attoseconds of `10^18` or more carry into the seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the seconds overflow.
+    pub fn new(seconds: i64, attoseconds: u64) -> Self {
+        Self::from_attoseconds(seconds as i128 * ATTOS_PER_SEC + attoseconds as i128)
+    }
+
+    /// Creates a duration from a total number of attoseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration doesn't fit into an `i64` number of seconds.
+    pub fn from_attoseconds(attoseconds: i128) -> Self {
+        Self::checked_from_attoseconds(attoseconds).expect("overflow when creating duration")
+    }
+
+    /// Creates a duration from a total number of attoseconds, returning
+    /// `None` if it doesn't fit into an `i64` number of seconds.
+    pub fn checked_from_attoseconds(attoseconds: i128) -> Option<Self> {
+        let (seconds, attoseconds) = split(attoseconds)?;
+        Some(Self {
+            seconds,
+            attoseconds,
+        })
+    }
+
+    /// The total number of attoseconds.
+    pub fn whole_attoseconds(self) -> i128 {
+        self.seconds as i128 * ATTOS_PER_SEC + self.attoseconds as i128
+    }
+
+    /// The seconds, rounded down.
+    pub fn floor_seconds(self) -> i64 {
+        self.seconds
+    }
+
+    /// The attoseconds on top of [`floor_seconds`](Self::floor_seconds),
+    /// always within `0..10^18`.
+    pub fn subsec_attoseconds(self) -> u64 {
+        self.attoseconds
+    }
+
+    /// Adds two durations, returning `None` on overflow.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::checked_from_attoseconds(self.whole_attoseconds() + rhs.whole_attoseconds())
+    }
+
+    /// Subtracts two durations, returning `None` on overflow.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::checked_from_attoseconds(self.whole_attoseconds() - rhs.whole_attoseconds())
+    }
+
+    /// Rounds to the nearest nanosecond, with ties going away from zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration rounds up to `i64::MAX` seconds and a whole
+    /// second more.
+    pub fn round(self) -> Duration {
+        nanos_to_duration(round_to_nanos(self.whole_attoseconds()))
+    }
+
+    /// Drops everything below a nanosecond. As the attoseconds are never
+    /// negative, this rounds towards negative infinity.
+    pub fn trunc(self) -> Duration {
+        nanos_to_duration(self.whole_attoseconds().div_euclid(ATTOS_PER_NANO))
+    }
+}
+
+/// Fails for durations of less than `i64::MIN` seconds, which don't fit.
+impl TryFrom<Duration> for FineDuration {
+    type Error = OutOfRange;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        Self::checked_from_attoseconds(duration.whole_nanoseconds() * ATTOS_PER_NANO)
+            .ok_or(OutOfRange)
+    }
+}
+
+impl Neg for FineDuration {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::from_attoseconds(-self.whole_attoseconds())
+    }
+}
+
+impl Add for FineDuration {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("overflow when adding durations")
+    }
+}
+
+impl Sub for FineDuration {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("overflow when subtracting durations")
+    }
+}
+
+impl Add<Duration> for FineDuration {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        let attos = self.whole_attoseconds() + rhs.whole_nanoseconds() * ATTOS_PER_NANO;
+        Self::checked_from_attoseconds(attos).expect("overflow when adding durations")
+    }
+}
+
+impl Sub<Duration> for FineDuration {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        let attos = self.whole_attoseconds() - rhs.whole_nanoseconds() * ATTOS_PER_NANO;
+        Self::checked_from_attoseconds(attos).expect("overflow when subtracting durations")
+    }
+}
+
+/// An instant on the TAI time scale with attosecond resolution. It is stored
+/// the same way as a [`FineDuration`] since 1970-01-01 00:00:00 TAI.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HighResTai(FineDuration);
+
+impl HighResTai {
+    /// Creates an instant from whole TAI seconds since 1970-01-01 00:00:00 TAI
+    /// and additional attoseconds.
+    pub fn new(seconds: i64, attoseconds: u64) -> Self {
+        Self(FineDuration::new(seconds, attoseconds))
+    }
+
+    /// The duration since 1970-01-01 00:00:00 TAI.
+    pub fn since_epoch(self) -> FineDuration {
+        self.0
+    }
+
+    /// Rounds to the nearest nanosecond, with ties going away from zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instant rounds up to after [`TaiDateTime::MAX`].
+    pub fn round(self) -> TaiDateTime {
+        TaiDateTime(self.0.round())
+    }
+
+    /// Drops everything below a nanosecond, i.e. rounds towards the past.
+    pub fn trunc(self) -> TaiDateTime {
+        TaiDateTime(self.0.trunc())
+    }
+
+    /// Adds a duration, returning `None` on overflow.
+    pub fn checked_add(self, rhs: FineDuration) -> Option<Self> {
+        self.0.checked_add(rhs).map(Self)
+    }
+
+    /// Subtracts a duration, returning `None` on overflow.
+    pub fn checked_sub(self, rhs: FineDuration) -> Option<Self> {
+        self.0.checked_sub(rhs).map(Self)
+    }
+}
+
+impl From<TaiDateTime> for HighResTai {
+    fn from(time: TaiDateTime) -> Self {
+        // `TaiDateTime::MIN` is a whole second, so every instant fits.
+        Self(FineDuration::from_attoseconds(
+            time.0.whole_nanoseconds() * ATTOS_PER_NANO,
+        ))
+    }
+}
+
+impl From<OffsetDateTime> for HighResTai {
+    fn from(time: OffsetDateTime) -> Self {
+        TaiDateTime::from(time).into()
+    }
+}
+
+/// Converts to UTC by [truncating](HighResTai::trunc) to nanoseconds first.
+impl From<HighResTai> for OffsetDateTime {
+    fn from(time: HighResTai) -> Self {
+        time.trunc().into()
+    }
+}
+
+impl Sub for HighResTai {
+    type Output = FineDuration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.0 - rhs.0
+    }
+}
+
+impl Add<FineDuration> for HighResTai {
+    type Output = Self;
+
+    fn add(self, rhs: FineDuration) -> Self::Output {
+        Self(self.0 + rhs)
+    }
+}
+
+impl Sub<FineDuration> for HighResTai {
+    type Output = Self;
+
+    fn sub(self, rhs: FineDuration) -> Self::Output {
+        Self(self.0 - rhs)
+    }
+}
+
+impl Add<Duration> for HighResTai {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self(self.0 + rhs)
+    }
+}
+
+impl Sub<Duration> for HighResTai {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self(self.0 - rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Attoseconds spread over the whole range, but mostly close to the
+    // boundaries of the seconds around zero, where carries and borrows happen.
+    fn random_attos(rng: &mut fastrand::Rng) -> i128 {
+        let min = i64::MIN as i128 * ATTOS_PER_SEC;
+        let max = (i64::MAX as i128 + 1) * ATTOS_PER_SEC - 1;
+        match rng.u8(0..4) {
+            0 => rng.i128(min..=max),
+            1 => rng.i128(-3..=3) * ATTOS_PER_SEC + rng.i128(-3..=3),
+            2 => rng.i128(-3..=3) * ATTOS_PER_SEC + rng.i128(-ATTOS_PER_SEC..ATTOS_PER_SEC),
+            _ => [min, max, 0, -1, 1][rng.usize(0..5)],
+        }
+    }
+
+    fn check_parts(duration: FineDuration, attos: i128) {
+        assert_eq!(duration.whole_attoseconds(), attos);
+        assert_eq!(
+            duration.floor_seconds() as i128,
+            attos.div_euclid(ATTOS_PER_SEC)
+        );
+        assert_eq!(
+            duration.subsec_attoseconds() as i128,
+            attos.rem_euclid(ATTOS_PER_SEC)
+        );
+    }
+
+    #[test]
+    fn carry_and_borrow() {
+        let attosecond = FineDuration::new(0, 1);
+        let almost_zero = FineDuration::new(-1, 999_999_999_999_999_999);
+        assert_eq!(almost_zero + attosecond, FineDuration::ZERO);
+        assert_eq!(FineDuration::ZERO - attosecond, almost_zero);
+        assert_eq!(-attosecond, almost_zero);
+        assert_eq!(
+            FineDuration::new(-2, 500_000_000_000_000_000)
+                - FineDuration::new(0, 750_000_000_000_000_000),
+            FineDuration::new(-3, 750_000_000_000_000_000),
+        );
+        assert_eq!(
+            FineDuration::new(-1, 750_000_000_000_000_000) + Duration::milliseconds(500),
+            FineDuration::new(0, 250_000_000_000_000_000),
+        );
+        assert_eq!(
+            FineDuration::new(-1, ATTOS_PER_SEC as u64),
+            FineDuration::ZERO
+        );
+        check_parts(FineDuration::from_attoseconds(-1), -1);
+    }
+
+    #[test]
+    fn arithmetic_matches_i128() {
+        let mut rng = fastrand::Rng::with_seed(0xa770);
+        for _ in 0..10_000 {
+            let (a, b) = (random_attos(&mut rng), random_attos(&mut rng));
+            let (x, y) = (
+                FineDuration::from_attoseconds(a),
+                FineDuration::from_attoseconds(b),
+            );
+            check_parts(x, a);
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+
+            let sum = x.checked_add(y);
+            assert_eq!(sum, FineDuration::checked_from_attoseconds(a + b));
+            if let Some(sum) = sum {
+                check_parts(sum, a + b);
+            }
+            let difference = x.checked_sub(y);
+            assert_eq!(difference, FineDuration::checked_from_attoseconds(a - b));
+            if let Some(difference) = difference {
+                check_parts(difference, a - b);
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_matches_i128() {
+        // Rounds half away from zero on the magnitude.
+        let round = |attos: i128| {
+            let nanos = (attos.abs() + ATTOS_PER_NANO / 2) / ATTOS_PER_NANO;
+            if attos < 0 {
+                -nanos
+            } else {
+                nanos
+            }
+        };
+        let mut rng = fastrand::Rng::with_seed(0x2077);
+        let range = -10 * ATTOS_PER_SEC..10 * ATTOS_PER_SEC;
+        let ties = [
+            -ATTOS_PER_NANO / 2,
+            ATTOS_PER_NANO / 2,
+            -3 * ATTOS_PER_NANO / 2,
+        ];
+        let attos = ties
+            .into_iter()
+            .chain((0..10_000).map(|_| rng.i128(range.clone())));
+        for attos in attos {
+            let duration = FineDuration::from_attoseconds(attos);
+            assert_eq!(duration.round().whole_nanoseconds(), round(attos));
+            assert_eq!(
+                duration.trunc().whole_nanoseconds(),
+                attos.div_euclid(ATTOS_PER_NANO)
+            );
+        }
+    }
+
+    #[test]
+    fn range_of_tai_date_time() {
+        for time in [TaiDateTime::MIN, TaiDateTime::MAX] {
+            let high_res = HighResTai::from(time);
+            assert_eq!(high_res.round(), time);
+            assert_eq!(high_res.trunc(), time);
+            assert_eq!(FineDuration::try_from(time.0), Ok(high_res.since_epoch()));
+        }
+        assert_eq!(
+            HighResTai::from(TaiDateTime::MIN).since_epoch(),
+            FineDuration::new(i64::MIN, 0),
+        );
+        assert_eq!(FineDuration::try_from(Duration::MIN), Err(OutOfRange));
+        assert_eq!(
+            FineDuration::try_from(Duration::MAX).map(FineDuration::trunc),
+            Ok(Duration::MAX),
+        );
+    }
+}
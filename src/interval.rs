@@ -0,0 +1,214 @@
+use core::ops::Range;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::TaiDateTime;
+
+/// A half-open interval of TAI instants, i.e. including the start but not the
+/// end. The end is never before the start, but they may be the same, in which
+/// case the interval is empty.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaiInterval {
+    start: TaiDateTime,
+    end: TaiDateTime,
+}
+
+impl TaiInterval {
+    /// Creates an interval from `start` up to, but not including, `end`.
+    /// Returns `None` if `end` is before `start`.
+    pub fn new(start: TaiDateTime, end: TaiDateTime) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// Creates the interval spanning the same instants as the range of UTC date
+    /// times. Any leap second inserted within the range is part of the
+    /// interval, so a range ending at 2017-01-01 00:00:00 UTC includes
+    /// 2016-12-31 23:59:60 UTC. Returns `None` if the end of the range is
+    /// before its start.
+    pub fn from_utc_range(range: Range<OffsetDateTime>) -> Option<Self> {
+        Self::new(range.start.into(), range.end.into())
+    }
+
+    /// The first instant within the interval.
+    pub fn start(&self) -> TaiDateTime {
+        self.start
+    }
+
+    /// The first instant after the interval.
+    pub fn end(&self) -> TaiDateTime {
+        self.end
+    }
+
+    /// The time between the start and the end, including any leap seconds.
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Returns whether the interval doesn't contain any instants.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns whether the instant is within the interval.
+    pub fn contains(&self, time: TaiDateTime) -> bool {
+        self.start <= time && time < self.end
+    }
+
+    /// Returns the instants within both intervals, or `None` if there are
+    /// none.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start < end).then_some(Self { start, end })
+    }
+
+    /// Returns the instants within either interval, if they overlap or one ends
+    /// right where the other one starts. Otherwise there would be a gap, so
+    /// `None` is returned.
+    pub fn union_if_contiguous(&self, other: &Self) -> Option<Self> {
+        (self.start <= other.end && other.start <= self.end).then(|| Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+
+    /// Splits the interval into the part before `time` and the part from
+    /// `time` on. Returns `None` if `time` is outside of the interval, though
+    /// it may be its end.
+    pub fn split_at(&self, time: TaiDateTime) -> Option<(Self, Self)> {
+        (self.start <= time && time <= self.end).then_some((
+            Self {
+                start: self.start,
+                end: time,
+            },
+            Self {
+                start: time,
+                end: self.end,
+            },
+        ))
+    }
+
+    /// Splits the interval at each UTC midnight within it. A full UTC day lasts
+    /// 86400 seconds, 86401 seconds if a leap second gets inserted at its end,
+    /// or 86399 seconds if one gets removed. The first and the last part may be
+    /// shorter, as they are cut off by the start and the end of the interval.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interval reaches outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn iter_days_utc(&self) -> impl Iterator<Item = Self> {
+        let end = self.end;
+        let mut start = self.start;
+        core::iter::from_fn(move || {
+            if start >= end {
+                return None;
+            }
+            let day = Self {
+                start,
+                end: start.next_utc_midnight().min(end),
+            };
+            start = day.end;
+            Some(day)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn utc(utc: OffsetDateTime) -> TaiDateTime {
+        utc.into()
+    }
+
+    #[test]
+    fn across_leap_second() {
+        let midnight = utc(datetime!(2017-01-01 0:00 UTC));
+        let leap_second = midnight - Duration::SECOND;
+        let last_minute = TaiInterval::from_utc_range(
+            datetime!(2016-12-31 23:59 UTC)..datetime!(2017-01-01 0:00 UTC),
+        )
+        .unwrap();
+        assert_eq!(last_minute.duration(), Duration::seconds(61));
+        assert!(last_minute.contains(leap_second));
+        assert!(last_minute.contains(midnight - Duration::NANOSECOND));
+        assert!(!last_minute.contains(midnight));
+
+        let around = TaiInterval::from_utc_range(
+            datetime!(2016-12-31 23:59:59 UTC)..datetime!(2017-01-01 0:00:01 UTC),
+        )
+        .unwrap();
+        assert_eq!(around.duration(), Duration::seconds(3));
+
+        // Only the leap second and the second before it are in both.
+        let both = last_minute.intersect(&around).unwrap();
+        assert_eq!(both.start(), leap_second - Duration::SECOND);
+        assert_eq!(both.end(), midnight);
+        assert_eq!(both.duration(), Duration::seconds(2));
+
+        let either = last_minute.union_if_contiguous(&around).unwrap();
+        assert_eq!(either.start(), last_minute.start());
+        assert_eq!(either.end(), around.end());
+        assert_eq!(either.duration(), Duration::seconds(62));
+
+        let (before, after) = last_minute.split_at(leap_second).unwrap();
+        assert_eq!(before.duration(), Duration::seconds(60));
+        assert_eq!(after, TaiInterval::new(leap_second, midnight).unwrap());
+        assert_eq!(before.union_if_contiguous(&after), Some(last_minute));
+        assert_eq!(before.intersect(&after), None);
+
+        // The leap second on its own is a gap between the two.
+        let next_minute = TaiInterval::new(midnight, midnight + Duration::MINUTE).unwrap();
+        assert_eq!(before.union_if_contiguous(&next_minute), None);
+        assert_eq!(last_minute.split_at(midnight + Duration::SECOND), None);
+        assert!(last_minute.split_at(midnight).unwrap().1.is_empty());
+
+        assert_eq!(
+            TaiInterval::from_utc_range(
+                datetime!(2017-01-01 0:00 UTC)..datetime!(2016-12-31 23:59:59 UTC),
+            ),
+            None,
+        );
+    }
+
+    #[test]
+    fn days_across_leap_second() {
+        let interval = TaiInterval::from_utc_range(
+            datetime!(2016-12-30 12:00 UTC)..datetime!(2017-01-01 12:00 UTC),
+        )
+        .unwrap();
+        let mut days = interval.iter_days_utc();
+        let expected = [
+            (datetime!(2016-12-30 12:00 UTC), 43_200),
+            (datetime!(2016-12-31 0:00 UTC), 86_401),
+            (datetime!(2017-01-01 0:00 UTC), 43_200),
+        ];
+        for (start, seconds) in expected {
+            let day = days.next().unwrap();
+            assert_eq!(day.start(), utc(start));
+            assert_eq!(day.duration(), Duration::seconds(seconds));
+        }
+        assert_eq!(days.next(), None);
+
+        // An interval of whole days without a leap second.
+        let interval = TaiInterval::from_utc_range(
+            datetime!(2016-12-29 0:00 UTC)..datetime!(2016-12-31 0:00 UTC),
+        )
+        .unwrap();
+        assert!(interval
+            .iter_days_utc()
+            .map(|day| day.duration())
+            .eq([Duration::DAY; 2]));
+
+        // An interval within a single day is a single part, an empty one has
+        // none.
+        let midnight = utc(datetime!(2017-01-01 0:00 UTC));
+        let leap_second = TaiInterval::new(midnight - Duration::SECOND, midnight).unwrap();
+        assert!(leap_second.iter_days_utc().eq([leap_second]));
+        let empty = TaiInterval::new(midnight, midnight).unwrap();
+        assert_eq!(empty.iter_days_utc().next(), None);
+    }
+}
@@ -0,0 +1,74 @@
+use ::jiff::Timestamp;
+use time::Duration;
+
+use crate::{floor_seconds, leap_seconds_at_tai, leap_seconds_at_utc, TaiDateTime};
+
+/// Converts a UTC timestamp to TAI, just like the conversion from an
+/// [`OffsetDateTime`](time::OffsetDateTime), so the results are the same to the
+/// nanosecond. The conversion goes through the seconds and nanoseconds of the
+/// timestamp and never panics.
+impl From<Timestamp> for TaiDateTime {
+    fn from(time: Timestamp) -> Self {
+        let unix_time_stamp = Duration::new(time.as_second(), time.subsec_nanosecond());
+        let diff = leap_seconds_at_utc(floor_seconds(unix_time_stamp));
+        Self(unix_time_stamp + Duration::seconds(diff))
+    }
+}
+
+/// Converts to a UTC timestamp, just like the conversion to an
+/// [`OffsetDateTime`](time::OffsetDateTime). An instant inside of an inserted
+/// leap second maps to the same time of day in the second after it.
+///
+/// # Panics
+///
+/// Panics if the result is outside of the range of [`Timestamp`], i.e. before
+/// -009999-01-02 01:59:59 UTC or after 9999-12-30 22:00:00 UTC.
+impl From<TaiDateTime> for Timestamp {
+    fn from(time: TaiDateTime) -> Self {
+        let diff = leap_seconds_at_tai(floor_seconds(time.0));
+        let unix_time_stamp = time.0 - Duration::seconds(diff);
+        Timestamp::new(
+            unix_time_stamp.whole_seconds(),
+            unix_time_stamp.subsec_nanoseconds(),
+        )
+        .expect("instant out of range for a jiff timestamp")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, OffsetDateTime};
+
+    use super::*;
+
+    const LEAP_SECONDS: [OffsetDateTime; 4] = [
+        datetime!(1972-07-01 0:00 UTC),
+        datetime!(1990-01-01 0:00 UTC),
+        datetime!(2009-01-01 0:00 UTC),
+        datetime!(2017-01-01 0:00 UTC),
+    ];
+
+    #[test]
+    fn matches_offset_date_time() {
+        for leap_second in LEAP_SECONDS {
+            for millis in [-1500, -1000, -1, 0, 1, 999, 1000, 1500] {
+                let utc = leap_second + Duration::milliseconds(millis);
+                let timestamp =
+                    Timestamp::new(utc.unix_timestamp(), utc.nanosecond() as i32).unwrap();
+                let time = TaiDateTime::from(utc);
+                assert_eq!(TaiDateTime::from(timestamp), time, "{utc}");
+                assert_eq!(Timestamp::from(time), timestamp, "{utc}");
+            }
+
+            // Inside of the leap second both map to the second after it.
+            let start = TaiDateTime::from(leap_second) - Duration::seconds(1);
+            for millis in [0, 500, 999] {
+                let time = start + Duration::milliseconds(millis);
+                let timestamp = Timestamp::from(time);
+                let utc = OffsetDateTime::from(time);
+                assert_eq!(timestamp.as_second(), utc.unix_timestamp());
+                assert_eq!(timestamp.subsec_nanosecond(), utc.nanosecond() as i32);
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use core::{fmt, str::FromStr};
+
+use time::Duration;
+
+use crate::TaiDateTime;
+
+// Flips the sign bit of the seconds, so negative seconds sort before positive
+// ones when compared as unsigned big endian bytes.
+const SIGN_BIT: u64 = 1 << 63;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// The reason a sortable key could not be decoded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KeyError {
+    /// The text key doesn't consist of exactly 24 characters.
+    InvalidLength,
+    /// The text key contains a character that isn't a lowercase hexadecimal
+    /// digit.
+    InvalidDigit,
+    /// The nanoseconds are not within `0..1_000_000_000`.
+    InvalidNanoseconds,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => f.write_str("the key doesn't have 24 characters"),
+            Self::InvalidDigit => f.write_str("the key contains an invalid character"),
+            Self::InvalidNanoseconds => f.write_str("the nanoseconds of the key are out of range"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyError {}
+
+impl TaiDateTime {
+    /// Encodes the instant as 12 bytes that sort lexicographically in the same
+    /// order as the instants themselves, including the ones before 1970, which
+    /// makes them suitable as keys in databases.
+    ///
+    /// The first 8 bytes are the whole TAI seconds since 1970-01-01 00:00:00
+    /// TAI as a big endian integer with the sign bit flipped, and the last 4
+    /// bytes are the nanoseconds within that second as a big endian integer.
+    /// Just like with [`as_timespec`](Self::as_timespec), the seconds are
+    /// rounded down, so the nanoseconds are never negative. This encoding is
+    /// stable and will never change.
+    pub fn to_sortable_key(&self) -> [u8; 12] {
+        let (secs, nanos) = self.as_timespec();
+        let mut key = [0; 12];
+        key[..8].copy_from_slice(&(secs as u64 ^ SIGN_BIT).to_be_bytes());
+        key[8..].copy_from_slice(&(nanos as u32).to_be_bytes());
+        key
+    }
+
+    /// Decodes an instant encoded by [`to_sortable_key`](Self::to_sortable_key).
+    pub fn from_sortable_key(key: &[u8; 12]) -> Result<Self, KeyError> {
+        let (secs, nanos) = key.split_at(8);
+        let secs = u64::from_be_bytes(secs.try_into().unwrap()) ^ SIGN_BIT;
+        let nanos = u32::from_be_bytes(nanos.try_into().unwrap());
+        if nanos >= 1_000_000_000 {
+            return Err(KeyError::InvalidNanoseconds);
+        }
+        Ok(Self(Duration::new(secs as i64, nanos as i32)))
+    }
+
+    /// Encodes the instant as 24 lowercase hexadecimal digits of its
+    /// [sortable key](Self::to_sortable_key), for systems that need text keys.
+    /// These sort lexicographically in the same order as the instants. The key
+    /// can be decoded again with [`FromStr`].
+    #[cfg(feature = "alloc")]
+    pub fn to_key_string(&self) -> alloc::string::String {
+        self.to_sortable_key()
+            .iter()
+            .flat_map(|&b| {
+                [
+                    HEX_DIGITS[(b >> 4) as usize],
+                    HEX_DIGITS[(b & 0xF) as usize],
+                ]
+            })
+            .map(char::from)
+            .collect()
+    }
+}
+
+/// Decodes a text key encoded by [`to_key_string`](TaiDateTime::to_key_string).
+/// Use [`parse_auto`](TaiDateTime::parse_auto) to parse a date time instead.
+impl FromStr for TaiDateTime {
+    type Err = KeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 24 {
+            return Err(KeyError::InvalidLength);
+        }
+        let mut key = [0; 12];
+        for (byte, digits) in key.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+            let mut value = 0;
+            for &digit in digits {
+                let nibble = HEX_DIGITS
+                    .iter()
+                    .position(|&d| d == digit)
+                    .ok_or(KeyError::InvalidDigit)?;
+                value = value << 4 | nibble as u8;
+            }
+            *byte = value;
+        }
+        Self::from_sortable_key(&key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant(secs: i64, nanos: i32) -> TaiDateTime {
+        TaiDateTime(Duration::new(secs, nanos))
+    }
+
+    fn random_instant(rng: &mut fastrand::Rng) -> TaiDateTime {
+        match rng.u8(0..3) {
+            0 => instant(rng.i64(..), rng.i32(0..1_000_000_000)),
+            // Close to the epoch, so the signs of the seconds differ.
+            1 => instant(rng.i64(-3..=3), rng.i32(0..1_000_000_000)),
+            // Seconds or nanoseconds that are equal.
+            _ => instant(rng.i64(-3..=3), rng.i32(0..3)),
+        }
+    }
+
+    // Pins the encoding, which must never change.
+    const KEYS: &[(i64, i32, &str)] = &[
+        (i64::MIN, 0, "000000000000000000000000"),
+        (-2_208_988_800, 123_456_789, "7fffffff7c558180075bcd15"),
+        (-1, 250_000_000, "7fffffffffffffff0ee6b280"),
+        (0, 0, "800000000000000000000000"),
+        (0, 1, "800000000000000000000001"),
+        (1_483_228_836, 0, "80000000586846a400000000"),
+        (i64::MAX, 999_999_999, "ffffffffffffffff3b9ac9ff"),
+    ];
+
+    #[test]
+    fn stable_encoding() {
+        assert_eq!(instant(i64::MIN, 0), TaiDateTime::MIN);
+        assert_eq!(instant(i64::MAX, 999_999_999), TaiDateTime::MAX);
+        // Half a second before the epoch.
+        assert_eq!(
+            instant(-1, 500_000_000),
+            TaiDateTime(-Duration::milliseconds(500))
+        );
+
+        for &(secs, nanos, text) in KEYS {
+            let tai = instant(secs, nanos);
+            let key = tai.to_sortable_key();
+            assert_eq!(key[..8], (secs as u64 ^ SIGN_BIT).to_be_bytes());
+            assert_eq!(key[8..], (nanos as u32).to_be_bytes());
+            assert_eq!(TaiDateTime::from_sortable_key(&key), Ok(tai));
+            assert_eq!(text.parse(), Ok(tai));
+            #[cfg(feature = "alloc")]
+            assert_eq!(tai.to_key_string(), text);
+        }
+    }
+
+    #[test]
+    fn order_agrees() {
+        let mut rng = fastrand::Rng::with_seed(0x6e75);
+        for _ in 0..10_000 {
+            let (a, b) = (random_instant(&mut rng), random_instant(&mut rng));
+            let (x, y) = (a.to_sortable_key(), b.to_sortable_key());
+            assert_eq!(x.cmp(&y), a.cmp(&b), "{a:?} {b:?}");
+            assert_eq!(TaiDateTime::from_sortable_key(&x), Ok(a));
+            #[cfg(feature = "alloc")]
+            {
+                let (x, y) = (a.to_key_string(), b.to_key_string());
+                assert_eq!(x.cmp(&y), a.cmp(&b), "{a:?} {b:?}");
+                assert_eq!(x.parse(), Ok(a));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let mut key = TaiDateTime::MAX.to_sortable_key();
+        key[8..].copy_from_slice(&1_000_000_000u32.to_be_bytes());
+        assert_eq!(
+            TaiDateTime::from_sortable_key(&key),
+            Err(KeyError::InvalidNanoseconds),
+        );
+
+        for (text, error) in [
+            ("", KeyError::InvalidLength),
+            ("80000000000000000000000", KeyError::InvalidLength),
+            ("8000000000000000000000000", KeyError::InvalidLength),
+            ("80000000000000000000000A", KeyError::InvalidDigit),
+            ("80000000000000000000000g", KeyError::InvalidDigit),
+            ("8000000000000000000000+1", KeyError::InvalidDigit),
+            ("ffffffffffffffff3b9aca00", KeyError::InvalidNanoseconds),
+        ] {
+            assert_eq!(text.parse::<TaiDateTime>(), Err(error), "{text}");
+        }
+    }
+}
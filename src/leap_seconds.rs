@@ -0,0 +1,597 @@
+//! Queries about the leap seconds themselves, rather than about converting
+//! individual time stamps.
+
+use time::{Duration, OffsetDateTime};
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+mod bulletin;
+#[cfg(feature = "alloc")]
+mod info;
+mod metadata;
+#[cfg(all(feature = "std", any(windows, unix)))]
+mod report;
+#[cfg(feature = "alloc")]
+mod snapshot;
+#[cfg(feature = "alloc")]
+mod source;
+#[cfg(feature = "alloc")]
+mod table;
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub use self::bulletin::*;
+#[cfg(feature = "alloc")]
+pub use self::info::*;
+pub use self::metadata::*;
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub use self::report::*;
+#[cfg(feature = "alloc")]
+pub use self::snapshot::*;
+#[cfg(feature = "alloc")]
+pub use self::source::*;
+#[cfg(feature = "alloc")]
+pub use self::table::*;
+
+use crate::{
+    diff_before, floor_seconds, leap_seconds_at_tai, leap_table, leap_table_expiry,
+    leap_table_source, TaiDateTime, TaiUtcOffset,
+};
+
+/// Where the leap seconds used for conversions come from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LeapSource {
+    /// Only the table built into the crate is used. Either there are no
+    /// sources on this target or looking for them is [turned
+    /// off](Discovery::BuiltinOnly), or they didn't know about any leap
+    /// seconds the built-in table is missing.
+    BuiltIn,
+    /// The built-in table is merged with the leap seconds stored in the
+    /// Windows registry.
+    WindowsRegistry,
+    /// The built-in table is merged with the leap seconds listed in the tzdb,
+    /// usually `/usr/share/zoneinfo/leapseconds`. The directory named by the
+    /// `TZDIR` environment variable is searched first and `/etc/zoneinfo`
+    /// last, and a `leap-seconds.list` file is used if there is no
+    /// `leapseconds` file next to it.
+    UnixLeapFile,
+    /// None of the sources could be read, so only the built-in table is used,
+    /// which may be out of date.
+    FallbackStale,
+    /// The built-in table is merged with the leap seconds of a source
+    /// registered with [`register_source`] or loaded with
+    /// [`load_leap_seconds_from_bytes`].
+    Custom,
+    /// The table includes leap seconds that were announced at runtime, e.g.
+    /// with [`announce`] or [`apply_bulletin_c`].
+    Announcement,
+}
+
+impl LeapSource {
+    // A short description, e.g. for summaries of the table in use.
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Self::BuiltIn => "built-in table",
+            Self::WindowsRegistry => "Windows registry",
+            Self::UnixLeapFile => "tzdb leapseconds file",
+            Self::FallbackStale => "built-in table, as no source could be read",
+            Self::Custom => "custom source",
+            Self::Announcement => "announcements",
+        }
+    }
+}
+
+/// Returns where the leap seconds used for conversions come from. This loads
+/// the platform specific leap seconds if that didn't happen yet.
+pub fn leap_source() -> LeapSource {
+    leap_table_source()
+}
+
+/// Returns the UTC instant from which on the leap seconds used for conversions
+/// can no longer be relied upon, as a leap second may have been announced
+/// after the table was published. Conversions past this instant keep using
+/// the last known difference between TAI and UTC.
+pub fn expires_at() -> OffsetDateTime {
+    OffsetDateTime::UNIX_EPOCH + Duration::seconds(leap_table_expiry())
+}
+
+/// Returns the difference between TAI and UTC at the given instant, e.g. 37
+/// seconds since 2017. During an inserted leap second it's still the difference
+/// from before it. Past the [expiry](expires_at) of the leap second table it's
+/// the last known difference.
+pub fn tai_utc_offset_at(time: TaiDateTime) -> TaiUtcOffset {
+    TaiUtcOffset::from_whole_seconds(leap_seconds_at_tai(floor_seconds(time.0)))
+}
+
+/// Returns the difference between TAI and UTC [now](TaiDateTime::now), like
+/// [`tai_utc_offset_at`].
+#[cfg(feature = "std")]
+pub fn current_tai_utc_offset() -> TaiUtcOffset {
+    tai_utc_offset_at(TaiDateTime::now())
+}
+
+/// Returns the difference between TAI and UTC in whole seconds at the given
+/// instant, e.g. 37 since 2017, as needed for the `currentUtcOffset` of PTP.
+/// During an inserted leap second it's still the difference from before it.
+/// Past the [expiry](expires_at) of the leap second table it's the last known
+/// difference.
+///
+/// The difference has grown by 27 seconds from 1972 to 2017, so at anything
+/// like that rate it takes tens of thousands of years to get anywhere near
+/// [`i16::MAX`]. If it ever gets there, the result saturates.
+#[deprecated(note = "use `tai_utc_offset_at` instead")]
+pub fn utc_offset_seconds_at(time: TaiDateTime) -> i16 {
+    saturate_to_i16(tai_utc_offset_at(time))
+}
+
+/// Returns the difference between TAI and UTC in whole seconds
+/// [now](TaiDateTime::now), like [`utc_offset_seconds_at`].
+#[cfg(feature = "std")]
+#[deprecated(note = "use `current_tai_utc_offset` instead")]
+pub fn current_utc_offset_seconds() -> i16 {
+    saturate_to_i16(current_tai_utc_offset())
+}
+
+fn saturate_to_i16(offset: TaiUtcOffset) -> i16 {
+    offset
+        .whole_seconds()
+        .clamp(i16::MIN.into(), i16::MAX.into()) as i16
+}
+
+/// Whether a leap second gets inserted into or removed from UTC.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LeapSign {
+    /// A second gets inserted, so the day ends with 23:59:60.
+    Positive,
+    /// A second gets removed, so the day ends with 23:59:58.
+    Negative,
+}
+
+/// A change of the difference between TAI and UTC.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeapEvent {
+    /// The UTC instant at which the new offset takes effect. This is the
+    /// midnight right after the inserted or removed second.
+    pub utc_instant: OffsetDateTime,
+    /// Whether the leap second gets inserted or removed.
+    pub sign: LeapSign,
+    /// The difference between TAI and UTC in seconds from `utc_instant` on.
+    pub tai_offset_after: i64,
+    /// Whether the event was announced at runtime, e.g. with [`announce`],
+    /// rather than coming from the built-in table or a source of leap seconds.
+    pub provisional: bool,
+}
+
+impl LeapEvent {
+    /// The TAI instant at which the new offset takes effect.
+    pub fn tai_instant(&self) -> TaiDateTime {
+        self.utc_instant.into()
+    }
+}
+
+fn events() -> impl Iterator<Item = LeapEvent> {
+    let table = leap_table();
+    (0..table.len()).map(move |index| event_at(&table, table.provisional(), index))
+}
+
+// The leap events of a leap second table, marking the ones at the given UTC
+// time stamps as provisional.
+#[cfg(feature = "alloc")]
+fn events_in<'a>(
+    entries: &'a [(i64, i64)],
+    provisional: &'a [i64],
+) -> impl Iterator<Item = LeapEvent> + 'a {
+    (0..entries.len()).map(move |index| event_at(entries, provisional, index))
+}
+
+// The leap event of the entry at the given index of a leap second table.
+fn event_at(entries: &[(i64, i64)], provisional: &[i64], index: usize) -> LeapEvent {
+    let (t, diff) = entries[index];
+    let sign = if diff < diff_before(entries, index) {
+        LeapSign::Negative
+    } else {
+        LeapSign::Positive
+    };
+    LeapEvent {
+        utc_instant: OffsetDateTime::UNIX_EPOCH + Duration::seconds(t),
+        sign,
+        tai_offset_after: diff,
+        provisional: provisional.contains(&t),
+    }
+}
+
+/// Returns the first leap event that takes effect after the given instant. An
+/// instant inside of an inserted leap second is still before the event.
+pub fn next_after(time: TaiDateTime) -> Option<LeapEvent> {
+    events().find(|event| event.tai_instant() > time)
+}
+
+/// Returns the leap events that take effect after `start` and no later than
+/// `end`, i.e. exactly those that make the difference between TAI and UTC at
+/// `end` differ from the one at `start`. An inserted leap second only takes
+/// effect once it's over, so an interval that ends inside of one doesn't
+/// include it, while an interval that starts inside of one does. The events
+/// come from the same table that is used for conversions, in order.
+pub fn events_between(start: TaiDateTime, end: TaiDateTime) -> impl Iterator<Item = LeapEvent> {
+    events()
+        .skip_while(move |event| event.tai_instant() <= start)
+        .take_while(move |event| event.tai_instant() <= end)
+}
+
+/// Returns the next leap event if it takes effect within the given duration
+/// from now.
+#[cfg(feature = "std")]
+pub fn upcoming(within: Duration) -> Option<LeapEvent> {
+    let now = TaiDateTime::now();
+    next_after(now).filter(|event| event.tai_instant() - now <= within)
+}
+
+/// A summary of upcoming leap second activity, e.g. for monitoring, as returned
+/// by [`upcoming_leap_summary`].
+///
+/// Its [`Display`](core::fmt::Display) implementation is a single line, e.g.
+/// `+1 s leap second in 76 days on 2027-01-01, TAI - UTC = 37 s, leap second
+/// data expires in 254 days`.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LeapSummary {
+    /// The next leap event, if one is scheduled before the
+    /// [expiry](expires_at) of the leap second table.
+    pub next: Option<LeapEvent>,
+    /// The number of whole days until the next leap event takes effect.
+    pub days_until_next: Option<i64>,
+    /// The current difference between TAI and UTC in seconds.
+    pub current_offset: i16,
+    /// The UTC instant at which the leap second table expires.
+    pub expires_at: OffsetDateTime,
+    /// The number of whole days until the leap second table expires, which is
+    /// negative once it has expired.
+    pub days_until_expiry: i64,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for LeapSummary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match (self.next, self.days_until_next) {
+            (Some(next), Some(days)) => {
+                let sign = match next.sign {
+                    LeapSign::Positive => '+',
+                    LeapSign::Negative => '-',
+                };
+                write!(
+                    f,
+                    "{sign}1 s leap second in {days} days on {}, ",
+                    next.utc_instant.date()
+                )?;
+            }
+            _ => f.write_str("no leap second scheduled, ")?,
+        }
+        write!(f, "TAI - UTC = {} s, ", self.current_offset)?;
+        if self.days_until_expiry < 0 {
+            write!(
+                f,
+                "leap second data expired {} days ago",
+                -self.days_until_expiry
+            )
+        } else {
+            write!(
+                f,
+                "leap second data expires in {} days",
+                self.days_until_expiry
+            )
+        }
+    }
+}
+
+/// Returns a summary of upcoming leap second activity as of
+/// [now](TaiDateTime::now), based on the leap seconds used for conversions.
+#[cfg(feature = "std")]
+pub fn upcoming_leap_summary() -> LeapSummary {
+    let now = TaiDateTime::now();
+    let expires_at = expires_at();
+    let next = next_after(now).filter(|event| event.utc_instant < expires_at);
+    LeapSummary {
+        next,
+        days_until_next: next.map(|event| (event.tai_instant() - now).whole_days()),
+        current_offset: saturate_to_i16(tai_utc_offset_at(now)),
+        expires_at,
+        days_until_expiry: (TaiDateTime::from(expires_at) - now).whole_days(),
+    }
+}
+
+/// The handle of a thread started by [`watch`]. Dropping it stops the thread.
+#[cfg(feature = "std")]
+pub struct WatchHandle {
+    stop: Option<std::sync::mpsc::Sender<()>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(feature = "std")]
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        // Disconnecting the channel wakes up the thread.
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// The schedule gets re-evaluated at least this often, so changes to the leap
+// seconds the platform knows about are picked up.
+#[cfg(feature = "std")]
+const RESCHEDULE_INTERVAL: Duration = Duration::minutes(10);
+
+/// Spawns a thread that calls the callback `lead_time` before each upcoming
+/// leap event and once more right after the event took effect. Events that are
+/// already in the past are never reported. If the thread only starts within
+/// `lead_time` of an event, the first call happens immediately.
+#[cfg(feature = "std")]
+pub fn watch(callback: impl Fn(LeapEvent) + Send + 'static, lead_time: Duration) -> WatchHandle {
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    let (stop, stopped) = mpsc::channel::<()>();
+
+    let thread = std::thread::spawn(move || {
+        run_watch(callback, lead_time, |duration| {
+            matches!(
+                stopped.recv_timeout(duration.unsigned_abs()),
+                Ok(()) | Err(RecvTimeoutError::Disconnected)
+            )
+        });
+    });
+
+    WatchHandle {
+        stop: Some(stop),
+        thread: Some(thread),
+    }
+}
+
+// The body of the thread of `watch`, which reads the time with
+// `TaiDateTime::now`. `sleep` sleeps for the given duration and returns whether
+// the watch got cancelled, so the tests can fast-forward a mock clock instead.
+#[cfg(feature = "std")]
+fn run_watch(
+    callback: impl Fn(LeapEvent),
+    lead_time: Duration,
+    mut sleep: impl FnMut(Duration) -> bool,
+) {
+    // Sleeps at most until the next time the schedule needs to be
+    // re-evaluated.
+    let mut sleep = |duration: Duration| sleep(duration.clamp(Duration::ZERO, RESCHEDULE_INTERVAL));
+
+    let mut announced: Option<LeapEvent> = None;
+
+    loop {
+        let now = TaiDateTime::now();
+
+        if let Some(event) = announced {
+            // The announced event may have vanished from the table in the
+            // meantime, in which case it is not reported as having
+            // happened.
+            if !events().any(|e| e == event) {
+                announced = None;
+                continue;
+            }
+            if now >= event.tai_instant() {
+                announced = None;
+                callback(event);
+                continue;
+            }
+            if sleep(event.tai_instant() - now) {
+                return;
+            }
+            continue;
+        }
+
+        let Some(event) = next_after(now) else {
+            if sleep(RESCHEDULE_INTERVAL) {
+                return;
+            }
+            continue;
+        };
+
+        let announce_at = event.tai_instant() - lead_time;
+        if now >= announce_at {
+            announced = Some(event);
+            callback(event);
+        } else if sleep(announce_at - now) {
+            return;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use core::cell::RefCell;
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::{tests::builtin_table_with, MockTaiClock};
+
+    // The built-in table with a leap second at each of the given instants.
+    fn table_with(leap_seconds: &[OffsetDateTime]) -> LeapTable {
+        let leap_seconds: Vec<_> = leap_seconds.iter().copied().zip(38..).collect();
+        builtin_table_with(&leap_seconds, datetime!(2040-01-01 0:00 UTC))
+    }
+
+    // Runs a watch with a lead time of an hour on a mock clock with the given
+    // table, fast-forwarding from `start` relative to `event` to a day after
+    // it. Whenever the clock moves, `refresh` may replace the table. Returns
+    // when and with which events the callback got called.
+    fn run_mock_watch(
+        table: LeapTable,
+        event: TaiDateTime,
+        start: Duration,
+        mut refresh: impl FnMut(TaiDateTime) -> Option<LeapTable>,
+    ) -> Vec<(TaiDateTime, LeapEvent)> {
+        let (start, end) = (event + start, event + Duration::days(1));
+        let clock = RefCell::new(MockTaiClock::new(start).with_table(table));
+        clock.borrow().install();
+
+        let fired = RefCell::new(Vec::new());
+        run_watch(
+            |event| fired.borrow_mut().push((TaiDateTime::now(), event)),
+            Duration::HOUR,
+            |duration| {
+                let now = clock.borrow().now() + duration;
+                clock.borrow().set(now);
+                if let Some(table) = refresh(now) {
+                    // The old clock has to be uninstalled first.
+                    drop(clock.replace(MockTaiClock::new(now)));
+                    clock.replace(MockTaiClock::new(now).with_table(table));
+                    clock.borrow().install();
+                }
+                now >= end
+            },
+        );
+        fired.into_inner()
+    }
+
+    // The TAI instant of a UTC instant after the last built-in leap second and
+    // one more.
+    fn tai_instant(utc: OffsetDateTime) -> TaiDateTime {
+        TaiDateTime(Duration::seconds(utc.unix_timestamp() + 38))
+    }
+
+    fn event(utc_instant: OffsetDateTime, tai_offset_after: i64) -> LeapEvent {
+        LeapEvent {
+            utc_instant,
+            sign: LeapSign::Positive,
+            tai_offset_after,
+            provisional: false,
+        }
+    }
+
+    #[test]
+    fn summary_of_an_injected_leap_second() {
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let expiry = datetime!(2040-01-01 0:00 UTC);
+        let now = datetime!(2029-12-01 0:00 UTC);
+        // Other tests may have added leap seconds to the global table, so this
+        // can't convert with it.
+        let tai_now = TaiDateTime(Duration::seconds(now.unix_timestamp() + 37));
+        let clock = MockTaiClock::new(tai_now).with_table(table_with(&[utc]));
+        clock.install();
+
+        let summary = upcoming_leap_summary();
+        assert_eq!(
+            summary,
+            LeapSummary {
+                next: Some(event(utc, 38)),
+                days_until_next: Some(31),
+                current_offset: 37,
+                expires_at: expiry,
+                days_until_expiry: (expiry - now).whole_days(),
+            },
+        );
+        assert_eq!(
+            summary.to_string(),
+            "+1 s leap second in 31 days on 2030-01-01, TAI - UTC = 37 s, \
+             leap second data expires in 3683 days",
+        );
+
+        // Once it took effect, nothing else is scheduled.
+        clock.set(tai_instant(utc) + Duration::days(1));
+        let summary = upcoming_leap_summary();
+        assert_eq!((summary.next, summary.days_until_next), (None, None));
+        assert_eq!(summary.current_offset, 38);
+        assert_eq!(
+            summary.to_string(),
+            "no leap second scheduled, TAI - UTC = 38 s, \
+             leap second data expires in 3651 days",
+        );
+    }
+
+    #[test]
+    fn watch_fires_before_and_after_an_event() {
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let fired = run_mock_watch(
+            table_with(&[utc]),
+            tai_instant(utc),
+            -Duration::days(1),
+            |_| None,
+        );
+        let tai = tai_instant(utc);
+        assert_eq!(
+            fired,
+            [
+                (tai - Duration::HOUR, event(utc, 38)),
+                (tai, event(utc, 38)),
+            ],
+        );
+    }
+
+    #[test]
+    fn watch_starts_within_lead_time() {
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let start = -Duration::minutes(15);
+        let fired = run_mock_watch(table_with(&[utc]), tai_instant(utc), start, |_| None);
+        let tai = tai_instant(utc);
+        assert_eq!(
+            fired,
+            [(tai + start, event(utc, 38)), (tai, event(utc, 38))],
+        );
+    }
+
+    #[test]
+    fn watch_skips_past_events() {
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let fired = run_mock_watch(
+            table_with(&[utc]),
+            tai_instant(utc),
+            Duration::SECOND,
+            |_| None,
+        );
+        assert_eq!(fired, []);
+    }
+
+    #[test]
+    fn watch_reschedules_when_the_table_changes() {
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let tai = tai_instant(utc);
+
+        // The event vanishes after it got announced, so it's not reported as
+        // having happened.
+        let mut refreshed = false;
+        let fired = run_mock_watch(
+            table_with(&[utc]),
+            tai_instant(utc),
+            -Duration::days(1),
+            |now| {
+                if now > tai - Duration::HOUR && !refreshed {
+                    refreshed = true;
+                    return Some(table_with(&[]));
+                }
+                None
+            },
+        );
+        assert_eq!(fired, [(tai - Duration::HOUR, event(utc, 38))]);
+
+        // An event that shows up before it's due gets announced like any
+        // other.
+        let later = datetime!(2030-01-01 12:00 UTC);
+        let later_tai = tai + Duration::hours(12);
+        let mut refreshed = false;
+        let fired = run_mock_watch(
+            table_with(&[]),
+            tai_instant(utc),
+            -Duration::days(1),
+            |now| {
+                if now > tai && !refreshed {
+                    refreshed = true;
+                    return Some(table_with(&[later]));
+                }
+                None
+            },
+        );
+        assert_eq!(
+            fired,
+            [
+                (later_tai - Duration::HOUR, event(later, 38)),
+                (later_tai, event(later, 38)),
+            ],
+        );
+    }
+}
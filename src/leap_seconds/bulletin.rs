@@ -0,0 +1,411 @@
+use core::fmt;
+
+use time::{Date, Month, OffsetDateTime};
+
+use super::{LeapSign, LeapSource};
+use crate::{diff_before, seek_utc, update_effective_table, EffectiveTable};
+
+/// The reason an IERS Bulletin C could not be parsed or applied to the leap
+/// second table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BulletinError {
+    /// The text doesn't state whether a leap second will be introduced.
+    MissingAnnouncement,
+    /// The month at the end of which the leap second would be introduced is
+    /// malformed.
+    InvalidDate,
+    /// The difference between TAI and UTC stated in the bulletin doesn't match
+    /// the one following from the leap second table and the announcement.
+    OffsetMismatch {
+        /// The difference between TAI and UTC stated in the bulletin.
+        stated: i64,
+        /// The difference between TAI and UTC following from the table.
+        expected: i64,
+    },
+    /// The announcement contradicts the leap seconds already in the table.
+    Conflict,
+}
+
+impl fmt::Display for BulletinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingAnnouncement => {
+                write!(f, "the bulletin doesn't announce whether there is a leap second")
+            }
+            Self::InvalidDate => write!(f, "the date of the announcement is malformed"),
+            Self::OffsetMismatch { stated, expected } => write!(
+                f,
+                "the bulletin states a difference of {stated}s between TAI and UTC, but it is {expected}s"
+            ),
+            Self::Conflict => write!(f, "the announcement conflicts with the leap second table"),
+        }
+    }
+}
+
+impl std::error::Error for BulletinError {}
+
+/// What an IERS Bulletin C announces.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Announcement {
+    /// The first day of the month after the one the announcement is about. If
+    /// a leap second is announced, the new difference between TAI and UTC
+    /// applies from the start of this day on.
+    pub utc_effective: Date,
+    /// Whether a leap second gets inserted or removed, if any.
+    pub sign: Option<LeapSign>,
+    /// The difference between TAI and UTC in seconds from `utc_effective` on,
+    /// if the bulletin states it.
+    pub tai_offset_after: Option<i64>,
+}
+
+fn parse_month(month: &str) -> Option<Month> {
+    Some(match month {
+        "january" => Month::January,
+        "february" => Month::February,
+        "march" => Month::March,
+        "april" => Month::April,
+        "may" => Month::May,
+        "june" => Month::June,
+        "july" => Month::July,
+        "august" => Month::August,
+        "september" => Month::September,
+        "october" => Month::October,
+        "november" => Month::November,
+        "december" => Month::December,
+        _ => return None,
+    })
+}
+
+/// Parses the announcement of an IERS Bulletin C.
+///
+/// Only the standard phrasing of the bulletin is recognized, i.e. either "NO
+/// leap second will be introduced at the end of `<month> <year>`" or "A
+/// positive / negative leap second will be introduced at the end of `<month>
+/// <year>`", optionally followed by the difference between UTC and TAI "until
+/// further notice".
+pub fn parse_bulletin_c(text: &str) -> Result<Announcement, BulletinError> {
+    const STATEMENT: &str = "leap second will be introduced at the end of ";
+
+    let text = text
+        .split_whitespace()
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let (before, after) = text
+        .split_once(STATEMENT)
+        .ok_or(BulletinError::MissingAnnouncement)?;
+    let sign = if before.ends_with("no ") {
+        None
+    } else if before.ends_with("a positive ") {
+        Some(LeapSign::Positive)
+    } else if before.ends_with("a negative ") {
+        Some(LeapSign::Negative)
+    } else {
+        return Err(BulletinError::MissingAnnouncement);
+    };
+
+    let mut words = after.split(' ');
+    let month = words
+        .next()
+        .and_then(parse_month)
+        .ok_or(BulletinError::InvalidDate)?;
+    let year = words
+        .next()
+        .and_then(|year| year.trim_end_matches('.').parse().ok())
+        .ok_or(BulletinError::InvalidDate)?;
+    let utc_effective = Date::from_calendar_date(year, month, 1)
+        .ok()
+        .and_then(|date| date.checked_add(time::Duration::days(31)))
+        .and_then(|date| date.replace_day(1).ok())
+        .ok_or(BulletinError::InvalidDate)?;
+
+    let tai_offset_after = after
+        .split_once("until further notice")
+        .and_then(|(_, rem)| rem.split_once("utc-tai ="))
+        .and_then(|(_, rem)| {
+            let offset: String = rem
+                .chars()
+                .take_while(|c| matches!(c, '+' | '-' | ' ' | '0'..='9'))
+                .filter(|&c| c != ' ')
+                .collect();
+            offset.parse::<i64>().ok()
+        })
+        .map(|utc_minus_tai| -utc_minus_tai);
+
+    Ok(Announcement {
+        utc_effective,
+        sign,
+        tai_offset_after,
+    })
+}
+
+/// The reason a leap second could not be announced.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AnnounceError {
+    /// The leap second would already have happened.
+    InPast,
+    /// The new difference between TAI and UTC would not take effect on the
+    /// first day of a month.
+    NotAtMonthBoundary,
+    /// The leap second would not be at the end of March, June, September or
+    /// December.
+    UnconventionalMonth,
+    /// The announcement contradicts the leap seconds already in the table.
+    Conflict,
+}
+
+impl fmt::Display for AnnounceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InPast => write!(f, "the leap second is in the past"),
+            Self::NotAtMonthBoundary => write!(f, "the leap second is not at the end of a month"),
+            Self::UnconventionalMonth => write!(
+                f,
+                "the leap second is not at the end of March, June, September or December"
+            ),
+            Self::Conflict => write!(f, "the announcement conflicts with the leap second table"),
+        }
+    }
+}
+
+impl std::error::Error for AnnounceError {}
+
+// Adds a provisional leap second to the table, unless the table already has
+// the exact same entry. The table must not have any entries after it.
+fn add_provisional(table: &mut EffectiveTable, time_stamp: i64, offset: i64) -> Result<(), ()> {
+    let index = seek_utc(&table.entries, table.entries.len(), time_stamp - 1);
+    match table.entries.get(index) {
+        Some(&existing) if existing == (time_stamp, offset) => Ok(()),
+        Some(_) => Err(()),
+        None => {
+            table.entries.push((time_stamp, offset));
+            table.provisional.push(time_stamp);
+            table.source = LeapSource::Announcement;
+            Ok(())
+        }
+    }
+}
+
+// The difference between TAI and UTC right before the given UTC time stamp
+// and after a leap second with the given sign at it.
+fn offsets_at(table: &EffectiveTable, time_stamp: i64, sign: Option<LeapSign>) -> (i64, i64) {
+    let index = seek_utc(&table.entries, table.entries.len(), time_stamp - 1);
+    let before = diff_before(&table.entries, index);
+    let after = match sign {
+        None => before,
+        Some(LeapSign::Positive) => before + 1,
+        Some(LeapSign::Negative) => before - 1,
+    };
+    (before, after)
+}
+
+/// Applies the announcement of an IERS Bulletin C to the leap second table used
+/// for conversions. The bulletin gets parsed with [`parse_bulletin_c`].
+///
+/// An announced leap second gets added to the table as a
+/// [provisional](super::LeapEvent::provisional) entry, and either way the table is
+/// considered valid until the end of that month. If the bulletin states the
+/// difference between TAI and UTC "until further notice", it has to match the
+/// one following from the table.
+///
+/// Applying the same bulletin more than once has no further effect.
+pub fn apply_bulletin_c(text: &str) -> Result<(), BulletinError> {
+    let announcement = parse_bulletin_c(text)?;
+    let time_stamp = announcement
+        .utc_effective
+        .midnight()
+        .assume_utc()
+        .unix_timestamp();
+
+    update_effective_table(|table| {
+        let (_, offset) = offsets_at(table, time_stamp, announcement.sign);
+        if let Some(stated) = announcement.tai_offset_after {
+            if stated != offset {
+                return Err(BulletinError::OffsetMismatch {
+                    stated,
+                    expected: offset,
+                });
+            }
+        }
+
+        let index = seek_utc(&table.entries, table.entries.len(), time_stamp - 1);
+        match (announcement.sign, table.entries.get(index)) {
+            (Some(_), _) => {
+                add_provisional(table, time_stamp, offset).map_err(|()| BulletinError::Conflict)?
+            }
+            (None, Some(&(t, _))) if t == time_stamp => return Err(BulletinError::Conflict),
+            // Either an old bulletin, where the table already knows about
+            // later leap seconds, or there's nothing to add.
+            (None, _) => {}
+        }
+        table.expires_at = table.expires_at.max(time_stamp);
+        Ok(())
+    })
+}
+
+/// Adds an announced leap second to the leap second table used for
+/// conversions, as a [provisional](super::LeapEvent::provisional) entry. The new
+/// difference between TAI and UTC takes effect at the start of `utc_effective`,
+/// which has to be in the future and has to be the first day of January,
+/// April, July or October, as leap seconds are only ever scheduled for the end
+/// of March, June, September or December. The table is then considered valid
+/// until six months after `utc_effective`.
+///
+/// Announcing the same leap second more than once has no further effect. Use
+/// [`announce_any_month`] to allow the end of any month.
+pub fn announce(utc_effective: Date, sign: LeapSign) -> Result<(), AnnounceError> {
+    if !matches!(
+        utc_effective.month(),
+        Month::January | Month::April | Month::July | Month::October
+    ) {
+        return Err(AnnounceError::UnconventionalMonth);
+    }
+    announce_any_month(utc_effective, sign)
+}
+
+/// Adds an announced leap second to the leap second table used for
+/// conversions, just like [`announce`], except that the leap second may be at
+/// the end of any month. `utc_effective` still has to be the first day of a
+/// month.
+pub fn announce_any_month(utc_effective: Date, sign: LeapSign) -> Result<(), AnnounceError> {
+    if utc_effective.day() != 1 {
+        return Err(AnnounceError::NotAtMonthBoundary);
+    }
+    let effective = utc_effective.midnight().assume_utc();
+    if effective <= OffsetDateTime::now_utc() {
+        return Err(AnnounceError::InPast);
+    }
+    let time_stamp = effective.unix_timestamp();
+
+    // Six months later, which is when the next leap second could at the
+    // earliest be announced for.
+    let (mut year, mut month) = (utc_effective.year(), utc_effective.month());
+    for _ in 0..6 {
+        if month == Month::December {
+            year += 1;
+        }
+        month = month.next();
+    }
+    let expires_at = Date::from_calendar_date(year, month, 1).map_or(i64::MAX, |date| {
+        date.midnight().assume_utc().unix_timestamp()
+    });
+
+    update_effective_table(|table| {
+        let (_, offset) = offsets_at(table, time_stamp, Some(sign));
+        add_provisional(table, time_stamp, offset).map_err(|()| AnnounceError::Conflict)?;
+        table.expires_at = table.expires_at.max(expires_at);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+    use crate::tests::lock_global_table;
+
+    const BULLETIN_C_65: &str = include_str!("../../tests/data/bulletin-c-65.txt");
+
+    #[test]
+    fn bulletin_c_65() {
+        assert_eq!(
+            parse_bulletin_c(BULLETIN_C_65),
+            Ok(Announcement {
+                utc_effective: date!(2023 - 07 - 01),
+                sign: None,
+                tai_offset_after: Some(37),
+            }),
+        );
+
+        // The stated difference has to match the table. It doesn't here, so
+        // the table stays untouched.
+        let _lock = lock_global_table();
+        let wrong = BULLETIN_C_65.replace("-37 s", "-38 s");
+        assert_eq!(
+            apply_bulletin_c(&wrong),
+            Err(BulletinError::OffsetMismatch {
+                stated: 38,
+                expected: 37,
+            }),
+        );
+    }
+
+    #[test]
+    fn positive_announcement() {
+        let text = "
+            A positive leap second will be introduced at the end of December 2016.
+            The sequence of dates of the UTC second markers will be:
+
+                    2016 December 31,     23h 59m 59s
+                    2016 December 31,     23h 59m 60s
+                    2017 January   1,      0h  0m  0s
+
+            The difference between UTC and the International Atomic Time TAI is:
+
+            from 2015 July 1, 0h UTC, to 2017 January 1 0h UTC   : UTC-TAI = - 36s
+            from 2017 January 1, 0h UTC, until further notice    : UTC-TAI = - 37s
+        ";
+        assert_eq!(
+            parse_bulletin_c(text),
+            Ok(Announcement {
+                utc_effective: date!(2017 - 01 - 01),
+                sign: Some(LeapSign::Positive),
+                tai_offset_after: Some(37),
+            }),
+        );
+
+        // The table already has this leap second, so applying it again has no
+        // effect.
+        let _lock = lock_global_table();
+        let before = crate::effective_table();
+        assert_eq!(apply_bulletin_c(text), Ok(()));
+        assert!(std::sync::Arc::ptr_eq(&before, &crate::effective_table()));
+        let conflicting = text.replace("A positive", "A negative");
+        assert_eq!(
+            apply_bulletin_c(&conflicting),
+            Err(BulletinError::OffsetMismatch {
+                stated: 37,
+                expected: 35,
+            }),
+        );
+        let conflicting = conflicting.replace("- 37s", "- 35s");
+        assert_eq!(apply_bulletin_c(&conflicting), Err(BulletinError::Conflict));
+    }
+
+    #[test]
+    fn negative_announcement() {
+        let text = "A NEGATIVE leap second will be introduced at the end of June 2031.";
+        assert_eq!(
+            parse_bulletin_c(text),
+            Ok(Announcement {
+                utc_effective: date!(2031 - 07 - 01),
+                sign: Some(LeapSign::Negative),
+                tai_offset_after: None,
+            }),
+        );
+    }
+
+    #[test]
+    fn malformed_bulletins() {
+        assert_eq!(
+            parse_bulletin_c("Bulletin C 65"),
+            Err(BulletinError::MissingAnnouncement),
+        );
+        assert_eq!(
+            parse_bulletin_c("Perhaps a leap second will be introduced at the end of June 2023."),
+            Err(BulletinError::MissingAnnouncement),
+        );
+        assert_eq!(
+            parse_bulletin_c("NO leap second will be introduced at the end of Juin 2023."),
+            Err(BulletinError::InvalidDate),
+        );
+        assert_eq!(
+            parse_bulletin_c("NO leap second will be introduced at the end of June."),
+            Err(BulletinError::InvalidDate),
+        );
+    }
+}
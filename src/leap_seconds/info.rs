@@ -0,0 +1,169 @@
+use alloc::string::String;
+use core::fmt;
+
+use time::{Date, Duration, OffsetDateTime};
+
+use super::{expires_at, LeapSource};
+use crate::{leap_table, leap_table_provenance, leap_table_source, EXPIRES_AT_UTC, LEAP_SECONDS};
+
+/// A summary of which leap second data is in use, e.g. for audits, as returned
+/// by [`table_info`]. Unlike a [`snapshot`](super::snapshot), it doesn't list
+/// the entries themselves.
+///
+/// Its [`Display`](fmt::Display) implementation puts each part on a line of
+/// its own, e.g. for dumping it into logs. The alternate form (`{:#}`) is a
+/// compact single line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct TableInfo {
+    /// The version of this crate.
+    pub crate_version: &'static str,
+    /// The number of leap seconds built into the crate.
+    pub builtin_entries: usize,
+    /// The UTC date from whose start on the difference set by the last leap
+    /// second built into the crate applies, i.e. the day after it.
+    pub builtin_last_leap: Date,
+    /// The UTC instant at which the table built into the crate expires.
+    pub builtin_expires_at: OffsetDateTime,
+    /// Where the leap seconds used for conversions come from, like
+    /// [`leap_source`](super::leap_source).
+    pub source: LeapSource,
+    /// Whether any source of leap seconds was loaded successfully, even if it
+    /// didn't know about any leap seconds the built-in table is missing.
+    pub loaded: bool,
+    /// Where the platform specific leap seconds were loaded from, i.e. the
+    /// path of the tzdb `leapseconds` file or the registry key on Windows, if
+    /// they were loaded successfully.
+    pub platform_location: Option<String>,
+    /// The number of leap seconds used for conversions that aren't built into
+    /// the crate, including the provisional ones.
+    pub added_entries: usize,
+    /// The number of leap seconds that were announced at runtime and aren't
+    /// confirmed by any source yet.
+    pub provisional_entries: usize,
+    /// The UTC instant at which the leap seconds used for conversions expire,
+    /// like [`expires_at`](super::expires_at).
+    pub expires_at: OffsetDateTime,
+}
+
+/// Returns a summary of which leap second data is in use. It reflects the
+/// leap seconds loaded from the sources, the ones
+/// [loaded](super::load_leap_seconds_from_bytes) and
+/// [announced](super::announce) since, and a [mock
+/// table](crate::MockTaiClock::with_table). This loads the platform specific
+/// leap seconds if that didn't happen yet.
+pub fn table_info() -> TableInfo {
+    let entries = leap_table();
+    let (loaded, platform_location) = leap_table_provenance();
+    let (last_time_stamp, _) = LEAP_SECONDS[LEAP_SECONDS.len() - 1];
+    TableInfo {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        builtin_entries: LEAP_SECONDS.len(),
+        builtin_last_leap: (OffsetDateTime::UNIX_EPOCH + Duration::seconds(last_time_stamp)).date(),
+        builtin_expires_at: OffsetDateTime::UNIX_EPOCH + Duration::seconds(EXPIRES_AT_UTC),
+        source: leap_table_source(),
+        loaded,
+        platform_location,
+        added_entries: entries
+            .iter()
+            .filter(|entry| !LEAP_SECONDS.contains(entry))
+            .count(),
+        provisional_entries: entries.provisional().len(),
+        expires_at: expires_at(),
+    }
+}
+
+impl fmt::Display for TableInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if f.alternate() { "; " } else { "\n" };
+        write!(f, "tai-stuff {}{separator}", self.crate_version)?;
+        write!(
+            f,
+            "built-in: {} leap seconds, the last one effective {}, expires on {}{separator}",
+            self.builtin_entries,
+            self.builtin_last_leap,
+            self.builtin_expires_at.date(),
+        )?;
+        match (&self.platform_location, self.loaded) {
+            (Some(location), _) => write!(f, "loaded: {location}")?,
+            (None, true) => f.write_str("loaded: custom sources only")?,
+            (None, false) => f.write_str("loaded: nothing")?,
+        }
+        write!(
+            f,
+            "{separator}in use: {}, {} added, {} provisional, expires on {}",
+            self.source.description(),
+            self.added_entries,
+            self.provisional_entries,
+            self.expires_at.date(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString};
+
+    use time::macros::{date, datetime};
+
+    use super::*;
+
+    #[test]
+    fn display() {
+        let info = TableInfo {
+            crate_version: "1.2.3",
+            builtin_entries: 27,
+            builtin_last_leap: date!(2017 - 01 - 01),
+            builtin_expires_at: datetime!(2023-06-28 0:00 UTC),
+            source: LeapSource::UnixLeapFile,
+            loaded: true,
+            platform_location: Some("/usr/share/zoneinfo/leapseconds".into()),
+            added_entries: 1,
+            provisional_entries: 0,
+            expires_at: datetime!(2027-06-28 0:00 UTC),
+        };
+        assert_eq!(
+            info.to_string(),
+            "tai-stuff 1.2.3\n\
+             built-in: 27 leap seconds, the last one effective 2017-01-01, expires on 2023-06-28\n\
+             loaded: /usr/share/zoneinfo/leapseconds\n\
+             in use: tzdb leapseconds file, 1 added, 0 provisional, expires on 2027-06-28",
+        );
+        assert_eq!(
+            format!("{info:#}"),
+            "tai-stuff 1.2.3; \
+             built-in: 27 leap seconds, the last one effective 2017-01-01, expires on 2023-06-28; \
+             loaded: /usr/share/zoneinfo/leapseconds; \
+             in use: tzdb leapseconds file, 1 added, 0 provisional, expires on 2027-06-28",
+        );
+    }
+
+    #[test]
+    fn builtin_numbers() {
+        let info = table_info();
+        assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.builtin_entries, 27);
+        assert_eq!(info.builtin_last_leap, date!(2017 - 01 - 01));
+        assert_eq!(info.builtin_expires_at, datetime!(2023-06-28 0:00 UTC));
+        assert!(info.expires_at >= info.builtin_expires_at);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn reflects_loaded_tables() {
+        use crate::{
+            tests::{lock_global_table, table_with_next_leap_second},
+            TaiDateTime,
+        };
+
+        let _lock = lock_global_table();
+        let before = table_info();
+        let table = table_with_next_leap_second();
+        TaiDateTime::set_leap_seconds(&table).unwrap();
+        let after = table_info();
+        assert_eq!(after.added_entries, before.added_entries + 1);
+        assert_eq!(after.source, LeapSource::Custom);
+        assert!(after.loaded);
+        assert_eq!(after.expires_at, table.expires_at());
+    }
+}
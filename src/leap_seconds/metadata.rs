@@ -0,0 +1,204 @@
+use core::fmt;
+
+use time::{macros::date, Date};
+
+use super::LeapEvent;
+use crate::LEAP_SECONDS;
+
+/// Background information about one of the leap seconds built into the crate.
+/// Only information that is known for sure is filled in. Leap seconds that
+/// were loaded at runtime, e.g. from the operating system, have none.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LeapMeta {
+    /// The UTC date from whose start on the new difference between TAI and
+    /// UTC applies, i.e. the day after the leap second.
+    pub utc_effective: Date,
+    /// The number of the IERS Bulletin C that announced the leap second.
+    pub bulletin_c: Option<u16>,
+    /// The date the announcement was published.
+    pub announced: Option<Date>,
+    /// UT1 - UTC right before the leap second, in milliseconds.
+    pub dut1_millis: Option<i16>,
+}
+
+impl LeapMeta {
+    const fn new(utc_effective: Date) -> Self {
+        Self {
+            utc_effective,
+            bulletin_c: None,
+            announced: None,
+            dut1_millis: None,
+        }
+    }
+
+    const fn bulletin(self, number: u16, announced: Date) -> Self {
+        Self {
+            announced: Some(announced),
+            ..self.bulletin_number(number)
+        }
+    }
+
+    const fn bulletin_number(self, number: u16) -> Self {
+        Self {
+            bulletin_c: Some(number),
+            ..self
+        }
+    }
+
+    /// Whether anything besides the date is known.
+    pub fn is_known(&self) -> bool {
+        self.bulletin_c.is_some() || self.announced.is_some() || self.dut1_millis.is_some()
+    }
+}
+
+/// Formats the information known besides the date, e.g. `announced in IERS
+/// Bulletin C 52 on 2016-07-06`, or `nothing known` if there is none.
+impl fmt::Display for LeapMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.bulletin_c, self.announced) {
+            (Some(number), Some(date)) => {
+                write!(f, "announced in IERS Bulletin C {number} on {date}")?
+            }
+            (Some(number), None) => write!(f, "announced in IERS Bulletin C {number}")?,
+            (None, Some(date)) => write!(f, "announced on {date}")?,
+            (None, None) if self.dut1_millis.is_none() => f.write_str("nothing known")?,
+            (None, None) => {}
+        }
+        if let Some(dut1) = self.dut1_millis {
+            if self.bulletin_c.is_some() || self.announced.is_some() {
+                f.write_str(", ")?;
+            }
+            write!(f, "UT1 - UTC = {dut1} ms")?;
+        }
+        Ok(())
+    }
+}
+
+/// Background information about the leap seconds built into the crate, in
+/// the same order. The initial difference of 10 seconds in 1972 is not an
+/// entry of its own.
+pub const LEAP_SECOND_METADATA: &[LeapMeta] = &[
+    LeapMeta::new(date!(1972 - 07 - 01)),
+    LeapMeta::new(date!(1973 - 01 - 01)),
+    LeapMeta::new(date!(1974 - 01 - 01)),
+    LeapMeta::new(date!(1975 - 01 - 01)),
+    LeapMeta::new(date!(1976 - 01 - 01)),
+    LeapMeta::new(date!(1977 - 01 - 01)),
+    LeapMeta::new(date!(1978 - 01 - 01)),
+    LeapMeta::new(date!(1979 - 01 - 01)),
+    LeapMeta::new(date!(1980 - 01 - 01)),
+    LeapMeta::new(date!(1981 - 07 - 01)),
+    LeapMeta::new(date!(1982 - 07 - 01)),
+    LeapMeta::new(date!(1983 - 07 - 01)),
+    LeapMeta::new(date!(1985 - 07 - 01)),
+    LeapMeta::new(date!(1988 - 01 - 01)),
+    LeapMeta::new(date!(1990 - 01 - 01)),
+    LeapMeta::new(date!(1991 - 01 - 01)),
+    LeapMeta::new(date!(1992 - 07 - 01)),
+    LeapMeta::new(date!(1993 - 07 - 01)),
+    LeapMeta::new(date!(1994 - 07 - 01)),
+    LeapMeta::new(date!(1996 - 01 - 01)).bulletin_number(10),
+    LeapMeta::new(date!(1997 - 07 - 01)).bulletin_number(13),
+    LeapMeta::new(date!(1999 - 01 - 01)).bulletin_number(16),
+    LeapMeta::new(date!(2006 - 01 - 01)).bulletin(30, date!(2005 - 07 - 04)),
+    LeapMeta::new(date!(2009 - 01 - 01)).bulletin(36, date!(2008 - 07 - 04)),
+    LeapMeta::new(date!(2012 - 07 - 01)).bulletin(43, date!(2012 - 01 - 05)),
+    LeapMeta::new(date!(2015 - 07 - 01)).bulletin(49, date!(2015 - 01 - 05)),
+    LeapMeta::new(date!(2017 - 01 - 01)).bulletin(52, date!(2016 - 07 - 06)),
+];
+
+// The metadata has to line up with the built-in table.
+const _: () = {
+    assert!(
+        LEAP_SECOND_METADATA.len() == LEAP_SECONDS.len(),
+        "leap second metadata is out of sync with the table"
+    );
+    let mut i = 0;
+    while i < LEAP_SECONDS.len() {
+        let effective = LEAP_SECOND_METADATA[i].utc_effective;
+        assert!(
+            effective.midnight().assume_utc().unix_timestamp() == LEAP_SECONDS[i].0,
+            "leap second metadata is out of sync with the table"
+        );
+        i += 1;
+    }
+};
+
+/// Returns the background information about the leap second, if it's one of
+/// the leap seconds built into the crate.
+pub fn metadata_for(event: &LeapEvent) -> Option<&'static LeapMeta> {
+    let time_stamp = event.utc_instant.unix_timestamp();
+    let index = LEAP_SECONDS
+        .binary_search_by_key(&time_stamp, |&(t, _)| t)
+        .ok()?;
+    if LEAP_SECONDS[index].1 != event.tai_offset_after {
+        return None;
+    }
+    LEAP_SECOND_METADATA.get(index)
+}
+
+impl LeapEvent {
+    /// Returns the background information about the leap second, if it's one
+    /// of the leap seconds built into the crate. See [`metadata_for`].
+    pub fn metadata(&self) -> Option<&'static LeapMeta> {
+        metadata_for(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_sync_with_table() {
+        assert_eq!(LEAP_SECOND_METADATA.len(), LEAP_SECONDS.len());
+        for (meta, &(time_stamp, _)) in LEAP_SECOND_METADATA.iter().zip(LEAP_SECONDS) {
+            assert_eq!(
+                meta.utc_effective.midnight().assume_utc().unix_timestamp(),
+                time_stamp,
+            );
+        }
+    }
+
+    #[test]
+    fn bulletins_in_order() {
+        // Bulletin C is published every six months and announces a leap second
+        // about six months ahead.
+        let mut previous: Option<(u16, Date)> = None;
+        for meta in LEAP_SECOND_METADATA {
+            if let Some(announced) = meta.announced {
+                let lead = meta.utc_effective - announced;
+                assert!(
+                    lead.whole_days() > 150 && lead.whole_days() < 200,
+                    "{meta:?}"
+                );
+            }
+            let Some(number) = meta.bulletin_c else {
+                continue;
+            };
+            if let Some((previous_number, previous_date)) = previous {
+                let half_years = (meta.utc_effective - previous_date).whole_days() / 182;
+                assert_eq!(i64::from(number - previous_number), half_years, "{meta:?}");
+            }
+            previous = Some((number, meta.utc_effective));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn display() {
+        use alloc::string::ToString;
+
+        let last = LEAP_SECOND_METADATA.last().unwrap();
+        assert_eq!(
+            last.to_string(),
+            "announced in IERS Bulletin C 52 on 2016-07-06",
+        );
+        assert_eq!(LEAP_SECOND_METADATA[0].to_string(), "nothing known");
+        assert_eq!(
+            LEAP_SECOND_METADATA[21].to_string(),
+            "announced in IERS Bulletin C 16",
+        );
+    }
+}
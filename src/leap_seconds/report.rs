@@ -0,0 +1,254 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{leap_load_hook, LeapLoadError, LeapSource, SourceError};
+
+/// A problem found while loading the platform specific leap seconds. Loading
+/// is lenient, so the affected data is skipped and loading carries on, unless
+/// the problem prevents it entirely.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LoadWarning {
+    /// A `Leap` line of the tzdb `leapseconds` file was skipped, as one of its
+    /// fields is malformed.
+    MalformedLine {
+        /// The (1-based) number of the line.
+        line: usize,
+        /// The malformed field, one of `year`, `month`, `day`, `date`, `time`
+        /// and `correction`. The time of an inserted leap second has to be
+        /// `hh:59:60` and the one of a removed leap second `hh:59:59`.
+        field: &'static str,
+    },
+    /// The `#expires` line of the tzdb `leapseconds` file is malformed, so it
+    /// was ignored.
+    MalformedExpiry {
+        /// The (1-based) number of the line.
+        line: usize,
+    },
+    /// A `Leap` line of the tzdb `leapseconds` file is valid, but its leap
+    /// second isn't in the last second of a month. It's used anyway.
+    NonStandardLine {
+        /// The (1-based) number of the line.
+        line: usize,
+    },
+    /// The tzdb `leapseconds` file isn't empty, but doesn't contain a single
+    /// valid `Leap` line, so it's most likely in a different format. Loading
+    /// it fails with [`SourceError::Invalid`] rather than yielding no leap
+    /// seconds.
+    NoEntries,
+    /// A registry function failed while reading the leap seconds from the
+    /// Windows registry.
+    RegistryStatus {
+        /// The name of the function.
+        function: &'static str,
+        /// The status code it returned.
+        status: u32,
+    },
+    /// The size of the registry value with the leap seconds is not a multiple
+    /// of the size of an entry.
+    RegistrySize {
+        /// The size of the value in bytes.
+        size: usize,
+        /// The size of an entry in bytes.
+        entry_size: usize,
+    },
+    /// The size of the registry value with the leap seconds changed between
+    /// querying it and reading it.
+    RegistrySizeChanged {
+        /// The size in bytes when it was queried.
+        before: usize,
+        /// The size in bytes when it was read.
+        after: usize,
+    },
+    /// An entry in the Windows registry was skipped, as it's not a valid date.
+    InvalidRegistryEntry {
+        /// The (0-based) index of the entry.
+        index: usize,
+    },
+    /// An entry in the Windows registry is valid, but its leap second isn't in
+    /// the last second of a month. It's used anyway.
+    NonStandardRegistryEntry {
+        /// The (0-based) index of the entry.
+        index: usize,
+    },
+    /// The leap seconds of a source were dropped together with its expiry, as
+    /// they disagree with the built-in table or a source loaded before it, or
+    /// merging them would make the table invalid.
+    ConflictingSource {
+        /// The kind of source whose leap seconds were dropped.
+        leap_source: LeapSource,
+    },
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedLine { line, field } => write!(
+                f,
+                "line {line} of the leapseconds file was skipped, as its {field} is malformed"
+            ),
+            Self::MalformedExpiry { line } => write!(
+                f,
+                "the expiry on line {line} of the leapseconds file is malformed"
+            ),
+            Self::NonStandardLine { line } => write!(
+                f,
+                "the leap second on line {line} of the leapseconds file isn't at the end of a \
+                 month, but was used anyway"
+            ),
+            Self::NoEntries => f.write_str(
+                "the leapseconds file doesn't contain any valid leap seconds, so it's probably in \
+                 a different format",
+            ),
+            Self::RegistryStatus { function, status } => write!(
+                f,
+                "{function} failed with status {status} while reading the leap seconds from the \
+                 registry"
+            ),
+            Self::RegistrySize { size, entry_size } => write!(
+                f,
+                "the leap seconds in the registry take up {size} bytes, which is not a multiple \
+                 of the {entry_size} bytes of an entry"
+            ),
+            Self::RegistrySizeChanged { before, after } => write!(
+                f,
+                "the leap seconds in the registry changed from {before} to {after} bytes while \
+                 reading them"
+            ),
+            Self::InvalidRegistryEntry { index } => write!(
+                f,
+                "entry {index} of the leap seconds in the registry was skipped, as it's not a \
+                 valid date"
+            ),
+            Self::NonStandardRegistryEntry { index } => write!(
+                f,
+                "entry {index} of the leap seconds in the registry isn't at the end of a month, \
+                 but was used anyway"
+            ),
+            Self::ConflictingSource { leap_source } => write!(
+                f,
+                "the leap seconds of the {} were dropped, as they conflict with the ones loaded \
+                 before them",
+                leap_source.description(),
+            ),
+        }
+    }
+}
+
+/// The outcome of loading the platform specific leap seconds, i.e. the tzdb
+/// `leapseconds` file on Unix and the registry on Windows.
+///
+/// Its [`Display`](fmt::Display) implementation lists the outcome and all
+/// warnings, one per line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LoadReport {
+    /// The platform specific source that was loaded.
+    pub leap_source: LeapSource,
+    /// The number of leap seconds that were loaded, or why loading failed.
+    pub outcome: Result<usize, SourceError>,
+    /// The problems found while loading, in the order they were found.
+    pub warnings: Vec<LoadWarning>,
+}
+
+impl LoadReport {
+    /// Whether loading failed or produced any warnings.
+    pub fn has_problems(&self) -> bool {
+        self.outcome.is_err() || !self.warnings.is_empty()
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self.leap_source {
+            LeapSource::WindowsRegistry => "the Windows registry",
+            LeapSource::UnixLeapFile => "the tzdb leapseconds file",
+            _ => "the platform",
+        };
+        match self.outcome {
+            Ok(1) => write!(f, "loaded 1 leap second from {source}")?,
+            Ok(count) => write!(f, "loaded {count} leap seconds from {source}")?,
+            Err(error) => write!(f, "failed to load the leap seconds from {source}: {error}")?,
+        }
+        match self.warnings.len() {
+            0 => {}
+            1 => f.write_str(", with 1 warning:")?,
+            count => write!(f, ", with {count} warnings:")?,
+        }
+        for warning in &self.warnings {
+            write!(f, "\n- {warning}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LoadReport {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.outcome.as_ref().err().map(|error| error as _)
+    }
+}
+
+static LOAD_REPORT: std::sync::Mutex<Option<LoadReport>> = std::sync::Mutex::new(None);
+
+static WARNING_HANDLER: std::sync::Mutex<fn(&LoadWarning)> = std::sync::Mutex::new(|_| {});
+
+// Keeps the report of loading the platform specific leap seconds around for
+// `last_load_report`.
+pub(crate) fn record_load_report(report: &LoadReport) {
+    *LOAD_REPORT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(report.clone());
+}
+
+// Calls the warning handler for each warning and then the load hook with the
+// outcome. This happens once the leap second table is built, so both of them
+// can do conversions themselves.
+pub(crate) fn notify_load(report: &LoadReport) {
+    let handler = *WARNING_HANDLER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    report.warnings.iter().for_each(handler);
+
+    leap_load_hook()(report.outcome.map(drop).map_err(|error| LeapLoadError {
+        leap_source: report.leap_source,
+        error,
+    }));
+}
+
+/// Returns the report of loading the platform specific leap seconds, or `None`
+/// if the leap second table wasn't built yet or there is no platform specific
+/// source, i.e. on Windows without the `windows` feature, with the
+/// `no-os-leap-data` feature or with [`Discovery::BuiltinOnly`](super::Discovery::BuiltinOnly).
+pub fn last_load_report() -> Option<LoadReport> {
+    LOAD_REPORT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Sets a function that gets called with each warning produced while loading
+/// the platform specific leap seconds, e.g. to log them. By default nothing
+/// happens.
+///
+/// Just like the hook set with [`set_leap_load_hook`](super::set_leap_load_hook),
+/// it's called right after the leap second table got built before the first
+/// conversion that needs it, so it has to be set before that.
+pub fn set_load_warning_handler(f: fn(&LoadWarning)) {
+    *WARNING_HANDLER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = f;
+}
+
+/// Builds the leap second table if that didn't happen yet and fails with the
+/// report of loading the platform specific leap seconds if that failed or
+/// produced any warnings.
+///
+/// The table itself is built leniently regardless, i.e. malformed data is
+/// skipped, so this allows an application to refuse to run on broken system
+/// data rather than degrading silently.
+pub fn strict_load() -> Result<(), LoadReport> {
+    crate::leap_table();
+    match last_load_report() {
+        Some(report) if report.has_problems() => Err(report),
+        _ => Ok(()),
+    }
+}
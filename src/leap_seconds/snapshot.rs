@@ -0,0 +1,932 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Write};
+
+use time::{Duration, OffsetDateTime};
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+use super::{check_entries, LeapLoadError, LeapSource, SourceError};
+use super::{events, expires_at, LeapEvent, LeapSign};
+use crate::{sha1::Sha1, FIRST_LEAP_SECONDS_DIFF, LEAP_BASE_OFFSET};
+#[cfg(all(feature = "std", any(windows, unix)))]
+use crate::{update_effective_table, EffectiveTable};
+
+/// A copy of a leap second table, either the one currently used for
+/// conversions or one parsed from a `leap-seconds.list` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableSnapshot {
+    /// The changes of the difference between TAI and UTC, in chronological
+    /// order. The initial difference of 10 seconds on 1 Jan 1972 is not an
+    /// entry of its own.
+    pub entries: Vec<LeapEvent>,
+    /// The instant from which on the table can no longer be relied upon, if
+    /// known.
+    pub expires_at: Option<OffsetDateTime>,
+    /// The instant at which the table was last updated, if known.
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// Lists the entries one per line, with the date from which on the new
+/// difference between TAI and UTC applies and what's known about the leap
+/// seconds built into the crate, followed by the expiry, e.g.
+/// `2017-01-01: +1 s, TAI - UTC = 37 s (announced in IERS Bulletin C 52 on
+/// 2016-07-06)`.
+impl fmt::Display for TableSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.entries {
+            let sign = match event.sign {
+                LeapSign::Positive => '+',
+                LeapSign::Negative => '-',
+            };
+            write!(
+                f,
+                "{}: {sign}1 s, TAI - UTC = {} s",
+                event.utc_instant.date(),
+                event.tai_offset_after
+            )?;
+            if event.provisional {
+                f.write_str(", provisional")?;
+            }
+            if let Some(metadata) = event.metadata().filter(|m| m.is_known()) {
+                write!(f, " ({metadata})")?;
+            }
+            f.write_str("\n")?;
+        }
+        match self.expires_at {
+            Some(expires_at) => write!(f, "expires on {}", expires_at.date()),
+            None => f.write_str("expiry unknown"),
+        }
+    }
+}
+
+/// Returns a copy of the leap second table currently used for conversions.
+pub fn snapshot() -> TableSnapshot {
+    TableSnapshot {
+        entries: events().collect(),
+        expires_at: Some(expires_at()),
+        updated_at: None,
+    }
+}
+
+/// The reason a `leap-seconds.list` file could not be parsed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ListParseError {
+    /// The line with the given (1-based) number is malformed.
+    InvalidLine {
+        /// The number of the line.
+        line: usize,
+    },
+    /// The entry on the line with the given (1-based) number is not after the
+    /// previous one.
+    NotSorted {
+        /// The number of the line.
+        line: usize,
+    },
+    /// The SHA-1 checksum on the `#h` line doesn't match the contents.
+    ChecksumMismatch,
+}
+
+impl fmt::Display for ListParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLine { line } => write!(f, "line {line} is malformed"),
+            Self::NotSorted { line } => {
+                write!(f, "the entry on line {line} is not after the previous one")
+            }
+            Self::ChecksumMismatch => write!(f, "the checksum doesn't match the contents"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ListParseError {}
+
+// The NTP time stamp, i.e. the seconds since 1900-01-01 00:00:00 UTC, of the
+// given UTC instant.
+fn to_ntp(time: OffsetDateTime) -> i64 {
+    time.unix_timestamp() + LEAP_BASE_OFFSET
+}
+
+fn from_ntp(time_stamp: &str) -> Option<OffsetDateTime> {
+    let time_stamp: i64 = time_stamp.parse().ok()?;
+    OffsetDateTime::UNIX_EPOCH.checked_add(Duration::seconds(time_stamp - LEAP_BASE_OFFSET))
+}
+
+impl TableSnapshot {
+    /// Parses a table in the format of the IETF / IANA `leap-seconds.list`
+    /// file. The SHA-1 checksum on the `#h` line is verified if there is one.
+    pub fn parse_leap_seconds_list(text: &str) -> Result<Self, ListParseError> {
+        let mut snapshot = Self {
+            entries: Vec::new(),
+            expires_at: None,
+            updated_at: None,
+        };
+        let mut checksum = None;
+        let mut hasher = Sha1::new();
+        let mut previous = FIRST_LEAP_SECONDS_DIFF;
+
+        for (index, line) in text.lines().enumerate() {
+            let invalid = ListParseError::InvalidLine { line: index + 1 };
+
+            if let Some(rem) = line.strip_prefix("#$") {
+                let rem = rem.trim();
+                snapshot.updated_at = Some(from_ntp(rem).ok_or(invalid)?);
+                hasher.update(rem.as_bytes());
+            } else if let Some(rem) = line.strip_prefix("#@") {
+                let rem = rem.trim();
+                snapshot.expires_at = Some(from_ntp(rem).ok_or(invalid)?);
+                hasher.update(rem.as_bytes());
+            } else if let Some(rem) = line.strip_prefix("#h") {
+                let mut words = [0; 5];
+                let mut parts = rem.split_whitespace();
+                for word in &mut words {
+                    let part = parts.next().ok_or(invalid)?;
+                    *word = u32::from_str_radix(part, 16).map_err(|_| invalid)?;
+                }
+                if parts.next().is_some() {
+                    return Err(invalid);
+                }
+                checksum = Some(words);
+            } else if !line.starts_with('#') {
+                let data = line.split_once('#').map_or(line, |(data, _)| data);
+                let mut parts = data.split_whitespace();
+                let Some(time_stamp) = parts.next() else {
+                    continue;
+                };
+                let offset = parts.next().ok_or(invalid)?;
+                if parts.next().is_some() {
+                    return Err(invalid);
+                }
+
+                let utc_instant = from_ntp(time_stamp).ok_or(invalid)?;
+                let diff: i64 = offset.parse().map_err(|_| invalid)?;
+                hasher.update(time_stamp.as_bytes());
+                hasher.update(offset.as_bytes());
+
+                if snapshot
+                    .entries
+                    .last()
+                    .is_some_and(|last| last.utc_instant >= utc_instant)
+                {
+                    return Err(ListParseError::NotSorted { line: index + 1 });
+                }
+
+                // The list starts with the initial difference in 1972, which
+                // isn't a change of the difference.
+                if diff != previous {
+                    snapshot.entries.push(LeapEvent {
+                        utc_instant,
+                        sign: if diff < previous {
+                            LeapSign::Negative
+                        } else {
+                            LeapSign::Positive
+                        },
+                        tai_offset_after: diff,
+                        provisional: false,
+                    });
+                    previous = diff;
+                }
+            }
+        }
+
+        if let Some(checksum) = checksum {
+            if hasher.finish() != checksum {
+                return Err(ListParseError::ChecksumMismatch);
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Writes the table in the format of the IETF / IANA `leap-seconds.list`
+    /// file, including the `#$` line with the update time and the `#@` line
+    /// with the expiry if they are known, and the `#h` line with the SHA-1
+    /// checksum. Parsing the result with
+    /// [`parse_leap_seconds_list`](Self::parse_leap_seconds_list) results in
+    /// the same table, except that no entry is
+    /// [provisional](LeapEvent::provisional) anymore, as the format has no way
+    /// of marking them. The instants are truncated to whole seconds.
+    pub fn to_leap_seconds_list(&self) -> String {
+        let mut hasher = Sha1::new();
+        let mut out = String::from("#\tleap-seconds.list exported by tai-stuff\n#\n");
+        // Writing to a `String` can't fail.
+        if let Some(updated_at) = self.updated_at {
+            let updated_at = to_ntp(updated_at).to_string();
+            hasher.update(updated_at.as_bytes());
+            let _ = writeln!(out, "#$\t{updated_at}");
+        }
+        if let Some(expires_at) = self.expires_at {
+            let expires_at = to_ntp(expires_at).to_string();
+            hasher.update(expires_at.as_bytes());
+            let _ = writeln!(out, "#@\t{expires_at}");
+        }
+        out.push_str("#\n");
+
+        let utc_era_start = OffsetDateTime::UNIX_EPOCH + Duration::seconds(crate::UTC_ERA_START);
+        let first = (utc_era_start, FIRST_LEAP_SECONDS_DIFF);
+        let entries = self
+            .entries
+            .iter()
+            .map(|event| (event.utc_instant, event.tai_offset_after));
+        for (utc_instant, diff) in core::iter::once(first).chain(entries) {
+            let time_stamp = to_ntp(utc_instant).to_string();
+            let diff = diff.to_string();
+            hasher.update(time_stamp.as_bytes());
+            hasher.update(diff.as_bytes());
+            let date = utc_instant.date();
+            let month = &MONTH_ABBREVIATIONS[date.month() as usize - 1];
+            let _ = writeln!(
+                out,
+                "{time_stamp}\t{diff}\t# {} {month} {}",
+                date.day(),
+                date.year()
+            );
+        }
+
+        let [a, b, c, d, e] = hasher.finish();
+        let _ = writeln!(out, "#\n#h\t{a:08x} {b:08x} {c:08x} {d:08x} {e:08x}");
+        out
+    }
+
+    /// Encodes the entries as the leap second records of a TZif file as
+    /// specified by RFC 8536, with 64-bit occurrences as used from version 2
+    /// on, e.g. to build a `right/` time zone. Each record is 12 bytes, the
+    /// occurrence as a big endian `i64` followed by the correction as a big
+    /// endian `i32`, i.e. the number of leap seconds so far. The occurrences
+    /// count the earlier leap seconds, just like the ones written by `zic`. The
+    /// header has to state the number of records, which is the number of
+    /// entries. No record for the expiry is added.
+    pub fn to_tzif_leap_block(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.entries.len() * 12);
+        let mut correction = 0;
+        for event in &self.entries {
+            let mut occurrence = event.utc_instant.unix_timestamp() + correction;
+            if event.sign == LeapSign::Negative {
+                // The removed second is the one right before the new offset
+                // applies, rather than one that gets inserted there.
+                occurrence -= 1;
+            }
+            correction = event.tai_offset_after - FIRST_LEAP_SECONDS_DIFF;
+            out.extend_from_slice(&occurrence.to_be_bytes());
+            out.extend_from_slice(&(correction as i32).to_be_bytes());
+        }
+        out
+    }
+}
+
+const MONTH_ABBREVIATIONS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Parses a table in the format of the IETF / IANA `leap-seconds.list` file
+/// from memory, e.g. one embedded into the binary with `include_bytes!`, and
+/// merges it into the leap second table used for conversions, without touching
+/// the file system. The SHA-1 checksum on the `#h` line is verified if there is
+/// one.
+///
+/// Leap seconds the table doesn't know about yet get added and the table is
+/// considered valid until the later of its current expiry and the one of the
+/// list. Leap seconds that were [announced](super::announce) and are listed as
+/// well are no longer [provisional](LeapEvent::provisional). The table is only
+/// changed if the list is valid, agrees with the table on the difference
+/// between TAI and UTC at every leap second both know about and the merged
+/// table still changes that difference by exactly one second at each leap
+/// second, otherwise this fails with [`SourceError::Invalid`]. Use
+/// [`TableSnapshot::parse_leap_seconds_list`] to find out what's wrong with the
+/// list.
+///
+/// Loading the same list more than once has no further effect, and doesn't
+/// replace the table either. A table that got replaced is freed once no
+/// conversion uses it anymore.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub fn load_leap_seconds_from_bytes(data: &[u8]) -> Result<(), LeapLoadError> {
+    let invalid = LeapLoadError {
+        leap_source: LeapSource::Custom,
+        error: SourceError::Invalid,
+    };
+    let text = core::str::from_utf8(data).map_err(|_| invalid)?;
+    let list = TableSnapshot::parse_leap_seconds_list(text).map_err(|_| invalid)?;
+
+    let entries = list
+        .entries
+        .iter()
+        .map(|event| (event.utc_instant.unix_timestamp(), event.tai_offset_after));
+    let expires_at = list.expires_at.map(OffsetDateTime::unix_timestamp);
+    update_effective_table(|table| merge_custom(table, entries, expires_at).map_err(|()| invalid))
+}
+
+// Merges leap seconds provided by the application into the effective table,
+// unless they disagree with it at any leap second both know about or the merged
+// table would be invalid, e.g. because the difference between TAI and UTC
+// doesn't change by exactly one second at each entry anymore.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub(super) fn merge_custom(
+    table: &mut EffectiveTable,
+    entries: impl IntoIterator<Item = (i64, i64)>,
+    expires_at: Option<i64>,
+) -> Result<(), ()> {
+    for (time_stamp, diff) in entries {
+        match table.entries.binary_search_by_key(&time_stamp, |&(t, _)| t) {
+            Ok(index) if table.entries[index].1 != diff => return Err(()),
+            Ok(_) => table.provisional.retain(|&t| t != time_stamp),
+            Err(index) => {
+                table.entries.insert(index, (time_stamp, diff));
+                table.source = LeapSource::Custom;
+            }
+        }
+    }
+    check_entries(&table.entries).map_err(|_| ())?;
+    if let Some(expires_at) = expires_at {
+        table.expires_at = table.expires_at.max(expires_at);
+    }
+    table.loaded = true;
+    Ok(())
+}
+
+/// A difference between the leap second table currently used for conversions
+/// and another one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// The candidate is missing a leap second that the current table has.
+    MissingFromCandidate(LeapEvent),
+    /// The current table is missing a leap second that the candidate has.
+    MissingFromCurrent(LeapEvent),
+    /// Both tables change the difference between TAI and UTC at the same
+    /// instant, but to different values.
+    ConflictingOffset {
+        /// The instant at which both tables change the difference.
+        utc_instant: OffsetDateTime,
+        /// The difference according to the current table.
+        current: i64,
+        /// The difference according to the candidate.
+        candidate: i64,
+    },
+    /// The tables expire at different instants.
+    DifferingExpiry {
+        /// The expiry of the current table.
+        current: Option<OffsetDateTime>,
+        /// The expiry of the candidate.
+        candidate: Option<OffsetDateTime>,
+    },
+}
+
+/// Compares the leap second table currently used for conversions with the
+/// given candidate, for example one parsed from a freshly downloaded
+/// `leap-seconds.list`.
+pub fn validate_against(parsed: &TableSnapshot) -> Vec<Discrepancy> {
+    compare(&snapshot(), parsed)
+}
+
+// Lists the differences between the two tables, in order of the instants of
+// the leap seconds and with the expiry last.
+fn compare(current: &TableSnapshot, parsed: &TableSnapshot) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+
+    let (mut ours, mut theirs) = (
+        current.entries.iter().peekable(),
+        parsed.entries.iter().peekable(),
+    );
+    loop {
+        match (ours.peek(), theirs.peek()) {
+            (Some(a), Some(b)) if a.utc_instant == b.utc_instant => {
+                if a.tai_offset_after != b.tai_offset_after {
+                    discrepancies.push(Discrepancy::ConflictingOffset {
+                        utc_instant: a.utc_instant,
+                        current: a.tai_offset_after,
+                        candidate: b.tai_offset_after,
+                    });
+                }
+                ours.next();
+                theirs.next();
+            }
+            (Some(&&a), Some(b)) if a.utc_instant < b.utc_instant => {
+                discrepancies.push(Discrepancy::MissingFromCandidate(a));
+                ours.next();
+            }
+            (_, Some(&&b)) => {
+                discrepancies.push(Discrepancy::MissingFromCurrent(b));
+                theirs.next();
+            }
+            (Some(&&a), None) => {
+                discrepancies.push(Discrepancy::MissingFromCandidate(a));
+                ours.next();
+            }
+            (None, None) => break,
+        }
+    }
+
+    if current.expires_at != parsed.expires_at {
+        discrepancies.push(Discrepancy::DifferingExpiry {
+            current: current.expires_at,
+            candidate: parsed.expires_at,
+        });
+    }
+
+    discrepancies
+}
+
+/// The URL [`fetch_ietf_list`] and [`fetch_leap_seconds`] download from by
+/// default.
+#[cfg(feature = "fetch")]
+pub const DEFAULT_LEAP_SECONDS_LIST_URL: &str =
+    "https://data.iana.org/time-zones/tzdb/leap-seconds.list";
+
+/// The reason the `leap-seconds.list` could not be fetched.
+#[cfg(feature = "fetch")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FetchError {
+    /// The HTTP request failed.
+    Http(Box<ureq::Error>),
+    /// Reading the response failed.
+    Io(std::io::Error),
+    /// The response is not a valid `leap-seconds.list`.
+    Parse(ListParseError),
+    /// The response doesn't contain a checksum, so its integrity can't be
+    /// verified.
+    MissingChecksum,
+}
+
+#[cfg(feature = "fetch")]
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "the HTTP request failed: {e}"),
+            Self::Io(e) => write!(f, "reading the response failed: {e}"),
+            Self::Parse(e) => write!(f, "the response is invalid: {e}"),
+            Self::MissingChecksum => write!(f, "the response doesn't contain a checksum"),
+        }
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            Self::MissingChecksum => None,
+        }
+    }
+}
+
+/// Downloads and parses a `leap-seconds.list`, by default from
+/// [`DEFAULT_LEAP_SECONDS_LIST_URL`]. The list must contain a checksum, which
+/// gets verified. This never happens implicitly, conversions only ever use the
+/// built-in and platform specific leap seconds.
+#[cfg(feature = "fetch")]
+pub fn fetch_ietf_list(url: Option<&str>) -> Result<TableSnapshot, FetchError> {
+    parse_checksummed(&download(url)?)
+}
+
+/// Downloads and parses a `leap-seconds.list`, by default from
+/// [`DEFAULT_LEAP_SECONDS_LIST_URL`]. Unlike [`fetch_ietf_list`], the checksum
+/// is only verified if there is one. The table is returned as pairs of the UNIX
+/// time stamp from which on a difference between TAI and UTC applies and that
+/// difference in seconds, leaving out the initial 10 seconds of 1972.
+#[cfg(feature = "fetch")]
+pub fn fetch_leap_seconds(url: Option<&str>) -> Result<Vec<(i64, i64)>, FetchError> {
+    let snapshot =
+        TableSnapshot::parse_leap_seconds_list(&download(url)?).map_err(FetchError::Parse)?;
+    Ok(snapshot
+        .entries
+        .iter()
+        .map(|event| (event.utc_instant.unix_timestamp(), event.tai_offset_after))
+        .collect())
+}
+
+#[cfg(feature = "fetch")]
+fn download(url: Option<&str>) -> Result<String, FetchError> {
+    ureq::get(url.unwrap_or(DEFAULT_LEAP_SECONDS_LIST_URL))
+        .call()
+        .map_err(|e| FetchError::Http(Box::new(e)))?
+        .into_string()
+        .map_err(FetchError::Io)
+}
+
+#[cfg(feature = "fetch")]
+fn parse_checksummed(text: &str) -> Result<TableSnapshot, FetchError> {
+    if !text.lines().any(|line| line.starts_with("#h")) {
+        return Err(FetchError::MissingChecksum);
+    }
+    TableSnapshot::parse_leap_seconds_list(text).map_err(FetchError::Parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::LEAP_SECONDS;
+
+    // A copy of the `leap-seconds.list` of the tzdb, which expires on
+    // 2026-06-28.
+    const LEAP_SECONDS_LIST: &str = include_str!("../../tests/data/leap-seconds.list");
+
+    // A copy of the `right/UTC` time zone of the tzdb, compiled by `zic`.
+    const RIGHT_UTC: &[u8] = include_bytes!("../../tests/data/right-UTC");
+
+    // The leap second records of the 64-bit data block of a TZif file.
+    fn tzif_leap_block(tzif: &[u8]) -> &[u8] {
+        let counts = |header: &[u8]| -> [usize; 6] {
+            core::array::from_fn(|i| {
+                u32::from_be_bytes(header[20 + 4 * i..24 + 4 * i].try_into().unwrap()) as usize
+            })
+        };
+        // The counts are `isutcnt`, `isstdcnt`, `leapcnt`, `timecnt`,
+        // `typecnt` and `charcnt`.
+        let [isut, isstd, leap, time, types, chars] = counts(tzif);
+        let v2 = 44 + time * 5 + types * 6 + chars + leap * 8 + isstd + isut;
+        let [_, _, leap, time, types, chars] = counts(&tzif[v2..]);
+        let start = v2 + 44 + time * 9 + types * 6 + chars;
+        &tzif[start..start + leap * 12]
+    }
+
+    #[test]
+    fn fixture_round_trip() {
+        let snapshot = TableSnapshot::parse_leap_seconds_list(LEAP_SECONDS_LIST).unwrap();
+        assert_eq!(snapshot.expires_at, Some(datetime!(2026-06-28 0:00 UTC)));
+        assert!(snapshot.updated_at.is_some());
+        let entries: Vec<_> = snapshot
+            .entries
+            .iter()
+            .map(|event| (event.utc_instant.unix_timestamp(), event.tai_offset_after))
+            .collect();
+        assert_eq!(entries, LEAP_SECONDS);
+
+        let list = snapshot.to_leap_seconds_list();
+        assert_eq!(TableSnapshot::parse_leap_seconds_list(&list), Ok(snapshot));
+        // The checksum only covers the data, not the comments, so it's the
+        // same as the one of the original list.
+        let checksum = |list: &str| {
+            let line = list.lines().find(|line| line.starts_with("#h")).unwrap();
+            line[2..].split_whitespace().collect::<Vec<_>>().join(" ")
+        };
+        assert_eq!(checksum(&list), checksum(LEAP_SECONDS_LIST));
+
+        let tampered = list.replace("3692217600\t37", "3692217600\t38");
+        assert_ne!(tampered, list);
+        assert_eq!(
+            TableSnapshot::parse_leap_seconds_list(&tampered),
+            Err(ListParseError::ChecksumMismatch),
+        );
+    }
+
+    #[test]
+    fn tzif_leap_block_matches_zic() {
+        let snapshot = TableSnapshot::parse_leap_seconds_list(LEAP_SECONDS_LIST).unwrap();
+        let block = snapshot.to_tzif_leap_block();
+        assert_eq!(block.len(), 27 * 12);
+        assert_eq!(block, tzif_leap_block(RIGHT_UTC));
+    }
+
+    #[test]
+    fn tzif_negative_leap_second() {
+        let snapshot = TableSnapshot {
+            entries: alloc::vec![
+                LeapEvent {
+                    utc_instant: datetime!(1972-07-01 0:00 UTC),
+                    sign: LeapSign::Positive,
+                    tai_offset_after: 11,
+                    provisional: false,
+                },
+                LeapEvent {
+                    utc_instant: datetime!(1973-01-01 0:00 UTC),
+                    sign: LeapSign::Negative,
+                    tai_offset_after: 10,
+                    provisional: false,
+                },
+            ],
+            expires_at: None,
+            updated_at: None,
+        };
+        let block = snapshot.to_tzif_leap_block();
+        let records: Vec<_> = block
+            .chunks(12)
+            .map(|record| {
+                (
+                    i64::from_be_bytes(record[..8].try_into().unwrap()),
+                    i32::from_be_bytes(record[8..].try_into().unwrap()),
+                )
+            })
+            .collect();
+        // The removed second is 1972-12-31 23:59:59 UTC, which is one second
+        // later on the `right/` scale due to the earlier leap second.
+        let removed = datetime!(1972-12-31 23:59:59 UTC).unix_timestamp() + 1;
+        assert_eq!(
+            records,
+            [
+                (datetime!(1972-07-01 0:00 UTC).unix_timestamp(), 1),
+                (removed, 0),
+            ],
+        );
+
+        let list = snapshot.to_leap_seconds_list();
+        assert_eq!(TableSnapshot::parse_leap_seconds_list(&list), Ok(snapshot));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn load_from_bytes() {
+        use std::sync::Arc;
+
+        use crate::{
+            effective_table,
+            tests::{lock_global_table, table_with_next_leap_second},
+            TaiDateTime, EXPIRES_AT_UTC,
+        };
+
+        let _lock = lock_global_table();
+        let table = table_with_next_leap_second();
+        let list = TableSnapshot {
+            entries: table.iter().collect(),
+            expires_at: Some(table.expires_at()),
+            updated_at: None,
+        }
+        .to_leap_seconds_list();
+        let &(t, diff) = table.entries().last().unwrap();
+        assert!(t > EXPIRES_AT_UTC);
+
+        assert_eq!(load_leap_seconds_from_bytes(list.as_bytes()), Ok(()));
+        let effective = OffsetDateTime::UNIX_EPOCH + Duration::seconds(t);
+        assert_eq!(
+            TaiDateTime::from(effective) - TaiDateTime::from_utc_with_offset(effective, 0),
+            Duration::seconds(diff),
+        );
+        assert_eq!(
+            TaiDateTime::from(effective - Duration::SECOND)
+                - TaiDateTime::from_utc_with_offset(effective - Duration::SECOND, 0),
+            Duration::seconds(diff - 1),
+        );
+        assert_eq!(expires_at(), table.expires_at());
+
+        // Loading it again leaves the table as it is.
+        let current = effective_table();
+        assert_eq!(load_leap_seconds_from_bytes(list.as_bytes()), Ok(()));
+        assert!(Arc::ptr_eq(&current, &effective_table()));
+
+        let invalid = Err(LeapLoadError {
+            leap_source: LeapSource::Custom,
+            error: SourceError::Invalid,
+        });
+        let tampered = list.replace(&format!("\t{diff}\t"), &format!("\t{}\t", diff + 2));
+        assert_eq!(load_leap_seconds_from_bytes(tampered.as_bytes()), invalid);
+        assert_eq!(load_leap_seconds_from_bytes(&[0xff, 0xfe]), invalid);
+        assert!(Arc::ptr_eq(&current, &effective_table()));
+    }
+
+    // The fixture and a copy of it that the given function modified.
+    fn fixture_and(modify: impl FnOnce(&mut TableSnapshot)) -> (TableSnapshot, TableSnapshot) {
+        let current = TableSnapshot::parse_leap_seconds_list(LEAP_SECONDS_LIST).unwrap();
+        let mut candidate = current.clone();
+        modify(&mut candidate);
+        (current, candidate)
+    }
+
+    #[test]
+    fn compare_identical() {
+        let (current, candidate) = fixture_and(|_| {});
+        assert_eq!(compare(&current, &candidate), []);
+    }
+
+    #[test]
+    fn compare_missing_entries() {
+        let (current, candidate) = fixture_and(|candidate| {
+            candidate.entries.pop();
+        });
+        let last = *current.entries.last().unwrap();
+        assert_eq!(
+            compare(&current, &candidate),
+            [Discrepancy::MissingFromCandidate(last)],
+        );
+        assert_eq!(
+            compare(&candidate, &current),
+            [Discrepancy::MissingFromCurrent(last)],
+        );
+
+        // One missing from the middle of each.
+        let (mut current, candidate) = fixture_and(|candidate| {
+            candidate.entries.remove(3);
+        });
+        let (ours, theirs) = (current.entries[3], current.entries.remove(10));
+        assert_eq!(
+            compare(&current, &candidate),
+            [
+                Discrepancy::MissingFromCandidate(ours),
+                Discrepancy::MissingFromCurrent(theirs),
+            ],
+        );
+    }
+
+    #[test]
+    fn compare_conflicting_offset() {
+        let (current, candidate) = fixture_and(|candidate| {
+            candidate.entries[5].tai_offset_after += 1;
+        });
+        assert_eq!(
+            compare(&current, &candidate),
+            [Discrepancy::ConflictingOffset {
+                utc_instant: current.entries[5].utc_instant,
+                current: current.entries[5].tai_offset_after,
+                candidate: current.entries[5].tai_offset_after + 1,
+            }],
+        );
+    }
+
+    #[test]
+    fn compare_differing_expiry() {
+        let (current, candidate) = fixture_and(|candidate| {
+            candidate.expires_at = Some(datetime!(2026-12-28 0:00 UTC));
+        });
+        assert_eq!(
+            compare(&current, &candidate),
+            [Discrepancy::DifferingExpiry {
+                current: Some(datetime!(2026-06-28 0:00 UTC)),
+                candidate: Some(datetime!(2026-12-28 0:00 UTC)),
+            }],
+        );
+
+        let (current, candidate) = fixture_and(|candidate| candidate.expires_at = None);
+        assert_eq!(
+            compare(&current, &candidate),
+            [Discrepancy::DifferingExpiry {
+                current: Some(datetime!(2026-06-28 0:00 UTC)),
+                candidate: None,
+            }],
+        );
+
+        // The update time isn't compared.
+        let (current, candidate) = fixture_and(|candidate| candidate.updated_at = None);
+        assert_eq!(compare(&current, &candidate), []);
+    }
+
+    #[test]
+    fn compare_everything() {
+        let extra = LeapEvent {
+            utc_instant: datetime!(2027-01-01 0:00 UTC),
+            sign: LeapSign::Positive,
+            tai_offset_after: 38,
+            provisional: false,
+        };
+        let (current, candidate) = fixture_and(|candidate| {
+            candidate.entries.remove(0);
+            candidate.entries[0].tai_offset_after = 99;
+            candidate.entries.push(extra);
+            candidate.expires_at = Some(datetime!(2027-06-28 0:00 UTC));
+        });
+        assert_eq!(
+            compare(&current, &candidate),
+            [
+                Discrepancy::MissingFromCandidate(current.entries[0]),
+                Discrepancy::ConflictingOffset {
+                    utc_instant: current.entries[1].utc_instant,
+                    current: current.entries[1].tai_offset_after,
+                    candidate: 99,
+                },
+                Discrepancy::MissingFromCurrent(extra),
+                Discrepancy::DifferingExpiry {
+                    current: current.expires_at,
+                    candidate: Some(datetime!(2027-06-28 0:00 UTC)),
+                },
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn validate_against_current() {
+        let _lock = crate::tests::lock_global_table();
+        let current = snapshot();
+        assert_eq!(validate_against(&current), []);
+
+        let mut candidate = current.clone();
+        let last = candidate.entries.pop().unwrap();
+        assert_eq!(
+            validate_against(&candidate),
+            [Discrepancy::MissingFromCandidate(last)],
+        );
+    }
+
+    // The fixture without its checksum.
+    #[cfg(feature = "fetch")]
+    fn without_checksum() -> String {
+        let list: String = LEAP_SECONDS_LIST
+            .lines()
+            .filter(|line| !line.starts_with("#h"))
+            .flat_map(|line| [line, "\n"])
+            .collect();
+        assert_ne!(list.len(), LEAP_SECONDS_LIST.len());
+        list
+    }
+
+    // The fixture with a wrong checksum.
+    #[cfg(feature = "fetch")]
+    fn with_wrong_checksum() -> String {
+        let list = LEAP_SECONDS_LIST.replace("3692217600      37", "3692217600      38");
+        assert_ne!(list, LEAP_SECONDS_LIST);
+        list
+    }
+
+    // Serves the given response once over HTTP on the loopback interface,
+    // returning the URL to fetch it from.
+    #[cfg(feature = "fetch")]
+    fn serve(status: &'static str, body: String) -> String {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/leap-seconds.list",
+            listener.local_addr().unwrap()
+        );
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            // Skips the request, which ends with an empty line.
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                reader.into_inner(),
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len(),
+            )
+            .unwrap();
+        });
+        url
+    }
+
+    #[test]
+    #[cfg(feature = "fetch")]
+    fn checksummed_lists() {
+        assert_eq!(
+            parse_checksummed(LEAP_SECONDS_LIST).unwrap(),
+            TableSnapshot::parse_leap_seconds_list(LEAP_SECONDS_LIST).unwrap(),
+        );
+        assert!(matches!(
+            parse_checksummed(&without_checksum()),
+            Err(FetchError::MissingChecksum),
+        ));
+        assert!(matches!(
+            parse_checksummed(&with_wrong_checksum()),
+            Err(FetchError::Parse(ListParseError::ChecksumMismatch)),
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "fetch")]
+    fn fetch_ietf_list_from_fixture() {
+        let url = serve("200 OK", LEAP_SECONDS_LIST.into());
+        assert_eq!(
+            fetch_ietf_list(Some(&url)).unwrap(),
+            TableSnapshot::parse_leap_seconds_list(LEAP_SECONDS_LIST).unwrap(),
+        );
+
+        let url = serve("200 OK", without_checksum());
+        assert!(matches!(
+            fetch_ietf_list(Some(&url)),
+            Err(FetchError::MissingChecksum),
+        ));
+
+        let url = serve("200 OK", with_wrong_checksum());
+        assert!(matches!(
+            fetch_ietf_list(Some(&url)),
+            Err(FetchError::Parse(ListParseError::ChecksumMismatch)),
+        ));
+
+        let url = serve("404 Not Found", String::new());
+        assert!(matches!(
+            fetch_ietf_list(Some(&url)),
+            Err(FetchError::Http(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "fetch")]
+    fn fetch_leap_seconds_from_fixture() {
+        let url = serve("200 OK", LEAP_SECONDS_LIST.into());
+        assert_eq!(fetch_leap_seconds(Some(&url)).unwrap(), LEAP_SECONDS);
+
+        // The checksum is optional, but gets verified if there is one.
+        let url = serve("200 OK", without_checksum());
+        assert_eq!(fetch_leap_seconds(Some(&url)).unwrap(), LEAP_SECONDS);
+
+        let url = serve("200 OK", with_wrong_checksum());
+        assert!(matches!(
+            fetch_leap_seconds(Some(&url)),
+            Err(FetchError::Parse(ListParseError::ChecksumMismatch)),
+        ));
+    }
+
+    #[test]
+    #[ignore = "downloads the list from the IETF"]
+    #[cfg(feature = "fetch")]
+    fn fetch_leap_seconds_from_ietf() {
+        let table = fetch_leap_seconds(None).unwrap();
+        assert_eq!(table[..LEAP_SECONDS.len()], *LEAP_SECONDS);
+    }
+}
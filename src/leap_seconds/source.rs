@@ -0,0 +1,1514 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use time::OffsetDateTime;
+
+use super::LeapSign;
+#[cfg(all(feature = "std", any(windows, unix)))]
+use super::{record_load_report, LeapSource, LoadReport, LoadWarning};
+
+/// A change of the difference between TAI and UTC, as provided by a
+/// [`LeapSecondSource`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeapEntry {
+    /// The UTC instant from which on the new offset applies. This is the
+    /// midnight right after the inserted or removed second.
+    pub utc_instant: OffsetDateTime,
+    /// The difference between TAI and UTC in seconds from `utc_instant` on.
+    pub tai_offset_after: i64,
+}
+
+/// The reason a [`LeapSecondSource`] could not provide any leap seconds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SourceError {
+    /// The data doesn't exist or could not be accessed.
+    Unavailable,
+    /// The data exists, but is malformed.
+    Invalid,
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable => f.write_str("the leap second data is unavailable"),
+            Self::Invalid => f.write_str("the leap second data is malformed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SourceError {}
+
+/// A leap second read from a calendar date and hour, as returned by
+/// [`leap_entry_from_calendar`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeapCandidate {
+    /// The UTC instant from which on the new offset applies, i.e. the start of
+    /// the hour after the leap second.
+    pub utc_instant: OffsetDateTime,
+    /// Whether the second gets inserted or removed.
+    pub sign: LeapSign,
+}
+
+/// The reason [`leap_entry_from_calendar`] rejected a leap second.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EntryError {
+    /// The year, month and day don't form a valid date.
+    InvalidDate,
+    /// The hour is not between 0 and 23.
+    InvalidHour,
+    /// The leap second is valid, but not in the last second of a month, where
+    /// all leap seconds so far have been. A source that wants to accept it
+    /// anyway can take the leap second from here and report a warning.
+    NotEndOfMonth(LeapCandidate),
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDate => f.write_str("the leap second is not on a valid date"),
+            Self::InvalidHour => f.write_str("the leap second is not in a valid hour"),
+            Self::NotEndOfMonth(_) => {
+                f.write_str("the leap second is not in the last second of a month")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EntryError {}
+
+/// Reads a leap second given as the UTC date and hour at whose end it's
+/// inserted or removed, like the Windows registry stores them. An inserted
+/// leap second follows `hour`:59:59, while a removed one is `hour`:59:59
+/// itself. Either way the new offset applies from the start of the next hour.
+///
+/// Leap seconds that aren't in the last second of a month are rejected with
+/// [`EntryError::NotEndOfMonth`], which still holds the leap second.
+pub fn leap_entry_from_calendar(
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    negative: bool,
+) -> Result<LeapCandidate, EntryError> {
+    let month = time::Month::try_from(month).map_err(|_| EntryError::InvalidDate)?;
+    let date =
+        time::Date::from_calendar_date(year, month, day).map_err(|_| EntryError::InvalidDate)?;
+    let last_second = date
+        .with_hms(hour, 59, 59)
+        .map_err(|_| EntryError::InvalidHour)?;
+    let candidate = LeapCandidate {
+        utc_instant: last_second.assume_utc() + time::Duration::SECOND,
+        sign: if negative {
+            LeapSign::Negative
+        } else {
+            LeapSign::Positive
+        },
+    };
+    if candidate.utc_instant.day() != 1 || candidate.utc_instant.hour() != 0 {
+        return Err(EntryError::NotEndOfMonth(candidate));
+    }
+    Ok(candidate)
+}
+
+/// Somewhere to load leap seconds from, in addition to the table built into
+/// the crate.
+///
+/// Sources are consulted once, right before the first conversion that needs
+/// the leap second table. See [`register_source`] for how the entries of
+/// several sources get merged, and [`Discovery::Custom`] for using one in
+/// place of the platform specific source. To convert with a source alone,
+/// without the built-in table or any global state, create a
+/// [`LeapTable::from_source`](super::LeapTable::from_source).
+///
+/// A [`LeapTable`](super::LeapTable) is a source itself.
+pub trait LeapSecondSource {
+    /// Loads the changes of the difference between TAI and UTC, in
+    /// chronological order.
+    fn load(&self) -> Result<Vec<LeapEntry>, SourceError>;
+
+    /// The UTC instant from which on the loaded entries can no longer be relied
+    /// upon, if known.
+    fn expiry(&self) -> Option<OffsetDateTime>;
+
+    /// Loads the entries together with their expiry, so they are consistent
+    /// with each other even if the data changes in between, and sources that
+    /// read a file don't have to read it twice. By default, this calls
+    /// [`load`](Self::load) and then [`expiry`](Self::expiry). The leap second
+    /// table used for conversions is built with this.
+    fn load_with_expiry(&self) -> Result<(Vec<LeapEntry>, Option<OffsetDateTime>), SourceError> {
+        let entries = self.load()?;
+        Ok((entries, self.expiry()))
+    }
+}
+
+/// The leap seconds listed in a tzdb `leapseconds` file, by default
+/// `/usr/share/zoneinfo/leapseconds`.
+#[cfg(all(feature = "std", unix))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnixLeapFile {
+    path: std::path::PathBuf,
+}
+
+#[cfg(all(feature = "std", unix))]
+impl Default for UnixLeapFile {
+    fn default() -> Self {
+        Self::new("/usr/share/zoneinfo/leapseconds")
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+impl UnixLeapFile {
+    /// Reads the leap seconds from the file at the given path.
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    // Parses the entries and the `#expires` line of the file. Malformed lines
+    // are skipped with a warning. A file that isn't blank, but has no valid
+    // entries at all, is most likely in a different format, so rather than
+    // passing for a file without any leap seconds, it's invalid.
+    fn read(
+        &self,
+        warnings: &mut Vec<LoadWarning>,
+    ) -> Result<(Vec<LeapEntry>, Option<i64>), SourceError> {
+        use crate::FIRST_LEAP_SECONDS_DIFF;
+
+        let file = std::fs::read_to_string(&self.path).map_err(|_| SourceError::Unavailable)?;
+        let mut elements = Vec::new();
+        let mut expires_at = None;
+        let mut diff = FIRST_LEAP_SECONDS_DIFF;
+        for (index, line) in file.split('\n').enumerate() {
+            // Newer versions of the file state the expiry as a UNIX time stamp
+            // in a comment.
+            if let Some(rem) = line.strip_prefix("#expires ") {
+                match rem.split_whitespace().next().and_then(|t| t.parse().ok()) {
+                    Some(time_stamp) => expires_at = expires_at.or(Some(time_stamp)),
+                    None => warnings.push(LoadWarning::MalformedExpiry { line: index + 1 }),
+                }
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            if fields.next() != Some("Leap") {
+                continue;
+            }
+            let (year, month, day, hour, negative) = match parse_leap_line(fields) {
+                Ok(fields) => fields,
+                Err(field) => {
+                    warnings.push(LoadWarning::MalformedLine {
+                        line: index + 1,
+                        field,
+                    });
+                    continue;
+                }
+            };
+            let candidate = match leap_entry_from_calendar(year, month, day, hour, negative) {
+                Ok(candidate) => candidate,
+                Err(EntryError::NotEndOfMonth(candidate)) => {
+                    warnings.push(LoadWarning::NonStandardLine { line: index + 1 });
+                    candidate
+                }
+                Err(error) => {
+                    let field = match error {
+                        EntryError::InvalidHour => "time",
+                        _ => "date",
+                    };
+                    warnings.push(LoadWarning::MalformedLine {
+                        line: index + 1,
+                        field,
+                    });
+                    continue;
+                }
+            };
+            let utc_instant = candidate.utc_instant;
+            match candidate.sign {
+                LeapSign::Positive => diff += 1,
+                LeapSign::Negative => diff -= 1,
+            }
+
+            elements.push(LeapEntry {
+                utc_instant,
+                tai_offset_after: diff,
+            });
+        }
+        if elements.is_empty() && !file.trim().is_empty() {
+            warnings.push(LoadWarning::NoEntries);
+            return Err(SourceError::Invalid);
+        }
+        Ok((elements, expires_at))
+    }
+}
+
+// Parses the fields of a `Leap` line after the `Leap` itself into the year,
+// month, day and hour of the leap second and whether it gets removed. The
+// time has to be `hour`:59:60 for an inserted leap second and `hour`:59:59
+// for a removed one. On failure the name of the malformed field is returned.
+#[cfg(all(feature = "std", unix))]
+fn parse_leap_line<'a>(
+    mut fields: impl Iterator<Item = &'a str>,
+) -> Result<(i32, u8, u8, u8, bool), &'static str> {
+    let year = fields.next().and_then(|f| f.parse().ok()).ok_or("year")?;
+    let month = match fields.next() {
+        Some("Jan") => 1,
+        Some("Feb") => 2,
+        Some("Mar") => 3,
+        Some("Apr") => 4,
+        Some("May") => 5,
+        Some("Jun") => 6,
+        Some("Jul") => 7,
+        Some("Aug") => 8,
+        Some("Sep") => 9,
+        Some("Oct") => 10,
+        Some("Nov") => 11,
+        Some("Dec") => 12,
+        _ => return Err("month"),
+    };
+    let day = fields.next().and_then(|f| f.parse().ok()).ok_or("day")?;
+
+    let mut time = fields.next().ok_or("time")?.split(':');
+    let mut component = || time.next().and_then(|c| c.parse::<u8>().ok()).ok_or("time");
+    let (hour, minute, second) = (component()?, component()?, component()?);
+
+    let negative = match fields.next() {
+        Some("+") => false,
+        Some("-") => true,
+        _ => return Err("correction"),
+    };
+    if minute != 59 || second != if negative { 59 } else { 60 } {
+        return Err("time");
+    }
+    Ok((year, month, day, hour, negative))
+}
+
+#[cfg(all(feature = "std", unix))]
+impl LeapSecondSource for UnixLeapFile {
+    fn load(&self) -> Result<Vec<LeapEntry>, SourceError> {
+        self.read(&mut Vec::new()).map(|(entries, _)| entries)
+    }
+
+    fn expiry(&self) -> Option<OffsetDateTime> {
+        self.load_with_expiry().ok()?.1
+    }
+
+    fn load_with_expiry(&self) -> Result<(Vec<LeapEntry>, Option<OffsetDateTime>), SourceError> {
+        let (entries, expires_at) = self.read(&mut Vec::new())?;
+        let expiry = expires_at.and_then(|t| OffsetDateTime::from_unix_timestamp(t).ok());
+        Ok((entries, expiry))
+    }
+}
+
+/// The leap seconds stored in the Windows registry.
+#[cfg(all(feature = "windows", windows))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WindowsRegistry;
+
+#[cfg(all(feature = "windows", windows))]
+impl WindowsRegistry {
+    // Reads the entries, skipping the invalid ones with a warning.
+    fn read(&self, warnings: &mut Vec<LoadWarning>) -> Result<Vec<LeapEntry>, SourceError> {
+        let elements = crate::platform::registry_leap_seconds(warnings)?;
+        Ok(registry_entries(&elements, warnings))
+    }
+}
+
+// Converts the entries of the registry, skipping the invalid ones with a
+// warning. Only reading them needs Windows, so this is tested everywhere.
+#[cfg(any(
+    all(feature = "windows", windows),
+    all(test, feature = "std", any(windows, unix))
+))]
+fn registry_entries(
+    elements: &[crate::platform::LeapInfo],
+    warnings: &mut Vec<LoadWarning>,
+) -> Vec<LeapEntry> {
+    // The Windows Registry started tracking leap seconds since June 2018. The
+    // initial difference between TAI and UTC is therefore 37 seconds.
+    let mut diff = 37;
+    let mut list = Vec::new();
+    for (index, element) in elements.iter().enumerate() {
+        let candidate = match registry_leap_entry(element) {
+            Ok(candidate) => candidate,
+            Err(EntryError::NotEndOfMonth(candidate)) => {
+                warnings.push(LoadWarning::NonStandardRegistryEntry { index });
+                candidate
+            }
+            Err(_) => {
+                warnings.push(LoadWarning::InvalidRegistryEntry { index });
+                continue;
+            }
+        };
+        match candidate.sign {
+            LeapSign::Positive => diff += 1,
+            LeapSign::Negative => diff -= 1,
+        }
+        list.push(LeapEntry {
+            utc_instant: candidate.utc_instant,
+            tai_offset_after: diff,
+        });
+    }
+    list
+}
+
+// Reads an entry of the registry, which stores the hour at whose end the leap
+// second is.
+#[cfg(any(
+    all(feature = "windows", windows),
+    all(test, feature = "std", any(windows, unix))
+))]
+fn registry_leap_entry(element: &crate::platform::LeapInfo) -> Result<LeapCandidate, EntryError> {
+    let year = element.year.into();
+    let month = u8::try_from(element.month).map_err(|_| EntryError::InvalidDate)?;
+    let day = u8::try_from(element.day).map_err(|_| EntryError::InvalidDate)?;
+    let hour = u8::try_from(element.hour).map_err(|_| EntryError::InvalidHour)?;
+    leap_entry_from_calendar(year, month, day, hour, element.negative != 0)
+}
+
+#[cfg(all(feature = "windows", windows))]
+impl LeapSecondSource for WindowsRegistry {
+    fn load(&self) -> Result<Vec<LeapEntry>, SourceError> {
+        self.read(&mut Vec::new())
+    }
+
+    fn expiry(&self) -> Option<OffsetDateTime> {
+        None
+    }
+}
+
+/// The reason leap seconds could not be loaded, either the platform specific
+/// ones, as reported to the hook set with [`set_leap_load_hook`], or the ones
+/// passed to [`load_leap_seconds_from_bytes`](super::load_leap_seconds_from_bytes).
+#[cfg(all(feature = "std", any(windows, unix)))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeapLoadError {
+    /// The source that failed to load.
+    pub leap_source: LeapSource,
+    /// Why it failed to load.
+    pub error: SourceError,
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+impl fmt::Display for LeapLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.leap_source {
+            LeapSource::Custom => f.write_str("the leap seconds could not be loaded"),
+            _ => f.write_str("the platform specific leap seconds could not be loaded"),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+impl std::error::Error for LeapLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+type LoadHook = fn(Result<(), LeapLoadError>);
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+static LOAD_HOOK: std::sync::Mutex<LoadHook> = std::sync::Mutex::new(|_| {});
+
+/// Sets a function that gets called with the outcome of loading the platform
+/// specific leap seconds, i.e. the tzdb `leapseconds` file on Unix and the
+/// registry on Windows. If they can't be loaded, conversions silently fall back
+/// to the other sources and the built-in table, so this allows logging or
+/// alerting about it. By default nothing happens.
+///
+/// The hook is called exactly once, right after the leap second table got built
+/// before the first conversion that needs it, so it has to be set before that.
+/// It's called on the thread that built the table and may do conversions
+/// itself. If there is no platform specific source, i.e. on Windows without
+/// the `windows` feature, with the `no-os-leap-data` feature or with
+/// [`Discovery::BuiltinOnly`], the hook is never called.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub fn set_leap_load_hook(f: fn(Result<(), LeapLoadError>)) {
+    *LOAD_HOOK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = f;
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub(crate) fn leap_load_hook() -> LoadHook {
+    *LOAD_HOOK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+type BoxedSource = Box<dyn LeapSecondSource + Send + Sync>;
+
+// The sources registered by the application, most recently registered first.
+#[cfg(all(feature = "std", any(windows, unix)))]
+static REGISTERED_SOURCES: std::sync::Mutex<Vec<BoxedSource>> = std::sync::Mutex::new(Vec::new());
+
+/// Adds a source of leap seconds in front of all sources registered so far and
+/// the platform specific one.
+///
+/// The leap second table is built once, right before the first conversion that
+/// needs it. Sources registered after that have no effect. The table starts out
+/// as the one built into the crate. Then the sources are loaded in order of
+/// priority, i.e. the most recently registered one first and the platform
+/// specific one last. Each source contributes the entries at UTC instants that
+/// none of the built-in table and the higher priority sources has an entry for.
+/// A source that disagrees with them at any instant they all have an entry for,
+/// or whose entries would make the table invalid, is dropped as a whole, which
+/// is reported as a [`LoadWarning::ConflictingSource`]. A source that fails to
+/// load is skipped. The table expires at the latest expiry of the built-in
+/// table and all sources that were merged into it.
+///
+/// Conversions only ever look at the merged table, never at the sources
+/// individually, so exactly one entry governs each instant: an entry applies
+/// from its UTC instant on, inclusive, up to the UTC instant of the next entry,
+/// exclusive. In particular, an entry right at the expiry of the built-in table
+/// governs that instant, while everything before it is still governed by the
+/// last built-in entry. An entry doesn't move the expiry by itself, so unless
+/// its source states a later expiry, the table still expires at that instant.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub fn register_source(source: BoxedSource) {
+    let mut sources = REGISTERED_SOURCES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    sources.insert(0, source);
+}
+
+// What the platform specific source is reported as by `leap_source`, what it
+// loaded, its expiry and where it was loaded from, if it was.
+#[cfg(all(feature = "std", any(windows, unix)))]
+type PlatformLoad = (
+    LeapSource,
+    Result<Vec<LeapEntry>, SourceError>,
+    Option<OffsetDateTime>,
+    Option<String>,
+);
+
+// The directories searched for the tzdb leap second data on Unix, in order.
+// Just like with the C library, the `TZDIR` environment variable takes
+// precedence. Alpine and other musl based distributions often lack the files
+// in `/usr/share/zoneinfo`, but may have them in `/etc/zoneinfo`.
+#[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+fn tzdb_dirs(tzdir: Option<std::ffi::OsString>) -> Vec<std::path::PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = tzdir.filter(|dir| !dir.is_empty()) {
+        dirs.push(dir.into());
+    }
+    dirs.push("/usr/share/zoneinfo".into());
+    dirs.push("/etc/zoneinfo".into());
+    dirs
+}
+
+// Reads the entries and the expiry of an IETF / IANA `leap-seconds.list` file,
+// which the tzdb ships alongside the `leapseconds` file.
+#[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+fn read_leap_seconds_list(
+    path: &std::path::Path,
+) -> Result<(Vec<LeapEntry>, Option<i64>), SourceError> {
+    let text = std::fs::read_to_string(path).map_err(|_| SourceError::Unavailable)?;
+    let list =
+        super::TableSnapshot::parse_leap_seconds_list(&text).map_err(|_| SourceError::Invalid)?;
+    let entries = list
+        .entries
+        .iter()
+        .map(|event| LeapEntry {
+            utc_instant: event.utc_instant,
+            tai_offset_after: event.tai_offset_after,
+        })
+        .collect();
+    Ok((entries, list.expires_at.map(OffsetDateTime::unix_timestamp)))
+}
+
+// Loads the platform specific source, alongside what it's reported as by
+// `leap_source`, collecting the warnings. On Unix, this is the first file that
+// can be read and parsed, looking for a `leapseconds` file and then for a
+// `leap-seconds.list` file in each of the tzdb directories.
+#[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+fn load_platform(warnings: &mut Vec<LoadWarning>) -> Option<PlatformLoad> {
+    load_tzdb_dirs(tzdb_dirs(std::env::var_os("TZDIR")), warnings)
+}
+
+// Loads the first file that can be read and parsed in the given tzdb
+// directories, like `load_platform`.
+#[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+fn load_tzdb_dirs(
+    dirs: Vec<std::path::PathBuf>,
+    warnings: &mut Vec<LoadWarning>,
+) -> Option<PlatformLoad> {
+    let mut error = SourceError::Unavailable;
+    for dir in dirs {
+        for path in [dir.join("leapseconds"), dir.join("leap-seconds.list")] {
+            // The file is only read once, rather than once for the entries and
+            // once for the expiry, so every warning is only reported once.
+            let read = if path.ends_with("leapseconds") {
+                UnixLeapFile::new(&path).read(warnings)
+            } else {
+                read_leap_seconds_list(&path)
+            };
+            match read {
+                Ok((entries, expires_at)) => {
+                    let expiry =
+                        expires_at.and_then(|t| OffsetDateTime::from_unix_timestamp(t).ok());
+                    let location = path.display().to_string();
+                    return Some((
+                        crate::PLATFORM_LEAP_SOURCE,
+                        Ok(entries),
+                        expiry,
+                        Some(location),
+                    ));
+                }
+                Err(SourceError::Invalid) => error = SourceError::Invalid,
+                Err(SourceError::Unavailable) => {}
+            }
+        }
+    }
+    Some((crate::PLATFORM_LEAP_SOURCE, Err(error), None, None))
+}
+
+#[cfg(all(feature = "windows", windows, not(feature = "no-os-leap-data")))]
+fn load_platform(warnings: &mut Vec<LoadWarning>) -> Option<PlatformLoad> {
+    let loaded = WindowsRegistry.read(warnings);
+    let location = loaded.is_ok().then(|| {
+        r"HKEY_LOCAL_MACHINE\SYSTEM\CurrentControlSet\Control\LeapSecondInformation".into()
+    });
+    Some((crate::PLATFORM_LEAP_SOURCE, loaded, None, location))
+}
+
+// There is no platform specific source on Windows without the `windows`
+// feature, and with the `no-os-leap-data` feature it's compiled out.
+#[cfg(all(
+    feature = "std",
+    any(windows, unix),
+    any(feature = "no-os-leap-data", all(windows, not(feature = "windows")))
+))]
+fn load_platform(_: &mut Vec<LoadWarning>) -> Option<PlatformLoad> {
+    None
+}
+
+/// How the platform specific leap seconds compare to the table built into the
+/// crate, as returned by [`compare_leap_sources`], e.g. to tell whether to rely
+/// on the data of the operating system or to update the crate.
+#[cfg(all(feature = "std", any(windows, unix)))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LeapComparison {
+    /// The UTC instant at which the table built into the crate expires.
+    pub builtin_expires_at: OffsetDateTime,
+    /// The platform specific source, or `None` if there is none on this
+    /// target.
+    pub os_source: Option<LeapSource>,
+    /// Where the platform specific leap seconds were read from, if they could
+    /// be.
+    pub os_location: Option<String>,
+    /// The number of leap seconds the platform specific source lists, or why
+    /// it couldn't be read.
+    pub os_outcome: Result<usize, SourceError>,
+    /// The UTC instant at which the platform specific leap seconds expire, if
+    /// they state that. The Windows registry doesn't.
+    pub os_expires_at: Option<OffsetDateTime>,
+    /// The number of platform specific leap seconds that take effect at or
+    /// after the expiry of the built-in table, i.e. that the crate doesn't
+    /// know about.
+    pub entries_past_builtin_expiry: usize,
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+impl LeapComparison {
+    /// Returns whether the platform specific leap seconds extend beyond the
+    /// expiry of the built-in table, either as they list leap seconds past it
+    /// or as they expire later.
+    pub fn os_is_newer(&self) -> bool {
+        self.entries_past_builtin_expiry > 0
+            || self
+                .os_expires_at
+                .is_some_and(|expires_at| expires_at > self.builtin_expires_at)
+    }
+}
+
+/// Reads the platform specific leap seconds, i.e. the tzdb on Unix and the
+/// registry on Windows, and compares them to the table built into the crate.
+/// They are read anew on each call, and the leap seconds used for conversions
+/// aren't affected. With [`Discovery::BuiltinOnly`] they aren't read at all, so
+/// there is no `os_source` and the outcome is [`SourceError::Unavailable`].
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub fn compare_leap_sources() -> LeapComparison {
+    let builtin_expires_at =
+        OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(crate::EXPIRES_AT_UTC);
+    let mut comparison = LeapComparison {
+        builtin_expires_at,
+        os_source: None,
+        os_location: None,
+        os_outcome: Err(SourceError::Unavailable),
+        os_expires_at: None,
+        entries_past_builtin_expiry: 0,
+    };
+    if let Some((kind, loaded, expiry, location)) = read_platform() {
+        comparison.os_source = Some(kind);
+        comparison.os_location = location;
+        comparison.os_expires_at = expiry;
+        if let Ok(entries) = &loaded {
+            comparison.entries_past_builtin_expiry = entries
+                .iter()
+                .filter(|entry| entry.utc_instant >= builtin_expires_at)
+                .count();
+        }
+        comparison.os_outcome = loaded.as_ref().map(Vec::len).map_err(|&error| error);
+    }
+    comparison
+}
+
+// Loads the platform specific leap seconds anew to inspect them rather than to
+// convert with them, unless `Discovery::BuiltinOnly` rules out accessing the
+// file system or the registry. The warnings aren't reported anywhere.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub(super) fn read_platform() -> Option<PlatformLoad> {
+    if !platform_access_allowed() {
+        return None;
+    }
+    load_platform(&mut Vec::new())
+}
+
+/// Whether and where to look for leap seconds at runtime, besides the sources
+/// registered with [`register_source`], as set with [`set_discovery`].
+#[cfg(all(feature = "std", any(windows, unix)))]
+#[derive(Default)]
+#[non_exhaustive]
+pub enum Discovery {
+    /// Only the table built into the crate and the registered sources are
+    /// used. Neither the file system nor the registry is ever accessed to look
+    /// for leap seconds.
+    BuiltinOnly,
+    /// The platform specific leap seconds are loaded, i.e. the tzdb on Unix
+    /// and the registry on Windows, and only the built-in table is used if
+    /// they can't be. This is the default. With the `no-os-leap-data` feature
+    /// there is no platform specific source, so this is the same as
+    /// [`BuiltinOnly`](Self::BuiltinOnly).
+    #[default]
+    OsThenBuiltin,
+    /// The given source is loaded in place of the platform specific one. Its
+    /// outcome is reported just like theirs, i.e. to the hook set with
+    /// [`set_leap_load_hook`] and by [`last_load_report`](super::last_load_report),
+    /// as [`LeapSource::Custom`].
+    Custom(BoxedSource),
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+impl fmt::Debug for Discovery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BuiltinOnly => f.write_str("BuiltinOnly"),
+            Self::OsThenBuiltin => f.write_str("OsThenBuiltin"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// The reason [`set_discovery`] failed.
+#[cfg(all(feature = "std", any(windows, unix)))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DiscoveryError {
+    /// The leap second table was already built, so the sources were already
+    /// loaded.
+    AlreadyLoaded,
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+impl fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyLoaded => f.write_str("the leap second sources were already loaded"),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+impl std::error::Error for DiscoveryError {}
+
+// How to look for leap seconds at runtime, which is taken once the leap second
+// table gets built.
+#[cfg(all(feature = "std", any(windows, unix)))]
+static DISCOVERY: std::sync::Mutex<Option<Discovery>> =
+    std::sync::Mutex::new(Some(Discovery::OsThenBuiltin));
+
+// Whether the discovery taken to build the leap second table was
+// `BuiltinOnly`, which keeps ruling out platform specific leap seconds after
+// that. Only changed while holding the lock of `DISCOVERY`.
+#[cfg(all(feature = "std", any(windows, unix)))]
+static BUILTIN_ONLY: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+// Returns whether the file system or the registry may be accessed to look for
+// leap seconds, i.e. whether the discovery, set or already taken, isn't
+// `BuiltinOnly`.
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn platform_access_allowed() -> bool {
+    let discovery = DISCOVERY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match &*discovery {
+        Some(discovery) => !matches!(discovery, Discovery::BuiltinOnly),
+        None => !BUILTIN_ONLY.load(core::sync::atomic::Ordering::Relaxed),
+    }
+}
+
+/// Sets whether and where to look for leap seconds at runtime. This has to
+/// happen before the leap second table is built, i.e. before the first
+/// conversion that needs it, as the sources are only loaded once. Otherwise
+/// this fails with [`DiscoveryError::AlreadyLoaded`] and nothing changes.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub fn set_discovery(discovery: Discovery) -> Result<(), DiscoveryError> {
+    let mut current = DISCOVERY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let current = current.as_mut().ok_or(DiscoveryError::AlreadyLoaded)?;
+    *current = discovery;
+    Ok(())
+}
+
+// Loads all sources in order of priority, alongside what they are reported as
+// by `leap_source` and where the platform specific one was loaded from. `f`
+// returns a warning if it had to drop what a source loaded. Returns the report
+// of loading the platform specific source, or the one set with `set_discovery`
+// in its place, which is also kept around for `last_load_report`.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub(crate) fn load_sources(
+    f: impl FnMut(
+        LeapSource,
+        Result<Vec<LeapEntry>, SourceError>,
+        Option<OffsetDateTime>,
+        Option<String>,
+    ) -> Option<LoadWarning>,
+) -> Option<LoadReport> {
+    // Taking it right away makes `set_discovery` fail from now on, so it can't
+    // succeed without having an effect.
+    let discovery = {
+        let mut discovery = DISCOVERY
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let discovery = discovery.take().unwrap_or_default();
+        BUILTIN_ONLY.store(
+            matches!(discovery, Discovery::BuiltinOnly),
+            core::sync::atomic::Ordering::Relaxed,
+        );
+        discovery
+    };
+
+    let sources = REGISTERED_SOURCES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let report = load_discovered(&sources, discovery, load_platform, f);
+    if let Some(report) = &report {
+        record_load_report(report);
+    }
+    report
+}
+
+// Loads the given registered sources and then the platform specific one with
+// `load_platform`, or whatever the discovery asks for in its place. This is
+// `load_sources` without the global state. The warnings about dropped sources
+// end up in the report, so there's nowhere to report them with
+// `Discovery::BuiltinOnly`.
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub(crate) fn load_discovered(
+    sources: &[BoxedSource],
+    discovery: Discovery,
+    load_platform: impl FnOnce(&mut Vec<LoadWarning>) -> Option<PlatformLoad>,
+    mut f: impl FnMut(
+        LeapSource,
+        Result<Vec<LeapEntry>, SourceError>,
+        Option<OffsetDateTime>,
+        Option<String>,
+    ) -> Option<LoadWarning>,
+) -> Option<LoadReport> {
+    let mut warnings = Vec::new();
+    for source in sources {
+        let (loaded, expiry) = load_custom(&**source);
+        warnings.extend(f(LeapSource::Custom, loaded, expiry, None));
+    }
+
+    let (kind, loaded, expiry, location) = match discovery {
+        Discovery::BuiltinOnly => return None,
+        Discovery::OsThenBuiltin => load_platform(&mut warnings)?,
+        Discovery::Custom(source) => {
+            let (loaded, expiry) = load_custom(&*source);
+            (LeapSource::Custom, loaded, expiry, None)
+        }
+    };
+    let outcome = loaded.as_ref().map(Vec::len).map_err(|&error| error);
+    warnings.extend(f(kind, loaded, expiry, location));
+    Some(LoadReport {
+        leap_source: kind,
+        outcome,
+        warnings,
+    })
+}
+
+// Loads the entries of a source together with their expiry, which is only
+// known if they could be loaded.
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn load_custom(
+    source: &dyn LeapSecondSource,
+) -> (Result<Vec<LeapEntry>, SourceError>, Option<OffsetDateTime>) {
+    match source.load_with_expiry() {
+        Ok((entries, expiry)) => (Ok(entries), expiry),
+        Err(error) => (Err(error), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    use std::sync::Arc;
+
+    use time::macros::datetime;
+
+    use super::*;
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    use crate::{leap_seconds::LeapTable, platform::LeapInfo, tests::lock_global_table};
+
+    #[test]
+    fn calendar_entries() {
+        // An inserted leap second follows 23:59:59, like `Leap 2016 Dec 31
+        // 23:59:60 + S` in the tzdb files.
+        assert_eq!(
+            leap_entry_from_calendar(2016, 12, 31, 23, false),
+            Ok(LeapCandidate {
+                utc_instant: datetime!(2017-01-01 0:00 UTC),
+                sign: LeapSign::Positive,
+            }),
+        );
+        // A removed one is 23:59:59 itself, like `Leap 2027 Jun 30 23:59:59 -
+        // S`. Either way the new offset applies from the next midnight on.
+        assert_eq!(
+            leap_entry_from_calendar(2027, 6, 30, 23, true),
+            Ok(LeapCandidate {
+                utc_instant: datetime!(2027-07-01 0:00 UTC),
+                sign: LeapSign::Negative,
+            }),
+        );
+        // The last day of February depends on the year.
+        assert_eq!(
+            leap_entry_from_calendar(2028, 2, 29, 23, false).map(|c| c.utc_instant),
+            Ok(datetime!(2028-03-01 0:00 UTC)),
+        );
+    }
+
+    #[test]
+    fn calendar_entries_off_the_end_of_month() {
+        assert_eq!(
+            leap_entry_from_calendar(2016, 12, 31, 22, false),
+            Err(EntryError::NotEndOfMonth(LeapCandidate {
+                utc_instant: datetime!(2016-12-31 23:00 UTC),
+                sign: LeapSign::Positive,
+            })),
+        );
+        assert_eq!(
+            leap_entry_from_calendar(2016, 12, 30, 23, true),
+            Err(EntryError::NotEndOfMonth(LeapCandidate {
+                utc_instant: datetime!(2016-12-31 0:00 UTC),
+                sign: LeapSign::Negative,
+            })),
+        );
+        assert_eq!(
+            leap_entry_from_calendar(2027, 2, 28, 23, false),
+            Ok(LeapCandidate {
+                utc_instant: datetime!(2027-03-01 0:00 UTC),
+                sign: LeapSign::Positive,
+            }),
+        );
+        assert!(matches!(
+            leap_entry_from_calendar(2028, 2, 28, 23, false),
+            Err(EntryError::NotEndOfMonth(_)),
+        ));
+    }
+
+    #[test]
+    fn invalid_calendar_entries() {
+        for (month, day) in [(0, 1), (13, 1), (6, 0), (6, 31), (2, 29)] {
+            assert_eq!(
+                leap_entry_from_calendar(2027, month, day, 23, false),
+                Err(EntryError::InvalidDate),
+            );
+        }
+        assert_eq!(
+            leap_entry_from_calendar(2016, 12, 31, 24, false),
+            Err(EntryError::InvalidHour),
+        );
+        assert_eq!(
+            leap_entry_from_calendar(2016, 12, 31, 24, true),
+            Err(EntryError::InvalidHour),
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn leap_lines() {
+        let parse = |line: &str| parse_leap_line(line.split_whitespace());
+        assert_eq!(
+            parse("2016 Dec 31 23:59:60 + S"),
+            Ok((2016, 12, 31, 23, false))
+        );
+        assert_eq!(
+            parse("2027 Jun 30 23:59:59 - S"),
+            Ok((2027, 6, 30, 23, true))
+        );
+        // The time has to match the sign.
+        assert_eq!(parse("2027 Jun 30 23:59:60 - S"), Err("time"));
+        assert_eq!(parse("2016 Dec 31 23:59:59 + S"), Err("time"));
+        assert_eq!(parse("2016 Dec 31 23:59:60 * S"), Err("correction"));
+        assert_eq!(parse("2016 Dez 31 23:59:60 + S"), Err("month"));
+    }
+
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn leap_info(year: u16, month: u16, day: u16, hour: u16, negative: u16) -> LeapInfo {
+        LeapInfo {
+            year,
+            month,
+            day,
+            hour,
+            negative,
+            reserved: 0,
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn registry_entries() {
+        // The layout of the registry value, as used by the MSVC STL.
+        assert_eq!(core::mem::size_of::<LeapInfo>(), 12);
+
+        let mut warnings = Vec::new();
+        let entries = super::registry_entries(
+            &[
+                leap_info(2030, 6, 30, 23, 0),
+                leap_info(2031, 13, 31, 23, 0),
+                leap_info(2031, 12, 31, 24, 0),
+                leap_info(2031, 12, 31, 11, 1),
+                leap_info(2032, 12, 31, 23, 1),
+            ],
+            &mut warnings,
+        );
+        assert_eq!(
+            entries,
+            [
+                LeapEntry {
+                    utc_instant: datetime!(2030-07-01 0:00 UTC),
+                    tai_offset_after: 38,
+                },
+                LeapEntry {
+                    utc_instant: datetime!(2031-12-31 12:00 UTC),
+                    tai_offset_after: 37,
+                },
+                LeapEntry {
+                    utc_instant: datetime!(2033-01-01 0:00 UTC),
+                    tai_offset_after: 36,
+                },
+            ],
+        );
+        assert_eq!(
+            warnings,
+            [
+                LoadWarning::InvalidRegistryEntry { index: 1 },
+                LoadWarning::InvalidRegistryEntry { index: 2 },
+                LoadWarning::NonStandardRegistryEntry { index: 3 },
+            ],
+        );
+
+        let mut warnings = Vec::new();
+        assert_eq!(super::registry_entries(&[], &mut warnings), []);
+        assert_eq!(warnings, []);
+    }
+
+    // A source that counts how often it's asked for its leap seconds or their
+    // expiry.
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    #[derive(Clone, Default)]
+    struct Canary(Arc<AtomicUsize>);
+
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    impl Canary {
+        fn calls(&self) -> usize {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    impl LeapSecondSource for Canary {
+        fn load(&self) -> Result<Vec<LeapEntry>, SourceError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(vec![LeapEntry {
+                utc_instant: datetime!(2027-01-01 0:00 UTC),
+                tai_offset_after: 38,
+            }])
+        }
+
+        fn expiry(&self) -> Option<OffsetDateTime> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Some(datetime!(2027-07-01 0:00 UTC))
+        }
+    }
+
+    // Loads the given discovery with a registered canary and another canary in
+    // place of the platform specific source, returning the report and what
+    // each source got reported as.
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn load_with_canaries(
+        discovery: Discovery,
+        registered: &Canary,
+        platform: &Canary,
+    ) -> (Option<LoadReport>, Vec<LeapSource>) {
+        let mut loaded = Vec::new();
+        let report = load_discovered(
+            &[Box::new(registered.clone())],
+            discovery,
+            |_| {
+                Some((
+                    LeapSource::UnixLeapFile,
+                    platform.load(),
+                    platform.expiry(),
+                    None,
+                ))
+            },
+            |kind, _, _, _| {
+                loaded.push(kind);
+                None
+            },
+        );
+        (report, loaded)
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn builtin_only_never_loads_the_platform() {
+        let (registered, platform) = (Canary::default(), Canary::default());
+        let (report, loaded) = load_with_canaries(Discovery::BuiltinOnly, &registered, &platform);
+        assert_eq!(report, None);
+        assert_eq!(loaded, [LeapSource::Custom]);
+        assert_eq!(registered.calls(), 2);
+        assert_eq!(platform.calls(), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn os_then_builtin_loads_the_platform() {
+        let (registered, platform) = (Canary::default(), Canary::default());
+        let (report, loaded) = load_with_canaries(Discovery::OsThenBuiltin, &registered, &platform);
+        let report = report.unwrap();
+        assert_eq!(report.leap_source, LeapSource::UnixLeapFile);
+        assert_eq!(report.outcome, Ok(1));
+        assert_eq!(loaded, [LeapSource::Custom, LeapSource::UnixLeapFile]);
+        assert_eq!(platform.calls(), 2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn custom_replaces_the_platform() {
+        let (registered, platform) = (Canary::default(), Canary::default());
+        let custom = Canary::default();
+        let discovery = Discovery::Custom(Box::new(custom.clone()));
+        let (report, loaded) = load_with_canaries(discovery, &registered, &platform);
+        assert_eq!(report.unwrap().leap_source, LeapSource::Custom);
+        assert_eq!(loaded, [LeapSource::Custom, LeapSource::Custom]);
+        assert_eq!(custom.calls(), 2);
+        assert_eq!(platform.calls(), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn set_discovery_after_loading() {
+        let _lock = lock_global_table();
+        crate::leap_table();
+        let canary = Canary::default();
+        assert_eq!(
+            set_discovery(Discovery::Custom(Box::new(canary.clone()))),
+            Err(DiscoveryError::AlreadyLoaded),
+        );
+        assert_eq!(
+            set_discovery(Discovery::BuiltinOnly),
+            Err(DiscoveryError::AlreadyLoaded),
+        );
+        LeapTable::current();
+        assert_eq!(canary.calls(), 0);
+        assert!(platform_access_allowed());
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn unix_leap_file_loads_with_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leapseconds");
+        std::fs::write(
+            &path,
+            "#expires 1782604800\nLeap\t1972\tJun\t30\t23:59:60\t+\tS\n",
+        )
+        .unwrap();
+        let file = UnixLeapFile::new(&path);
+        let entries = vec![LeapEntry {
+            utc_instant: datetime!(1972-07-01 0:00 UTC),
+            tai_offset_after: 11,
+        }];
+        let expiry = Some(datetime!(2026-06-28 0:00 UTC));
+        assert_eq!(file.load_with_expiry(), Ok((entries.clone(), expiry)));
+        assert_eq!((file.load(), file.expiry()), (Ok(entries), expiry));
+
+        let missing = UnixLeapFile::new(dir.path().join("missing"));
+        assert_eq!(missing.load_with_expiry(), Err(SourceError::Unavailable));
+        assert_eq!(missing.expiry(), None);
+    }
+
+    #[cfg(all(feature = "std", unix))]
+    const LEAPSECONDS: &str = include_str!("../../tests/data/leapseconds");
+
+    // What reading a tzdb `leapseconds` file loaded and the warnings.
+    #[cfg(all(feature = "std", unix))]
+    type ReadLeapseconds = (
+        Result<(Vec<LeapEntry>, Option<i64>), SourceError>,
+        Vec<LoadWarning>,
+    );
+
+    // Reads a tzdb `leapseconds` file with the given contents.
+    #[cfg(all(feature = "std", unix))]
+    fn read_leapseconds(contents: &str) -> ReadLeapseconds {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leapseconds");
+        std::fs::write(&path, contents).unwrap();
+        let mut warnings = Vec::new();
+        let read = UnixLeapFile::new(&path).read(&mut warnings);
+        (read, warnings)
+    }
+
+    // Reads the `leapseconds` fixture with the line of the given (1-based)
+    // number replaced.
+    #[cfg(all(feature = "std", unix))]
+    fn read_corrupted(line: usize, replacement: &str) -> ReadLeapseconds {
+        let mut lines: Vec<&str> = LEAPSECONDS.split('\n').collect();
+        lines[line - 1] = replacement;
+        read_leapseconds(&lines.join("\n"))
+    }
+
+    // The number of the `Leap` line of the last leap second in the fixture and
+    // the one of its `#expires` line.
+    #[cfg(all(feature = "std", unix))]
+    const LAST_LEAP_LINE: usize = 66;
+    #[cfg(all(feature = "std", unix))]
+    const EXPIRES_LINE: usize = 76;
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn leapseconds_fixture() {
+        let (read, warnings) = read_leapseconds(LEAPSECONDS);
+        let (entries, expires_at) = read.unwrap();
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|e| (e.utc_instant.unix_timestamp(), e.tai_offset_after))
+            .collect();
+        assert_eq!(entries, crate::LEAP_SECONDS);
+        assert_eq!(expires_at, Some(1_782_604_800));
+        assert_eq!(warnings, []);
+
+        let lines: Vec<&str> = LEAPSECONDS.split('\n').collect();
+        assert_eq!(
+            lines[LAST_LEAP_LINE - 1],
+            "Leap\t2016\tDec\t31\t23:59:60\t+\tS"
+        );
+        assert!(lines[EXPIRES_LINE - 1].starts_with("#expires 1782604800"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn malformed_leapseconds_lines() {
+        for (line, field) in [
+            ("Leap 20x6 Dec 31 23:59:60 + S", "year"),
+            ("Leap 2016 Dez 31 23:59:60 + S", "month"),
+            ("Leap 2016 Dec 3x 23:59:60 + S", "day"),
+            ("Leap 2016 Dec 32 23:59:60 + S", "date"),
+            ("Leap 2016 Dec 31 24:59:60 + S", "time"),
+            ("Leap 2016 Dec 31 23:59:59 + S", "time"),
+            ("Leap 2016 Dec 31 23:59:60 - S", "time"),
+            ("Leap 2016 Dec 31 23:59", "time"),
+            ("Leap 2016 Dec 31 23:59:60 ? S", "correction"),
+            ("Leap 2016 Dec 31 23:59:60", "correction"),
+        ] {
+            let (read, warnings) = read_corrupted(LAST_LEAP_LINE, line);
+            let (entries, expires_at) = read.unwrap();
+            assert_eq!(entries.len(), crate::LEAP_SECONDS.len() - 1, "{line}");
+            assert_eq!(expires_at, Some(1_782_604_800));
+            assert_eq!(
+                warnings,
+                [LoadWarning::MalformedLine {
+                    line: LAST_LEAP_LINE,
+                    field,
+                }],
+                "{line}",
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn non_standard_leapseconds_line() {
+        let (read, warnings) = read_corrupted(LAST_LEAP_LINE, "Leap 2016 Dec 30 23:59:60 + S");
+        let (entries, _) = read.unwrap();
+        assert_eq!(
+            entries.last(),
+            Some(&LeapEntry {
+                utc_instant: datetime!(2016-12-31 0:00 UTC),
+                tai_offset_after: 37,
+            }),
+        );
+        assert_eq!(
+            warnings,
+            [LoadWarning::NonStandardLine {
+                line: LAST_LEAP_LINE
+            }],
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn malformed_leapseconds_expiry() {
+        let (read, warnings) = read_corrupted(EXPIRES_LINE, "#expires soon");
+        let (entries, expires_at) = read.unwrap();
+        assert_eq!(entries.len(), crate::LEAP_SECONDS.len());
+        assert_eq!(expires_at, None);
+        assert_eq!(
+            warnings,
+            [LoadWarning::MalformedExpiry { line: EXPIRES_LINE }],
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn leapseconds_without_entries() {
+        // A `leap-seconds.list` is in a different format.
+        let list = include_str!("../../tests/data/leap-seconds.list");
+        assert_eq!(
+            read_leapseconds(list),
+            (Err(SourceError::Invalid), vec![LoadWarning::NoEntries]),
+        );
+
+        // Neither is one that only has comments, while a blank one has no leap
+        // seconds.
+        let comments: String = LEAPSECONDS
+            .split_inclusive('\n')
+            .filter(|line| !line.starts_with("Leap"))
+            .collect();
+        assert_eq!(
+            read_leapseconds(&comments),
+            (Err(SourceError::Invalid), vec![LoadWarning::NoEntries]),
+        );
+        assert_eq!(
+            read_leapseconds(" \n"),
+            (Ok((Vec::new(), None)), Vec::new())
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    fn tzdir_takes_precedence() {
+        let defaults = ["/usr/share/zoneinfo", "/etc/zoneinfo"].map(std::path::PathBuf::from);
+        assert_eq!(tzdb_dirs(None), defaults);
+        assert_eq!(tzdb_dirs(Some("".into())), defaults);
+        assert_eq!(
+            tzdb_dirs(Some("/opt/tzdb".into())),
+            ["/opt/tzdb", "/usr/share/zoneinfo", "/etc/zoneinfo"].map(std::path::PathBuf::from),
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    fn load_first_tzdb_dir() {
+        // Stand-ins for the `TZDIR`, `/usr/share/zoneinfo` and
+        // `/etc/zoneinfo`, in that order.
+        let root = tempfile::tempdir().unwrap();
+        let dirs = ["tzdir", "usr", "etc"].map(|name| root.path().join(name));
+        for dir in &dirs {
+            std::fs::create_dir(dir).unwrap();
+        }
+        let load = || {
+            let (source, loaded, expiry, location) =
+                load_tzdb_dirs(dirs.to_vec(), &mut Vec::new()).unwrap();
+            assert_eq!(source, crate::PLATFORM_LEAP_SOURCE);
+            (loaded.map(|entries| entries.len()), expiry, location)
+        };
+        let location = |dir: &std::path::Path, file| Some(dir.join(file).display().to_string());
+        let expiry = Some(datetime!(2026-06-28 0:00 UTC));
+        let list = include_str!("../../tests/data/leap-seconds.list");
+        // The fixture with the last leap second removed, so it can be told
+        // apart.
+        let shorter = LEAPSECONDS.replace("Leap\t2016\tDec\t31\t23:59:60\t+\tS\n", "");
+
+        assert_eq!(load(), (Err(SourceError::Unavailable), None, None));
+
+        // Falling back to `/etc/zoneinfo` if the others lack the files.
+        std::fs::write(dirs[2].join("leap-seconds.list"), list).unwrap();
+        assert_eq!(
+            load(),
+            (Ok(27), expiry, location(&dirs[2], "leap-seconds.list")),
+        );
+
+        // Within a directory, the `leapseconds` file comes first.
+        std::fs::write(dirs[2].join("leapseconds"), &shorter).unwrap();
+        assert_eq!(load(), (Ok(26), expiry, location(&dirs[2], "leapseconds")));
+
+        // `/usr/share/zoneinfo` comes before it, but not with invalid files.
+        std::fs::write(dirs[1].join("leapseconds"), "garbage").unwrap();
+        std::fs::write(dirs[1].join("leap-seconds.list"), "garbage").unwrap();
+        assert_eq!(load(), (Ok(26), expiry, location(&dirs[2], "leapseconds")));
+        std::fs::write(dirs[1].join("leap-seconds.list"), list).unwrap();
+        assert_eq!(
+            load(),
+            (Ok(27), expiry, location(&dirs[1], "leap-seconds.list")),
+        );
+
+        // And the `TZDIR` comes before all of them.
+        std::fs::write(dirs[0].join("leapseconds"), &shorter).unwrap();
+        assert_eq!(load(), (Ok(26), expiry, location(&dirs[0], "leapseconds")));
+
+        // If none of the files are valid, that's reported rather than them
+        // being unavailable.
+        for dir in &dirs {
+            for file in ["leapseconds", "leap-seconds.list"] {
+                std::fs::write(dir.join(file), "garbage").unwrap();
+            }
+        }
+        assert_eq!(load(), (Err(SourceError::Invalid), None, None));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    fn leapseconds_in_a_different_format() {
+        // E.g. an error page that got saved in place of the file.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("leapseconds"),
+            "<html><body>404 Not Found</body></html>\n",
+        )
+        .unwrap();
+        let (table, report) = crate::build_effective_table(|f| {
+            load_discovered(
+                &[],
+                Discovery::OsThenBuiltin,
+                |warnings| load_tzdb_dirs(vec![dir.path().into()], warnings),
+                f,
+            )
+        });
+        let report = report.unwrap();
+
+        assert_eq!(report.outcome, Err(SourceError::Invalid));
+        assert_eq!(report.warnings, [LoadWarning::NoEntries]);
+        assert!(report.has_problems());
+        assert_eq!(table.entries, crate::LEAP_SECONDS);
+        assert_eq!(table.source, LeapSource::FallbackStale);
+        assert!(!table.loaded);
+    }
+
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    fn tzdir_with_next_leap_second() -> tempfile::TempDir {
+        crate::tests::tzdir_with_leap_seconds(&crate::tests::builtin_table_with(
+            &[(datetime!(2027-01-01 0:00 UTC), 38)],
+            datetime!(2027-07-01 0:00 UTC),
+        ))
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    fn compare_leap_sources_reads_tzdir() {
+        let _lock = lock_global_table();
+        let dir = tzdir_with_next_leap_second();
+        let comparison = compare_leap_sources();
+        std::env::remove_var("TZDIR");
+
+        assert_eq!(comparison.os_source, Some(crate::PLATFORM_LEAP_SOURCE));
+        assert_eq!(
+            comparison.os_location,
+            Some(dir.path().join("leap-seconds.list").display().to_string()),
+        );
+        assert_eq!(comparison.os_outcome, Ok(crate::LEAP_SECONDS.len() + 1));
+        assert_eq!(
+            comparison.os_expires_at,
+            Some(datetime!(2027-07-01 0:00 UTC))
+        );
+        assert_eq!(comparison.entries_past_builtin_expiry, 1);
+        assert_eq!(
+            comparison.builtin_expires_at,
+            OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(crate::EXPIRES_AT_UTC),
+        );
+        assert!(comparison.os_is_newer());
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    fn builtin_only_rules_out_reading_the_platform() {
+        let _lock = lock_global_table();
+        let _dir = tzdir_with_next_leap_second();
+        // The table is built, so this is what `load_sources` leaves behind
+        // with `Discovery::BuiltinOnly`.
+        BUILTIN_ONLY.store(true, Ordering::Relaxed);
+        let comparison = compare_leap_sources();
+        let table = LeapTable::from_system();
+        BUILTIN_ONLY.store(false, Ordering::Relaxed);
+        std::env::remove_var("TZDIR");
+
+        assert_eq!(comparison.os_source, None);
+        assert_eq!(comparison.os_location, None);
+        assert_eq!(comparison.os_outcome, Err(SourceError::Unavailable));
+        assert_eq!(comparison.os_expires_at, None);
+        assert_eq!(comparison.entries_past_builtin_expiry, 0);
+        assert!(!comparison.os_is_newer());
+        assert_eq!(table, Err(SourceError::Unavailable));
+    }
+
+    // The outcomes the hook got called with.
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    static HOOK_CALLS: std::sync::Mutex<Vec<Result<(), LeapLoadError>>> =
+        std::sync::Mutex::new(Vec::new());
+
+    #[test]
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    fn load_hook_reports_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let load = || {
+            load_discovered(
+                &[],
+                Discovery::OsThenBuiltin,
+                |warnings| load_tzdb_dirs(vec![dir.path().into()], warnings),
+                |_, _, _, _| None,
+            )
+            .unwrap()
+        };
+        let missing = load();
+        assert_eq!(missing.leap_source, crate::PLATFORM_LEAP_SOURCE);
+        assert_eq!(missing.outcome, Err(SourceError::Unavailable));
+
+        std::fs::copy(
+            "tests/data/leap-seconds.list",
+            dir.path().join("leap-seconds.list"),
+        )
+        .unwrap();
+        let found = load();
+        assert_eq!(found.outcome, Ok(crate::LEAP_SECONDS.len()));
+
+        // The hook is only called for the reports passed on here, as the
+        // table used for conversions is built by now.
+        crate::leap_table();
+        set_leap_load_hook(|outcome| HOOK_CALLS.lock().unwrap().push(outcome));
+        super::super::notify_load(&missing);
+        super::super::notify_load(&found);
+        set_leap_load_hook(|_| {});
+        assert_eq!(
+            *HOOK_CALLS.lock().unwrap(),
+            [
+                Err(LeapLoadError {
+                    leap_source: crate::PLATFORM_LEAP_SOURCE,
+                    error: SourceError::Unavailable,
+                }),
+                Ok(()),
+            ],
+        );
+    }
+}
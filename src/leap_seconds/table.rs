@@ -0,0 +1,578 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use time::{Duration, OffsetDateTime};
+
+use super::{events_in, LeapEntry, LeapEvent, LeapSecondSource, SourceError};
+#[cfg(all(feature = "std", any(windows, unix)))]
+use super::{merge_custom, LeapLoadError, LeapSource};
+#[cfg(feature = "std")]
+use super::{ListParseError, TableSnapshot};
+#[cfg(all(feature = "std", any(windows, unix)))]
+use crate::update_effective_table;
+use crate::{
+    diff_before, leap_table, leap_table_expiry, tai_to_utc, utc_to_tai, TaiDateTime, TaiUtcOffset,
+    EXPIRES_AT_UTC, FIRST_LEAP_SECONDS_DIFF, LEAP_SECONDS,
+};
+
+/// The reason a [`LeapTable`] could not be created.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LeapTableError {
+    /// The entry at the given index is not after the previous one.
+    NotSorted {
+        /// The index of the entry.
+        index: usize,
+    },
+    /// The difference between TAI and UTC of the entry at the given index
+    /// doesn't differ by exactly one second from the previous one, or from
+    /// the initial 10 seconds for the first entry.
+    InvalidStep {
+        /// The index of the entry.
+        index: usize,
+    },
+    /// The `leap-seconds.list` could not be parsed.
+    #[cfg(feature = "std")]
+    List(ListParseError),
+}
+
+impl fmt::Display for LeapTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSorted { index } => {
+                write!(f, "entry {index} is not after the previous one")
+            }
+            Self::InvalidStep { index } => write!(
+                f,
+                "the offset of entry {index} doesn't differ by one second from the previous one"
+            ),
+            #[cfg(feature = "std")]
+            Self::List(_) => f.write_str("the leap second list is malformed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LeapTableError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::List(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A leap second table to convert with, independent of the one used by the
+/// [`From`] conversions. This allows converting with several tables in the
+/// same process, e.g. to compare an old and a new `leap-seconds.list`.
+///
+/// The conversions work exactly like the [`From`] conversions, just with this
+/// table instead of the one that is used globally.
+///
+/// It also serves to inspect, log and compare leap second data, e.g.
+/// [`current`](Self::current) is a copy of the table the [`From`] conversions
+/// use and [`from_system`](Self::from_system) the one the operating system
+/// provides.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeapTable {
+    entries: Vec<(i64, i64)>,
+    expires_at: i64,
+}
+
+impl LeapTable {
+    /// The table built into the crate.
+    pub fn builtin() -> Self {
+        Self {
+            entries: LEAP_SECONDS.to_vec(),
+            expires_at: EXPIRES_AT_UTC,
+        }
+    }
+
+    /// A copy of the table currently used by the [`From`] conversions, which
+    /// includes the platform specific leap seconds.
+    pub fn current() -> Self {
+        Self {
+            entries: leap_table().to_vec(),
+            expires_at: leap_table_expiry(),
+        }
+    }
+
+    /// The table built into the crate merged with the platform specific leap
+    /// seconds, i.e. the tzdb on Unix and the registry on Windows, just like
+    /// the table used by the [`From`] conversions is built, but without any
+    /// registered sources or announcements. They are read anew on each call.
+    /// Fails with [`SourceError::Unavailable`] if they can't be read, there are
+    /// none on this target or [`Discovery::BuiltinOnly`](super::Discovery::BuiltinOnly)
+    /// rules out reading them, and with [`SourceError::Invalid`] if they don't
+    /// form a valid table together with the built-in entries.
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    pub fn from_system() -> Result<Self, SourceError> {
+        let Some((_, loaded, expiry, _)) = super::read_platform() else {
+            return Err(SourceError::Unavailable);
+        };
+        let mut entries = LEAP_SECONDS.to_vec();
+        for entry in loaded? {
+            let time_stamp = entry.utc_instant.unix_timestamp();
+            if let Err(index) = entries.binary_search_by_key(&time_stamp, |&(t, _)| t) {
+                entries.insert(index, (time_stamp, entry.tai_offset_after));
+            }
+        }
+        let expires_at = expiry.map_or(EXPIRES_AT_UTC, |expiry| {
+            EXPIRES_AT_UTC.max(expiry.unix_timestamp())
+        });
+        Self::from_entries(
+            entries,
+            OffsetDateTime::UNIX_EPOCH + Duration::seconds(expires_at),
+        )
+        .map_err(|_| SourceError::Invalid)
+    }
+
+    /// Creates a table from pairs of the UNIX time stamp from which on a
+    /// difference between TAI and UTC applies and that difference in seconds,
+    /// leaving out the initial 10 seconds of 1972. This is the format
+    /// `fetch_leap_seconds` returns.
+    ///
+    /// The time stamps have to be strictly increasing and each difference has
+    /// to be exactly one second more or less than the previous one.
+    pub fn from_entries(
+        entries: Vec<(i64, i64)>,
+        expires_at: OffsetDateTime,
+    ) -> Result<Self, LeapTableError> {
+        check_entries(&entries)?;
+        Ok(Self {
+            entries,
+            expires_at: expires_at.unix_timestamp(),
+        })
+    }
+
+    /// Creates a table from the leap seconds of a source alone, without
+    /// merging in the table built into the crate, e.g. to convert with an
+    /// authoritative table of your own. If the source doesn't state when its
+    /// data expires, it's considered to expire right at its last entry. Fails
+    /// with [`SourceError::Invalid`] if the entries don't form a valid table,
+    /// see [`from_entries`](Self::from_entries).
+    pub fn from_source(source: &(impl LeapSecondSource + ?Sized)) -> Result<Self, SourceError> {
+        let (entries, expiry) = source.load_with_expiry()?;
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.utc_instant.unix_timestamp(), entry.tai_offset_after))
+            .collect();
+        let expires_at = expiry.unwrap_or_else(|| {
+            entries
+                .last()
+                .map_or(OffsetDateTime::UNIX_EPOCH, |&(t, _)| {
+                    OffsetDateTime::UNIX_EPOCH + Duration::seconds(t)
+                })
+        });
+        Self::from_entries(entries, expires_at).map_err(|_| SourceError::Invalid)
+    }
+
+    /// Parses a table in the format of the IETF / IANA `leap-seconds.list`
+    /// file, with [`TableSnapshot::parse_leap_seconds_list`]. If the list
+    /// doesn't state when it expires, it's considered to expire right at its
+    /// last entry.
+    #[cfg(feature = "std")]
+    pub fn from_leap_seconds_list(text: &str) -> Result<Self, LeapTableError> {
+        let snapshot =
+            TableSnapshot::parse_leap_seconds_list(text).map_err(LeapTableError::List)?;
+        let entries: Vec<_> = snapshot
+            .entries
+            .iter()
+            .map(|event| (event.utc_instant.unix_timestamp(), event.tai_offset_after))
+            .collect();
+        let expires_at = snapshot.expires_at.unwrap_or_else(|| {
+            entries
+                .last()
+                .map_or(OffsetDateTime::UNIX_EPOCH, |&(t, _)| {
+                    OffsetDateTime::UNIX_EPOCH + Duration::seconds(t)
+                })
+        });
+        Self::from_entries(entries, expires_at)
+    }
+
+    /// The pairs of the UNIX time stamp from which on a difference between TAI
+    /// and UTC applies and that difference in seconds.
+    pub fn entries(&self) -> &[(i64, i64)] {
+        &self.entries
+    }
+
+    /// Returns the leap events of the table in chronological order. None of
+    /// them are [provisional](LeapEvent::provisional).
+    pub fn iter(&self) -> impl Iterator<Item = LeapEvent> + '_ {
+        events_in(&self.entries, &[])
+    }
+
+    /// Returns the first leap event of the table that takes effect after the
+    /// given instant, like [`next_after`](super::next_after).
+    pub fn next_after(&self, time: TaiDateTime) -> Option<LeapEvent> {
+        self.iter().find(|event| event.tai_instant() > time)
+    }
+
+    /// The UTC instant from which on the table can no longer be relied upon.
+    pub fn expires_at(&self) -> OffsetDateTime {
+        OffsetDateTime::UNIX_EPOCH + Duration::seconds(self.expires_at)
+    }
+
+    /// The difference between TAI and UTC at the given UNIX time stamp.
+    pub fn tai_utc_offset_at(&self, unix_time_stamp: i64) -> TaiUtcOffset {
+        TaiUtcOffset::from_whole_seconds(self.offset_at_utc(unix_time_stamp))
+    }
+
+    /// The difference between TAI and UTC in seconds at the given UNIX time
+    /// stamp.
+    #[deprecated(note = "use `tai_utc_offset_at` instead")]
+    pub fn offset_at(&self, unix_time_stamp: i64) -> i64 {
+        self.offset_at_utc(unix_time_stamp)
+    }
+
+    // The difference between TAI and UTC in seconds at the given UTC time
+    // stamp.
+    fn offset_at_utc(&self, unix_time_stamp: i64) -> i64 {
+        let index = self.entries.partition_point(|&(t, _)| t <= unix_time_stamp);
+        diff_before(&self.entries, index)
+    }
+
+    // The difference between TAI and UTC in seconds at the given TAI time
+    // stamp.
+    fn offset_at_tai(&self, tai_time_stamp: i64) -> i64 {
+        let index = self
+            .entries
+            .partition_point(|&(t, diff)| t + diff <= tai_time_stamp);
+        diff_before(&self.entries, index)
+    }
+
+    /// Converts a UTC date time to TAI with this table.
+    pub fn utc_to_tai(&self, time: OffsetDateTime) -> TaiDateTime {
+        utc_to_tai(time, |t| self.offset_at_utc(t))
+    }
+
+    /// Converts a TAI date time to UTC with this table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting UTC date time is outside of the range of
+    /// [`OffsetDateTime`], just like the [`From`] conversion.
+    pub fn tai_to_utc(&self, time: TaiDateTime) -> OffsetDateTime {
+        tai_to_utc(time, |t| self.offset_at_tai(t))
+    }
+}
+
+// Checks that the time stamps are strictly increasing and that each difference
+// between TAI and UTC is exactly one second more or less than the previous one.
+pub(crate) fn check_entries(entries: &[(i64, i64)]) -> Result<(), LeapTableError> {
+    let mut previous = None;
+    for (index, &(time_stamp, diff)) in entries.iter().enumerate() {
+        if previous.is_some_and(|(t, _)| time_stamp <= t) {
+            return Err(LeapTableError::NotSorted { index });
+        }
+        let previous_diff = previous.map_or(FIRST_LEAP_SECONDS_DIFF, |(_, d)| d);
+        if (diff - previous_diff).abs() != 1 {
+            return Err(LeapTableError::InvalidStep { index });
+        }
+        previous = Some((time_stamp, diff));
+    }
+    Ok(())
+}
+
+/// Provides the entries of the table, so it can be passed to
+/// [`register_source`](super::register_source) or
+/// [`Discovery::Custom`](super::Discovery::Custom), e.g. to make the [`From`]
+/// conversions use a table downloaded elsewhere. Use
+/// [`TaiDateTime::set_leap_seconds`] once conversions already happened.
+impl LeapSecondSource for LeapTable {
+    fn load(&self) -> Result<Vec<LeapEntry>, SourceError> {
+        Ok(self
+            .entries
+            .iter()
+            .map(|&(t, diff)| LeapEntry {
+                utc_instant: OffsetDateTime::UNIX_EPOCH + Duration::seconds(t),
+                tai_offset_after: diff,
+            })
+            .collect())
+    }
+
+    fn expiry(&self) -> Option<OffsetDateTime> {
+        Some(self.expires_at())
+    }
+}
+
+impl TaiDateTime {
+    /// Merges a leap second table into the one used by the [`From`]
+    /// conversions at runtime, e.g. after the application downloaded an up to
+    /// date `leap-seconds.list`, as the one shipped by the operating system
+    /// is often stale, especially in containers. Sources have to be set up
+    /// before the first conversion, while this can be called at any time.
+    ///
+    /// This works just like [`load_leap_seconds_from_bytes`](super::load_leap_seconds_from_bytes):
+    /// leap seconds the current table doesn't know about yet get added, and
+    /// the current table is considered valid until the later of its current
+    /// expiry and the one of the given table. Nothing is removed, so an older
+    /// table never undoes a newer one. The current table is only changed if
+    /// both agree on the difference between TAI and UTC at every leap second
+    /// both know about and the merged table still changes that difference by
+    /// exactly one second at each leap second, otherwise this fails with
+    /// [`SourceError::Invalid`]. Installing a table that adds nothing new
+    /// leaves the current one as it is, so this is cheap to call whenever a
+    /// table got downloaded.
+    ///
+    /// Use [`LeapTable::utc_to_tai`] and [`LeapTable::tai_to_utc`] instead to
+    /// convert with a table without affecting the rest of the process.
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    pub fn set_leap_seconds(table: &LeapTable) -> Result<(), LeapLoadError> {
+        update_effective_table(|current| {
+            merge_custom(
+                current,
+                table.entries.iter().copied(),
+                Some(table.expires_at),
+            )
+            .map_err(|()| LeapLoadError {
+                leap_source: LeapSource::Custom,
+                error: SourceError::Invalid,
+            })
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std", any(windows, unix)))]
+mod tests {
+    use std::sync::Arc;
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::{
+        effective_table,
+        leap_seconds::{expires_at, leap_source},
+        tests::{builtin_table_with, lock_global_table, table_with_next_leap_second},
+    };
+
+    #[test]
+    fn set_leap_seconds() {
+        let _lock = lock_global_table();
+        let table = table_with_next_leap_second();
+        let &(t, diff) = table.entries().last().unwrap();
+        let effective = OffsetDateTime::UNIX_EPOCH + Duration::seconds(t);
+        assert_eq!(TaiDateTime::set_leap_seconds(&table), Ok(()));
+        assert_eq!(
+            TaiDateTime::from(effective) - TaiDateTime::from_utc_with_offset(effective, 0),
+            Duration::seconds(diff),
+        );
+        assert_eq!(leap_source(), LeapSource::Custom);
+        assert_eq!(expires_at(), table.expires_at());
+
+        // Installing the same table again, or an older one, changes nothing,
+        // so the table doesn't get replaced.
+        let current = effective_table();
+        assert_eq!(TaiDateTime::set_leap_seconds(&table), Ok(()));
+        assert_eq!(TaiDateTime::set_leap_seconds(&LeapTable::builtin()), Ok(()));
+        assert!(Arc::ptr_eq(&current, &effective_table()));
+    }
+
+    #[test]
+    fn set_leap_seconds_rejects_invalid_merge() {
+        let _lock = lock_global_table();
+        // Moving the last leap second to a later date is fine on its own, but
+        // once merged, there are two entries with the same difference.
+        let next = table_with_next_leap_second();
+        let mut entries = next.entries().to_vec();
+        let (t, _) = entries.pop().unwrap();
+        let (_, diff) = entries.pop().unwrap();
+        entries.push((t, diff));
+        let table = LeapTable::from_entries(entries, next.expires_at()).unwrap();
+
+        let current = effective_table();
+        assert_eq!(
+            TaiDateTime::set_leap_seconds(&table),
+            Err(LeapLoadError {
+                leap_source: LeapSource::Custom,
+                error: SourceError::Invalid,
+            }),
+        );
+        assert!(Arc::ptr_eq(&current, &effective_table()));
+    }
+
+    #[test]
+    #[cfg(all(unix, not(feature = "no-os-leap-data")))]
+    fn from_system() {
+        use crate::tests::tzdir_with_leap_seconds;
+
+        let _lock = lock_global_table();
+        let expiry = datetime!(2027-07-01 0:00 UTC);
+        let table = builtin_table_with(&[(datetime!(2027-01-01 0:00 UTC), 38)], expiry);
+        let _dir = tzdir_with_leap_seconds(&table);
+        assert_eq!(LeapTable::from_system(), Ok(table));
+
+        // Listing the last leap second of the built-in table later, on its
+        // own, is fine, but not once merged with the built-in table.
+        let mut entries = LEAP_SECONDS.to_vec();
+        let (_, diff) = entries.pop().unwrap();
+        entries.push((datetime!(2027-01-01 0:00 UTC).unix_timestamp(), diff));
+        let _dir = tzdir_with_leap_seconds(&LeapTable::from_entries(entries, expiry).unwrap());
+        let table = LeapTable::from_system();
+        std::env::remove_var("TZDIR");
+        assert_eq!(table, Err(SourceError::Invalid));
+    }
+
+    // A source that can only be loaded together with its expiry.
+    struct Snapshot(Vec<LeapEntry>, Option<OffsetDateTime>);
+
+    impl LeapSecondSource for Snapshot {
+        fn load(&self) -> Result<Vec<LeapEntry>, SourceError> {
+            unreachable!()
+        }
+
+        fn expiry(&self) -> Option<OffsetDateTime> {
+            unreachable!()
+        }
+
+        fn load_with_expiry(
+            &self,
+        ) -> Result<(Vec<LeapEntry>, Option<OffsetDateTime>), SourceError> {
+            Ok((self.0.clone(), self.1))
+        }
+    }
+
+    #[test]
+    fn from_source() {
+        let entries = vec![
+            LeapEntry {
+                utc_instant: datetime!(1972-07-01 0:00 UTC),
+                tai_offset_after: 11,
+            },
+            LeapEntry {
+                utc_instant: datetime!(1973-01-01 0:00 UTC),
+                tai_offset_after: 12,
+            },
+        ];
+        let expected = |expires_at| {
+            LeapTable::from_entries(
+                vec![
+                    (datetime!(1972-07-01 0:00 UTC).unix_timestamp(), 11),
+                    (datetime!(1973-01-01 0:00 UTC).unix_timestamp(), 12),
+                ],
+                expires_at,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            LeapTable::from_source(&Snapshot(
+                entries.clone(),
+                Some(datetime!(1973-06-28 0:00 UTC))
+            )),
+            Ok(expected(datetime!(1973-06-28 0:00 UTC))),
+        );
+        assert_eq!(
+            LeapTable::from_source(&Snapshot(entries.clone(), None)),
+            Ok(expected(datetime!(1973-01-01 0:00 UTC))),
+        );
+
+        let mut unsorted = entries;
+        unsorted.reverse();
+        assert_eq!(
+            LeapTable::from_source(&Snapshot(unsorted, None)),
+            Err(SourceError::Invalid),
+        );
+    }
+
+    #[test]
+    fn builtin_matches_from() {
+        let table = LeapTable::builtin();
+        assert_eq!(table.entries(), LEAP_SECONDS);
+        assert_eq!(table.expires_at().unix_timestamp(), EXPIRES_AT_UTC);
+
+        // Other tests may add leap seconds after the built-in ones to the
+        // global table, so only compare before the last one.
+        let mut rng = fastrand::Rng::with_seed(324);
+        let end = datetime!(2017-01-01 0:00 UTC).unix_timestamp();
+        for _ in 0..10_000 {
+            let utc = OffsetDateTime::UNIX_EPOCH
+                + Duration::new(rng.i64(-86_400..end), rng.i32(0..1_000_000_000));
+            let tai = table.utc_to_tai(utc);
+            assert_eq!(tai, TaiDateTime::from(utc), "{utc}");
+            assert_eq!(table.tai_to_utc(tai), utc, "{utc}");
+        }
+    }
+
+    #[test]
+    fn independent_tables() {
+        // The same leap second, once inserted and once removed.
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let expiry = datetime!(2030-06-28 0:00 UTC);
+        let inserted = builtin_table_with(&[(utc, 38)], expiry);
+        let removed = builtin_table_with(&[(utc, 36)], expiry);
+
+        let before = utc - Duration::SECOND;
+        assert_eq!(
+            inserted.utc_to_tai(utc) - inserted.utc_to_tai(before),
+            Duration::seconds(2),
+        );
+        // 23:59:59 is skipped, so it's the same instant as the midnight.
+        assert_eq!(
+            removed.utc_to_tai(utc) - removed.utc_to_tai(before),
+            Duration::ZERO,
+        );
+        assert_eq!(
+            inserted
+                .tai_utc_offset_at(utc.unix_timestamp())
+                .whole_seconds(),
+            38
+        );
+        assert_eq!(
+            removed
+                .tai_utc_offset_at(utc.unix_timestamp())
+                .whole_seconds(),
+            36
+        );
+        assert_eq!(
+            inserted.tai_utc_offset_at(before.unix_timestamp()),
+            removed.tai_utc_offset_at(before.unix_timestamp()),
+        );
+
+        // Each converts back with its own leap second.
+        for table in [&inserted, &removed] {
+            for utc in [
+                before - Duration::SECOND,
+                utc,
+                utc + Duration::milliseconds(1500),
+            ] {
+                assert_eq!(table.tai_to_utc(table.utc_to_tai(utc)), utc);
+            }
+        }
+        // Inside of the inserted leap second.
+        assert_eq!(
+            inserted.tai_to_utc(inserted.utc_to_tai(utc) - Duration::milliseconds(500)),
+            utc + Duration::milliseconds(500),
+        );
+    }
+
+    #[test]
+    fn invalid_entries() {
+        let t = |year| {
+            time::Date::from_calendar_date(year, time::Month::January, 1)
+                .unwrap()
+                .midnight()
+                .assume_utc()
+                .unix_timestamp()
+        };
+        let expiry = datetime!(2000-01-01 0:00 UTC);
+        assert_eq!(
+            LeapTable::from_entries(vec![(t(1980), 11), (t(1980), 12)], expiry),
+            Err(LeapTableError::NotSorted { index: 1 }),
+        );
+        assert_eq!(
+            LeapTable::from_entries(vec![(t(1980), 11), (t(1979), 12)], expiry),
+            Err(LeapTableError::NotSorted { index: 1 }),
+        );
+        assert_eq!(
+            LeapTable::from_entries(vec![(t(1980), 12)], expiry),
+            Err(LeapTableError::InvalidStep { index: 0 }),
+        );
+        assert_eq!(
+            LeapTable::from_entries(vec![(t(1980), 11), (t(1981), 11)], expiry),
+            Err(LeapTableError::InvalidStep { index: 1 }),
+        );
+        // Both directions are fine.
+        assert!(LeapTable::from_entries(vec![(t(1980), 9), (t(1981), 10)], expiry).is_ok());
+        assert!(LeapTable::from_entries(Vec::new(), expiry).is_ok());
+    }
+}
@@ -1,8 +1,99 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use core::ops::{Add, Sub};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use time::{Duration, OffsetDateTime};
+use core::{
+    ops::{Add, RangeInclusive, Sub},
+    time::Duration as StdDuration,
+};
+
+use time::{
+    macros::datetime, Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday,
+};
+
+use leap_seconds::LeapSource;
+
+mod aggregate;
+#[cfg(feature = "rkyv")]
+mod archive;
+mod batch;
+mod civil;
+#[cfg(feature = "std")]
+mod clock;
+pub mod collections;
+mod config;
+#[cfg(feature = "std")]
+pub mod deadline;
+#[cfg(feature = "dense-table")]
+mod dense;
+mod display;
+mod duration;
+mod error;
+mod explain;
+#[cfg(all(feature = "std", unix))]
+pub mod file_time;
+#[cfg(feature = "hifitime")]
+mod hifitime;
+pub mod high_res;
+mod interval;
+#[cfg(feature = "jiff")]
+mod jiff;
+mod key;
+pub mod leap_seconds;
+#[cfg(feature = "test-util")]
+mod mock;
+mod navigate;
+mod offset;
+mod parse;
+#[cfg(all(feature = "phc", target_os = "linux"))]
+pub mod phc;
+#[cfg(feature = "std")]
+mod platform;
+pub mod raw;
+mod relative;
+#[cfg(feature = "alloc")]
+mod rfc3339;
+pub mod scale;
+pub mod schedule;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "alloc")]
+mod sha1;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+mod wire;
+
+pub use aggregate::{average, min_max};
+#[cfg(feature = "rkyv")]
+pub use archive::ArchivedTaiDateTime;
+pub use batch::{tai_conversions, UtcBucket};
+#[cfg(feature = "alloc")]
+pub use batch::{utc_bucket_indices, utc_slice_to_tai};
+#[cfg(feature = "std")]
+pub use clock::CachedTaiClock;
+#[cfg(all(feature = "std", any(windows, unix)))]
+pub use config::set_global_config;
+pub use config::{Config, ConfigBuilder, ConfigError, Era};
+pub use duration::{
+    tai_duration_between, tai_duration_between_utc, DiffExplain, LeapAwareDuration,
+};
+pub use error::{ClockError, ConversionError, Negative, OutOfRange};
+pub use explain::{ConversionDirection, ConversionTrace, LeapMatch, LeapOrigin};
+pub use interval::TaiInterval;
+pub use key::KeyError;
+#[cfg(feature = "test-util")]
+pub use mock::MockTaiClock;
+pub use navigate::LeapFold;
+pub use offset::TaiUtcOffset;
+pub use parse::ParseError;
+pub use relative::{RelativeStyle, RelativeTime};
+#[cfg(feature = "std")]
+pub use rfc3339::format_now_rfc3339;
+#[cfg(feature = "std")]
+pub use scale::Ut1DateTime;
+pub use scale::{GpsDateTime, TtDateTime};
+pub use wire::{DecodeError, EncodeError};
 
 // The document starts with 1900 instead of the usual 1970, so that's 70 years
 // in seconds.
@@ -47,45 +138,720 @@ const LEAP_SECONDS: &[(i64, i64)] = &[
 ];
 
 const EXPIRES_AT_UTC: i64 = 3896899200 - LEAP_BASE_OFFSET;
-const EXPIRES_AT_TAI: i64 = EXPIRES_AT_UTC + LEAP_SECONDS[LEAP_SECONDS.len() - 1].1;
 
-#[derive(Copy, Clone, Debug)]
+// 1 Jan 1972, from which on TAI and UTC differ by whole seconds.
+const UTC_ERA_START: i64 = 2272060800 - LEAP_BASE_OFFSET;
+
+// Catches mistakes when updating the table: the entries have to be in order,
+// after the start of the UTC era and before the expiry, and each has to change
+// the difference between TAI and UTC by exactly one second.
+const _: () = {
+    let mut previous = (UTC_ERA_START, FIRST_LEAP_SECONDS_DIFF);
+    let mut i = 0;
+    while i < LEAP_SECONDS.len() {
+        let (t, diff) = LEAP_SECONDS[i];
+        assert!(t > previous.0, "leap seconds out of order");
+        assert!(
+            diff == previous.1 + 1 || diff == previous.1 - 1,
+            "leap second changes the difference by more than a second"
+        );
+        previous = (t, diff);
+        i += 1;
+    }
+    assert!(
+        EXPIRES_AT_UTC > previous.0,
+        "leap second table expires before its last entry"
+    );
+};
+
+/// Returns whether the `TZ` environment variable selects one of the `right/`
+/// time zones, such as `right/UTC`. With those, the C library expects the
+/// system's real time clock to count the leap seconds as well, i.e. to be set
+/// to TAI - 10 seconds rather than to UTC. The current time is then read
+/// accordingly whenever it doesn't come from a dedicated TAI clock, so the
+/// leap seconds don't get applied twice.
+#[cfg(feature = "std")]
+pub fn detect_right_tz() -> bool {
+    let Some(tz) = std::env::var_os("TZ") else {
+        return false;
+    };
+    let Some(tz) = tz.to_str() else { return false };
+    let tz = tz.strip_prefix(':').unwrap_or(tz);
+    tz.starts_with("right/") || tz.contains("/right/")
+}
+
+/// The UTC date times that can be converted to a [`TaiDateTime`] and back
+/// without panicking. This is the whole range of [`OffsetDateTime`], so it
+/// depends on whether `time`'s `large-dates` feature is enabled, which this
+/// crate's `large-dates` feature does. Either way the underlying [`Duration`]
+/// can represent any instant in that range, with lots of room to spare.
+pub const SUPPORTED_UTC_RANGE: RangeInclusive<OffsetDateTime> =
+    PrimitiveDateTime::MIN.assume_utc()..=PrimitiveDateTime::MAX.assume_utc();
+
+// How far Terrestrial Time (TT) is ahead of TAI, by definition.
+const TT_MINUS_TAI: Duration = Duration::new(32, 184_000_000);
+
+// The Modified Julian Date of 1970-01-01.
+const MJD_UNIX_EPOCH: i64 = 40587;
+
+const NANOS_PER_DAY: i128 = 86_400 * 1_000_000_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TaiDateTime(Duration);
 
 impl TaiDateTime {
-    #[cfg(all(
-        feature = "std",
-        not(any(
-            target_os = "android",
-            target_os = "emscripten",
-            target_os = "fuchsia",
-            target_os = "linux"
-        ))
-    ))]
+    /// The earliest representable instant. It's a whole number of seconds
+    /// before the epoch, so the seconds of every instant fit into an `i64` when
+    /// rounded down, as with [`as_timespec`](Self::as_timespec).
+    pub const MIN: Self = Self(Duration::seconds(i64::MIN));
+
+    /// The latest representable instant.
+    pub const MAX: Self = Self(Duration::MAX);
+
+    /// Returns the current time, read from the kernel's TAI clock if there is
+    /// one and otherwise converted from the system's UTC clock with the leap
+    /// second table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no clock on the target. Use
+    /// [`try_now`](Self::try_now) to handle that instead.
+    #[cfg(feature = "std")]
     pub fn now() -> Self {
-        OffsetDateTime::now_utc().into()
+        Self::try_now().expect("there is no clock on this target")
+    }
+
+    /// Returns the current time like [`now`](Self::now), or `None` if there is
+    /// no clock on the target. That's the case without the `std` feature and
+    /// on `wasm32-unknown-unknown`, where the standard library can't read the
+    /// time. This allows libraries to use the crate regardless of the target
+    /// and handle the lack of a clock themselves.
+    pub fn try_now() -> Option<Self> {
+        #[cfg(feature = "std")]
+        {
+            #[cfg(feature = "test-util")]
+            if let Some(now) = mock::now() {
+                return Some(now);
+            }
+            if let Some(since_epoch) = platform::clock_tai() {
+                return Some(Self(since_epoch));
+            }
+            platform::clock_utc().map(Self::from_system_clock)
+        }
+        #[cfg(not(feature = "std"))]
+        None
+    }
+
+    /// Returns the current time straight from the kernel's TAI clock. Unlike
+    /// [`now`](Self::now), this doesn't fall back to the system's UTC clock and
+    /// the leap second table, but fails if the target has no TAI clock, or if
+    /// the clock can't be trusted because the kernel's TAI offset was never set
+    /// up, in which case it reads the same as the UTC clock. This is meant for
+    /// systems that require a TAI clock disciplined by e.g. PTP.
+    #[cfg(feature = "std")]
+    pub fn try_now_kernel() -> Result<Self, ClockError> {
+        #[cfg(feature = "test-util")]
+        if let Some(now) = mock::now_kernel() {
+            return now;
+        }
+        let since_epoch = platform::clock_tai().ok_or(ClockError::Unavailable)?;
+        let utc = platform::clock_realtime().ok_or(ClockError::Unavailable)?;
+        // TAI has been at least 10 seconds ahead of UTC since 1972.
+        if (since_epoch - utc).abs() < Duration::SECOND {
+            return Err(ClockError::Unconfigured);
+        }
+        Ok(Self(since_epoch))
+    }
+
+    // Converts a reading of the system's real time clock. That is usually UTC,
+    // but with a `right/` time zone it counts the leap seconds as well.
+    #[cfg(feature = "std")]
+    fn from_system_clock(since_epoch: Duration) -> Self {
+        if detect_right_tz() {
+            Self(since_epoch + Duration::seconds(FIRST_LEAP_SECONDS_DIFF))
+        } else {
+            let diff = leap_seconds_at_utc(floor_seconds(since_epoch));
+            Self(since_epoch + Duration::new(diff, 0))
+        }
+    }
+
+    /// Returns the current time with the resolution of the system's clock tick,
+    /// which is a lot cheaper to query than [`now`](Self::now). There is no
+    /// coarse TAI clock, so this reads the coarse UTC clock and converts it
+    /// with the leap second table, which unlike [`now`](Self::now) doesn't rely
+    /// on the kernel's TAI offset being set up. On targets without a coarse
+    /// clock this is the same as [`now`](Self::now).
+    #[cfg(feature = "std")]
+    pub fn now_coarse() -> Self {
+        #[cfg(feature = "test-util")]
+        if let Some(now) = mock::now() {
+            return now;
+        }
+        match platform::clock_realtime_coarse() {
+            Some(since_epoch) => Self::from_system_clock(since_epoch),
+            None => Self::now(),
+        }
+    }
+
+    /// Converts a UTC date time to TAI. Every [`OffsetDateTime`] is
+    /// representable as a [`TaiDateTime`], so there is nothing to clamp and
+    /// this is the same as the [`From`] conversion. It exists as the
+    /// counterpart of [`to_utc_saturating`](Self::to_utc_saturating).
+    pub fn from_utc_saturating(time: OffsetDateTime) -> Self {
+        time.into()
+    }
+
+    /// Converts a UTC date time to TAI, treating any instant past the
+    /// [expiry](leap_seconds::expires_at) of the leap second table with the
+    /// last known difference between TAI and UTC. This is what the [`From`]
+    /// conversion does implicitly, but calling this makes the choice explicit:
+    /// a leap second announced after the table was published is not taken into
+    /// account, so instants after it end up off by a second.
+    pub fn from_utc_clamped(time: OffsetDateTime) -> Self {
+        time.into()
+    }
+
+    /// Converts a UTC date time to TAI. Unlike the [`From`] conversion, this
+    /// fails for instants the leap second table doesn't cover, i.e. before 1972
+    /// or at or after its [expiry](leap_seconds::expires_at).
+    ///
+    /// This can't be a [`TryFrom`] implementation, as the [`From`]
+    /// implementation already implies an infallible one.
+    pub fn try_from_utc(time: OffsetDateTime) -> Result<Self, ConversionError> {
+        check_within_table(time)?;
+        Ok(time.into())
+    }
+
+    /// Converts to UTC. Unlike the [`From`] conversion, this never panics. It
+    /// fails for instants the leap second table doesn't cover, i.e. before 1972
+    /// or at or after its [expiry](leap_seconds::expires_at), for instants
+    /// outside of the range of [`OffsetDateTime`], and for instants inside of
+    /// an inserted leap second.
+    pub fn try_to_utc(self) -> Result<OffsetDateTime, ConversionError> {
+        let table = leap_table();
+        let tai_time_stamp = floor_seconds(self.0);
+        let index = seek_tai(&table, table.len(), tai_time_stamp);
+        let diff = diff_before(&table, index);
+
+        let utc = self
+            .0
+            .checked_sub(Duration::new(diff, 0))
+            .and_then(|unix_time_stamp| OffsetDateTime::UNIX_EPOCH.checked_add(unix_time_stamp))
+            .ok_or(ConversionError::TargetOutOfRange)?;
+        check_within_table(utc)?;
+
+        if let Some(&(t, _)) = table.get(index) {
+            if tai_time_stamp >= t + diff {
+                return Err(ConversionError::InsideLeapSecond);
+            }
+        }
+
+        Ok(utc)
+    }
+
+    /// Converts to UTC, clamping to the minimum or maximum [`OffsetDateTime`]
+    /// if the instant is outside of [`SUPPORTED_UTC_RANGE`].
+    pub fn to_utc_saturating(&self) -> OffsetDateTime {
+        let (min, max) = (*SUPPORTED_UTC_RANGE.start(), *SUPPORTED_UTC_RANGE.end());
+        let clamp = || if self.0.is_negative() { min } else { max };
+
+        let diff = leap_seconds_at_tai(floor_seconds(self.0));
+        let Some(unix_time_stamp) = self.0.checked_sub(Duration::new(diff, 0)) else {
+            return clamp();
+        };
+        OffsetDateTime::UNIX_EPOCH
+            .checked_add(unix_time_stamp)
+            .unwrap_or_else(clamp)
+    }
+
+    /// Converts a UTC date time to TAI with the given difference between TAI
+    /// and UTC in seconds, without consulting the leap second table at all.
+    /// This is meant for data whose historical difference is known for sure
+    /// from elsewhere, e.g. a lab logbook, or that predates 1972.
+    pub fn from_utc_with_offset(time: OffsetDateTime, offset_secs: i64) -> Self {
+        utc_to_tai(time, |_| offset_secs)
+    }
+
+    /// Converts to UTC with the given difference between TAI and UTC in
+    /// seconds, without consulting the leap second table at all. This is the
+    /// inverse of [`from_utc_with_offset`](Self::from_utc_with_offset).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting UTC date time is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn to_utc_with_offset(self, offset_secs: i64) -> OffsetDateTime {
+        tai_to_utc(self, |_| offset_secs)
+    }
+
+    /// Converts to the local date time at the given UTC offset, e.g. for
+    /// displaying the instant to a user. This first converts to UTC, removing
+    /// the leap seconds, and only then applies the offset, which is the only
+    /// correct order, as the leap second table is indexed by UTC. An instant
+    /// inside of an inserted leap second is converted to the same time of day
+    /// in the second after it, just like with the [`From`] conversion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either the UTC or the local date time is outside of the range
+    /// of [`OffsetDateTime`].
+    pub fn to_offset(self, offset: UtcOffset) -> OffsetDateTime {
+        OffsetDateTime::from(self).to_offset(offset)
+    }
+
+    /// Returns whether the instant is before 1972-01-01 00:00:00 UTC, i.e.
+    /// 1972-01-01 00:00:10 TAI.
+    ///
+    /// Only from then on does UTC tick in SI seconds, with whole leap seconds
+    /// keeping it close to UT1. From 1961 on, UTC was instead kept close to
+    /// UT1 with seconds of a slightly different length and steps of fractions
+    /// of a second, while TAI itself only starts in 1958. There is no
+    /// meaningful whole number of seconds between the two before 1972, so all
+    /// conversions use the 10 seconds of 1972 for earlier instants, all the
+    /// way back. That way no durations across 1972 gain or lose any seconds.
+    pub fn is_before_utc_era(self) -> bool {
+        floor_seconds(self.0) < UTC_ERA_START + FIRST_LEAP_SECONDS_DIFF
     }
 
-    #[cfg(all(
-        feature = "std",
-        any(
-            target_os = "android",
-            target_os = "emscripten",
-            target_os = "fuchsia",
-            target_os = "linux"
+    /// Splits the instant into whole TAI seconds since 1970-01-01 00:00:00 TAI
+    /// and the nanoseconds within that second, like a POSIX `timespec`. Just
+    /// like there, the nanoseconds are always within `0..1_000_000_000`, so
+    /// for instants before the epoch the seconds are rounded down rather than
+    /// towards zero, i.e. -1.25 s is `(-2, 750_000_000)`.
+    pub fn as_timespec(self) -> (i64, i32) {
+        debug_assert!(self.is_normalized());
+        let nanos = self.0.subsec_nanoseconds().rem_euclid(1_000_000_000);
+        (floor_seconds(self.0), nanos)
+    }
+
+    // Wraps the time since 1970-01-01 00:00:00 TAI, saturating at `MIN`, as a
+    // `Duration` can be almost a second earlier.
+    pub(crate) fn saturating(since_epoch: Duration) -> Self {
+        Self(since_epoch.max(Self::MIN.0))
+    }
+
+    // Wraps the time since 1970-01-01 00:00:00 TAI, or returns `None` if it's
+    // earlier than `MIN`.
+    pub(crate) fn checked(since_epoch: Duration) -> Option<Self> {
+        (since_epoch >= Self::MIN.0).then_some(Self(since_epoch))
+    }
+
+    // Whether the instant is in range and the seconds and nanoseconds have the
+    // same sign and the nanoseconds are less than a second, so every instant
+    // has exactly one decomposition. `Duration` upholds this for every value, including the
+    // results of its arithmetic, which is what makes the derived comparisons
+    // and hashing agree with the total time and the encodings deterministic.
+    // This only guards against that ever changing.
+    fn is_normalized(self) -> bool {
+        let (secs, nanos) = (self.0.whole_seconds(), self.0.subsec_nanoseconds());
+        self >= Self::MIN
+            && nanos.unsigned_abs() < 1_000_000_000
+            && (secs == 0 || nanos == 0 || (secs < 0) == (nanos < 0))
+    }
+
+    /// Creates an instant from whole TAI seconds since 1970-01-01 00:00:00 TAI
+    /// and the nanoseconds within that second, like a POSIX `timespec`.
+    /// Nanoseconds outside of `0..1_000_000_000` carry into the seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instant is before [`MIN`](Self::MIN) or after
+    /// [`MAX`](Self::MAX), which is only possible if the nanoseconds carry.
+    pub fn from_timespec(secs: i64, nanos: i32) -> Self {
+        Self::checked(Duration::new(secs, nanos)).expect("overflow constructing instant")
+    }
+
+    // Converts to the UTC calendar. An instant within an inserted leap second
+    // is reported as 23:59:59 along with `true`, so it can be displayed as
+    // 23:59:60. The hint speeds up the table lookup for nearby instants and
+    // gets updated.
+    fn to_utc_label(self, hint: &mut usize) -> (OffsetDateTime, bool) {
+        let table = leap_table();
+        let tai_time_stamp = floor_seconds(self.0);
+        let index = seek_tai(&table, *hint, tai_time_stamp);
+        *hint = index;
+        let diff = diff_before(&table, index);
+
+        if let Some(&(t, _)) = table.get(index) {
+            if tai_time_stamp >= t + diff {
+                let last_second = Duration::new(t - 1, self.0.subsec_nanoseconds());
+                return (OffsetDateTime::UNIX_EPOCH + last_second, true);
+            }
+        }
+
+        (
+            OffsetDateTime::UNIX_EPOCH + (self.0 - Duration::new(diff, 0)),
+            false,
         )
-    ))]
-    pub fn now() -> Self {
-        use nix::time::{clock_gettime, ClockId};
+    }
+
+    // The whole seconds since 1970-01-01 00:00:00 UTC, with an instant within
+    // an inserted leap second counted as the second before it, i.e. 23:59:59,
+    // so it belongs to the day, hour and minute the leap second ends. The hint
+    // speeds up the table lookup for nearby instants and gets updated.
+    pub(crate) fn utc_label_seconds(self, hint: &mut usize) -> i64 {
+        let table = leap_table();
+        let tai_time_stamp = floor_seconds(self.0);
+        let index = seek_tai(&table, *hint, tai_time_stamp);
+        *hint = index;
+        let diff = diff_before(&table, index);
+        match table.get(index) {
+            Some(&(t, _)) if tai_time_stamp >= t + diff => t - 1,
+            _ => tai_time_stamp.saturating_sub(diff),
+        }
+    }
+
+    fn utc_bucket_index(self, bucket: UtcBucket) -> i64 {
+        let mut hint = usize::MAX;
+        self.utc_label_seconds(&mut hint)
+            .div_euclid(bucket.seconds())
+    }
+
+    /// Returns the number of the UTC day the instant is in, counting from
+    /// 1970-01-01, which is day 0, so earlier days are negative. An instant
+    /// inside of an inserted leap second belongs to the day the leap second
+    /// ends. This only takes integer math, so it's a lot cheaper than going
+    /// through the UTC calendar, e.g. for bucketing events by day.
+    pub fn utc_day_index(&self) -> i64 {
+        self.utc_bucket_index(UtcBucket::Day)
+    }
+
+    /// Returns the number of the UTC hour the instant is in, counting from
+    /// 1970-01-01 00:00 UTC, like [`utc_day_index`](Self::utc_day_index).
+    pub fn utc_hour_index(&self) -> i64 {
+        self.utc_bucket_index(UtcBucket::Hour)
+    }
+
+    /// Returns the number of the UTC minute the instant is in, counting from
+    /// 1970-01-01 00:00 UTC, like [`utc_day_index`](Self::utc_day_index).
+    pub fn utc_minute_index(&self) -> i64 {
+        self.utc_bucket_index(UtcBucket::Minute)
+    }
+
+    /// Returns the instant of midnight UTC at the start of the day with the
+    /// given number, counting from 1970-01-01 like
+    /// [`utc_day_index`](Self::utc_day_index). The result saturates for days
+    /// too far from 1970 to be represented.
+    pub fn from_utc_day_index(day: i64) -> Self {
+        let unix_time_stamp = day.saturating_mul(UtcBucket::Day.seconds());
+        let diff = leap_seconds_at_utc(unix_time_stamp);
+        Self(Duration::seconds(unix_time_stamp.saturating_add(diff)))
+    }
+
+    /// Returns the UTC date and time of day with a single lookup in the leap
+    /// second table. A [`Time`] can't represent the leap second 23:59:60, so
+    /// an instant inside of an inserted leap second is reported as 23:59:59 of
+    /// the day the leap second ends, keeping the fraction of the second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the UTC date time is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn to_utc_date_time(self) -> (Date, Time) {
+        let (utc, _) = self.to_utc_label(&mut 0);
+        (utc.date(), utc.time())
+    }
+
+    /// Returns the seconds since 1970-01-01 00:00:00 TAI as a floating point
+    /// number. An `f64` only has 53 bits of precision, so nanoseconds are only
+    /// represented exactly within about ±104 days of the epoch, and for
+    /// present day instants the precision is around 240 ns.
+    pub fn as_secs_f64(self) -> f64 {
+        self.0.as_seconds_f64()
+    }
+
+    /// Returns the reading of a Terrestrial Time (TT) clock at this instant, in
+    /// seconds since its reading of 1970-01-01 00:00:00. TT is always exactly
+    /// 32.184 seconds ahead of TAI, so this is the TAI seconds since
+    /// 1970-01-01 00:00:00 TAI plus 32.184, with the same precision as
+    /// [`as_secs_f64`](Self::as_secs_f64).
+    pub fn to_tt_seconds(self) -> f64 {
+        self.0.saturating_add(TT_MINUS_TAI).as_seconds_f64()
+    }
+
+    /// Returns the Modified Julian Date on the TAI time scale, i.e. the days
+    /// since 1858-11-17 00:00:00 TAI. 1970-01-01 00:00:00 UTC is MJD
+    /// 40587.000115740... on this scale, as TAI was 10 s ahead at the time. For
+    /// present day instants, the precision of an `f64` is around 0.6 µs, use
+    /// [`to_mjd_parts`](Self::to_mjd_parts) for an exact representation.
+    pub fn to_mjd_tai(self) -> f64 {
+        let (day, nanos) = self.to_mjd_parts();
+        day as f64 + nanos as f64 / NANOS_PER_DAY as f64
+    }
+
+    /// Creates an instant from a Modified Julian Date on the TAI time scale,
+    /// rounded to the nearest nanosecond. Returns an error if the date is not
+    /// finite or outside of the range of a [`TaiDateTime`].
+    pub fn from_mjd_tai(mjd: f64) -> Result<Self, OutOfRange> {
+        if !mjd.is_finite() {
+            return Err(OutOfRange);
+        }
+        if mjd.abs() >= 1e17 {
+            return Err(OutOfRange);
+        }
+        // Splitting off the day first keeps the fraction as precise as
+        // possible. `floor` and `round` aren't available without `std`, but
+        // the casts are exact for values of this magnitude.
+        let mut day = mjd as i64;
+        if day as f64 > mjd {
+            day -= 1;
+        }
+        let nanos = ((mjd - day as f64) * NANOS_PER_DAY as f64 + 0.5) as i128;
+        let nanos = (day - MJD_UNIX_EPOCH) as i128 * NANOS_PER_DAY + nanos;
+        let secs = i64::try_from(nanos.div_euclid(1_000_000_000)).map_err(|_| OutOfRange)?;
+        Ok(Self(Duration::new(
+            secs,
+            nanos.rem_euclid(1_000_000_000) as i32,
+        )))
+    }
+
+    /// Returns the Modified Julian Date on the TAI time scale as the day and
+    /// the nanoseconds within that day. Unlike
+    /// [`to_mjd_tai`](Self::to_mjd_tai), this is exact. The nanoseconds are
+    /// always within `0..86_400_000_000_000`, even before the MJD epoch.
+    pub fn to_mjd_parts(self) -> (i64, u64) {
+        let nanos = self.0.whole_nanoseconds();
+        let day = nanos.div_euclid(NANOS_PER_DAY) as i64 + MJD_UNIX_EPOCH;
+        (day, nanos.rem_euclid(NANOS_PER_DAY) as u64)
+    }
+
+    /// Returns the Modified Julian Date on the TAI time scale as the day and the
+    /// fraction of that day, as used by SLR and VLBI software. The fraction is
+    /// always within `0.0..1.0`, even before the MJD epoch. Splitting off the
+    /// day keeps the precision of the fraction at around 10 ps regardless of
+    /// the date, unlike with [`to_mjd_tai`](Self::to_mjd_tai). Use
+    /// [`to_mjd_parts`](Self::to_mjd_parts) for the exact nanoseconds instead.
+    pub fn mjd_parts(self) -> (i64, f64) {
+        let (day, nanos) = self.to_mjd_parts();
+        (day, nanos as f64 / NANOS_PER_DAY as f64)
+    }
+
+    /// Returns the calendar date and time of day on the TAI time scale. As TAI
+    /// has no leap seconds, every day on this scale has exactly 86400 seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the date is outside of the range of [`PrimitiveDateTime`].
+    pub fn to_primitive_tai(self) -> PrimitiveDateTime {
+        datetime!(1970-01-01 0:00) + self.0
+    }
+
+    /// Returns the day of the week on the TAI time scale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the date is outside of the range of [`PrimitiveDateTime`].
+    pub fn weekday(self) -> Weekday {
+        self.to_primitive_tai().weekday()
+    }
+
+    /// Returns the day of the year on the TAI time scale, starting with 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the date is outside of the range of [`PrimitiveDateTime`].
+    pub fn ordinal(self) -> u16 {
+        self.to_primitive_tai().ordinal()
+    }
+
+    /// Returns the exact signed number of nanoseconds from `earlier` to this
+    /// instant. Unlike subtracting the two, this can't overflow, as any
+    /// difference fits into an `i128`.
+    pub fn signed_nanos_since(self, earlier: TaiDateTime) -> i128 {
+        let secs = self.0.whole_seconds() as i128 - earlier.0.whole_seconds() as i128;
+        let nanos = self.0.subsec_nanoseconds() as i128 - earlier.0.subsec_nanoseconds() as i128;
+        secs * 1_000_000_000 + nanos
+    }
+
+    /// Creates an instant from the nanoseconds since 1970-01-01 00:00:00 TAI.
+    /// Fails if the instant is outside of the range between
+    /// [`MIN`](Self::MIN) and [`MAX`](Self::MAX).
+    pub fn from_unix_tai_nanos(nanos: i128) -> Result<Self, OutOfRange> {
+        // Both parts have the same sign, just like within a `Duration`, so
+        // every representable instant can be created.
+        let secs = i64::try_from(nanos / 1_000_000_000).map_err(|_| OutOfRange)?;
+        Self::checked(Duration::new(secs, (nanos % 1_000_000_000) as i32)).ok_or(OutOfRange)
+    }
+
+    /// Returns the nanoseconds since 1970-01-01 00:00:00 TAI. Every instant
+    /// fits into an `i128`.
+    pub fn unix_tai_nanos(&self) -> i128 {
+        self.0.whole_nanoseconds()
+    }
+
+    /// Creates an instant from a UNIX time stamp, i.e. the whole seconds since
+    /// 1970-01-01 00:00:00 UTC without the leap seconds. This is the same as
+    /// converting the [`OffsetDateTime`] of the time stamp, without having to
+    /// construct one, except that it isn't limited to its range, but saturates
+    /// at [`MAX`](Self::MAX) instead.
+    pub fn from_unix_utc_timestamp(secs: i64) -> Self {
+        let diff = leap_seconds_at_utc(secs);
+        Self(Duration::seconds(secs.saturating_add(diff)))
+    }
+
+    /// Creates an instant from the nanoseconds since 1970-01-01 00:00:00 UTC
+    /// without the leap seconds, like
+    /// [`from_unix_utc_timestamp`](Self::from_unix_utc_timestamp). Fails if
+    /// the instant is outside of the range between [`MIN`](Self::MIN) and
+    /// [`MAX`](Self::MAX).
+    pub fn from_unix_utc_timestamp_nanos(nanos: i128) -> Result<Self, OutOfRange> {
+        let secs = i64::try_from(nanos.div_euclid(1_000_000_000)).map_err(|_| OutOfRange)?;
+        let diff = leap_seconds_at_utc(secs) as i128 * 1_000_000_000;
+        Self::from_unix_tai_nanos(nanos.checked_add(diff).ok_or(OutOfRange)?)
+    }
 
-        if let Ok(time) = clock_gettime(ClockId::CLOCK_TAI) {
-            Self(Duration::new(time.tv_sec() as i64, time.tv_nsec() as i32))
+    /// Returns the UNIX time stamp of the instant, rounded down to whole
+    /// seconds. This is the same as the time stamp of the converted
+    /// [`OffsetDateTime`], without having to construct one, so an instant
+    /// inside of an inserted leap second has the time stamp of the midnight
+    /// right after it. Unlike the conversion, this never panics, but saturates
+    /// at the range of an `i64`.
+    pub fn to_unix_utc_timestamp(&self) -> i64 {
+        let tai_time_stamp = floor_seconds(self.0);
+        tai_time_stamp.saturating_sub(leap_seconds_at_tai(tai_time_stamp))
+    }
+
+    /// Returns the nanoseconds since 1970-01-01 00:00:00 UTC without the leap
+    /// seconds, like [`to_unix_utc_timestamp`](Self::to_unix_utc_timestamp).
+    /// Every instant fits into an `i128`.
+    pub fn to_unix_utc_timestamp_nanos(&self) -> i128 {
+        let diff = leap_seconds_at_tai(floor_seconds(self.0));
+        self.0.whole_nanoseconds() - diff as i128 * 1_000_000_000
+    }
+
+    /// Creates an instant from a UNIX time stamp like
+    /// [`from_unix_utc_timestamp`](Self::from_unix_utc_timestamp), but only
+    /// with the leap seconds built into the crate, ignoring any that were
+    /// loaded or announced at runtime. In exchange it can be used in constant
+    /// expressions.
+    pub const fn from_unix_utc_timestamp_builtin(secs: i64) -> Self {
+        let mut index = LEAP_SECONDS.len();
+        while index > 0 && LEAP_SECONDS[index - 1].0 > secs {
+            index -= 1;
+        }
+        let diff = if index == 0 {
+            FIRST_LEAP_SECONDS_DIFF
         } else {
-            OffsetDateTime::now_utc().into()
+            LEAP_SECONDS[index - 1].1
+        };
+        Self(Duration::seconds(secs.saturating_add(diff)))
+    }
+
+    /// Returns the UNIX time stamp of the instant like
+    /// [`to_unix_utc_timestamp`](Self::to_unix_utc_timestamp), but only with
+    /// the leap seconds built into the crate, ignoring any that were loaded or
+    /// announced at runtime. In exchange it can be used in constant
+    /// expressions.
+    pub const fn to_unix_utc_timestamp_builtin(&self) -> i64 {
+        let mut tai_time_stamp = self.0.whole_seconds();
+        if self.0.subsec_nanoseconds() < 0 {
+            tai_time_stamp = tai_time_stamp.saturating_sub(1);
         }
+        let mut index = LEAP_SECONDS.len();
+        while index > 0 && LEAP_SECONDS[index - 1].0 + LEAP_SECONDS[index - 1].1 > tai_time_stamp {
+            index -= 1;
+        }
+        let diff = if index == 0 {
+            FIRST_LEAP_SECONDS_DIFF
+        } else {
+            LEAP_SECONDS[index - 1].1
+        };
+        tai_time_stamp.saturating_sub(diff)
+    }
+
+    /// Returns the instant the given duration after this one, or [`None`] if
+    /// it's out of range.
+    pub fn checked_add_std(self, duration: StdDuration) -> Option<Self> {
+        Self::from_unix_tai_nanos(self.unix_tai_nanos() + duration.as_nanos() as i128).ok()
+    }
+
+    /// Returns the instant the given duration before this one, or [`None`] if
+    /// it's out of range.
+    pub fn checked_sub_std(self, duration: StdDuration) -> Option<Self> {
+        Self::from_unix_tai_nanos(self.unix_tai_nanos() - duration.as_nanos() as i128).ok()
+    }
+
+    /// Returns the actual elapsed time from `earlier` to this instant, like
+    /// `SystemTime::duration_since`. Fails if `earlier` is actually later than
+    /// this instant, with the error holding how much later it is. Unlike
+    /// subtracting the two, this never panics. A difference that doesn't fit,
+    /// which is only possible between instants close to [`MIN`](Self::MIN) and
+    /// [`MAX`](Self::MAX), saturates to the maximum duration.
+    pub fn duration_since(&self, earlier: Self) -> Result<StdDuration, Negative> {
+        let nanos = self.signed_nanos_since(earlier);
+        let magnitude = nanos.unsigned_abs();
+        let duration = match u64::try_from(magnitude / 1_000_000_000) {
+            Ok(secs) => StdDuration::new(secs, (magnitude % 1_000_000_000) as u32),
+            Err(_) => StdDuration::MAX,
+        };
+        if nanos < 0 {
+            Err(Negative(duration))
+        } else {
+            Ok(duration)
+        }
+    }
+
+    /// Returns the instant halfway between the two, rounded down to the
+    /// nanosecond, i.e. towards the earlier instant. This never overflows,
+    /// unlike adding half of the difference, which may not fit into a
+    /// [`Duration`].
+    pub fn midpoint(self, other: Self) -> Self {
+        let nanos = (self.unix_tai_nanos() + other.unix_tai_nanos()).div_euclid(2);
+        // The midpoint is between the two instants, so it's in range.
+        Self::from_unix_tai_nanos(nanos).unwrap_or(self)
+    }
+
+    /// Returns the actual elapsed time from `other` to this instant, like
+    /// `self - other`, but saturating to [`Duration::MIN`] or
+    /// [`Duration::MAX`] instead of panicking if it doesn't fit into a
+    /// [`Duration`], e.g. for [`TaiDateTime::MAX`] - [`TaiDateTime::MIN`].
+    pub fn saturating_duration_since(self, other: TaiDateTime) -> Duration {
+        self.0.saturating_sub(other.0)
+    }
+
+    /// Returns the difference between the two instants on the UTC clock, i.e.
+    /// without the leap seconds in between. Subtracting the instants directly
+    /// includes them instead, so across an inserted leap second this is one
+    /// second less than `self - other`. This corresponds to `self - other`,
+    /// so it is positive if `self` is the later instant. An instant inside of a
+    /// leap second counts as the midnight right after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either instant is outside of the range of [`OffsetDateTime`].
+    pub fn utc_duration_between(self, other: TaiDateTime) -> Duration {
+        OffsetDateTime::from(self) - OffsetDateTime::from(other)
+    }
+
+    /// Returns the non-negative difference between the two instants,
+    /// regardless of their order. This never panics, a difference that doesn't
+    /// fit into a [`Duration`] saturates to [`Duration::MAX`].
+    pub fn abs_diff(self, other: TaiDateTime) -> Duration {
+        let nanos = self.signed_nanos_since(other).unsigned_abs();
+        match i64::try_from(nanos / 1_000_000_000) {
+            Ok(secs) => Duration::new(secs, (nanos % 1_000_000_000) as i32),
+            Err(_) => Duration::MAX,
+        }
+    }
+
+    /// Returns whether the two instants are at most `tolerance` apart, in
+    /// either direction. A negative tolerance is never met.
+    pub fn approx_eq(self, other: TaiDateTime, tolerance: Duration) -> bool {
+        self.signed_nanos_since(other).abs() <= tolerance.whole_nanoseconds()
     }
 }
 
+/// The actual elapsed time between two instants, including any leap seconds
+/// in between. Use [`TaiDateTime::utc_duration_between`] for the difference on
+/// the UTC clock instead.
+///
+/// # Panics
+///
+/// Panics if the difference doesn't fit into a [`Duration`], which is only
+/// possible for instants more than about 292 billion years apart, such as
+/// [`TaiDateTime::MAX`] and [`TaiDateTime::MIN`]. Use
+/// [`TaiDateTime::saturating_duration_since`] to saturate instead, or
+/// [`TaiDateTime::signed_nanos_since`] for the exact difference.
 impl Sub for TaiDateTime {
     type Output = time::Duration;
 
@@ -94,250 +860,1517 @@ impl Sub for TaiDateTime {
     }
 }
 
+/// # Panics
+///
+/// Panics if the result is out of range.
 impl Add<Duration> for TaiDateTime {
     type Output = Self;
 
     fn add(self, rhs: Duration) -> Self::Output {
-        Self(self.0 + rhs)
+        Self::checked(self.0 + rhs).expect("overflow when adding duration to instant")
     }
 }
 
-#[cfg(all(feature = "std", windows))]
-fn read_additional_leap_seconds() -> Option<Box<[(i64, i64)]>> {
-    use std::{
-        mem::{self, MaybeUninit},
-        ptr,
-    };
+/// # Panics
+///
+/// Panics if the result is out of range.
+impl Sub<Duration> for TaiDateTime {
+    type Output = Self;
 
-    use winapi::{
-        shared::{
-            minwindef::HKEY,
-            winerror::{ERROR_MORE_DATA, ERROR_SUCCESS},
-        },
-        um::{
-            winnt::KEY_READ,
-            winreg::{RegCloseKey, RegOpenKeyExW, RegQueryValueExW, HKEY_LOCAL_MACHINE, LSTATUS},
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self::checked(self.0 - rhs).expect("overflow when subtracting duration from instant")
+    }
+}
+
+/// # Panics
+///
+/// Panics if the result is out of range. Use [`TaiDateTime::checked_add_std`]
+/// to handle that instead.
+impl Add<StdDuration> for TaiDateTime {
+    type Output = Self;
+
+    fn add(self, rhs: StdDuration) -> Self::Output {
+        self.checked_add_std(rhs)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+/// # Panics
+///
+/// Panics if the result is out of range. Use [`TaiDateTime::checked_sub_std`]
+/// to handle that instead.
+impl Sub<StdDuration> for TaiDateTime {
+    type Output = Self;
+
+    fn sub(self, rhs: StdDuration) -> Self::Output {
+        self.checked_sub_std(rhs)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+#[cfg(all(feature = "windows", windows, not(feature = "no-os-leap-data")))]
+const PLATFORM_LEAP_SOURCE: LeapSource = LeapSource::WindowsRegistry;
+
+#[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+const PLATFORM_LEAP_SOURCE: LeapSource = LeapSource::UnixLeapFile;
+
+// The built-in table merged with the leap seconds of all sources.
+#[cfg(all(feature = "std", any(windows, unix)))]
+#[derive(Clone, PartialEq)]
+struct EffectiveTable {
+    entries: Vec<(i64, i64)>,
+    // The UTC time stamps of the entries that were announced at runtime.
+    provisional: Vec<i64>,
+    expires_at: i64,
+    source: LeapSource,
+    // Whether any source of leap seconds was loaded successfully.
+    loaded: bool,
+    // Where the platform specific leap seconds were loaded from, if they were.
+    platform_location: Option<String>,
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+type SharedTable = std::sync::RwLock<std::sync::Arc<EffectiveTable>>;
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+static EFFECTIVE_TABLE: once_cell::sync::OnceCell<SharedTable> = once_cell::sync::OnceCell::new();
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+static UPDATE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+// Replaces the effective table with a modified copy, unless the modification
+// fails or doesn't change anything. Conversions that are still looking at the
+// previous table keep it alive until they are done, after which it's freed.
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn update_effective_table<E>(
+    update: impl FnOnce(&mut EffectiveTable) -> Result<(), E>,
+) -> Result<(), E> {
+    let _guard = UPDATE_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let current = effective_table();
+    let mut table = EffectiveTable::clone(&current);
+    update(&mut table)?;
+    if table != *current {
+        *shared_table()
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = std::sync::Arc::new(table);
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn effective_table() -> std::sync::Arc<EffectiveTable> {
+    let table = shared_table()
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    std::sync::Arc::clone(&table)
+}
+
+// The effective table, which gets built on first use.
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn shared_table() -> &'static SharedTable {
+    // The report of loading the platform specific leap seconds, which is only
+    // set on the thread that builds the table. The hooks are called once the
+    // table is built, so they can do conversions themselves.
+    let mut platform_report = None;
+    let table = EFFECTIVE_TABLE.get_or_init(|| {
+        let (table, report) = build_effective_table(|f| leap_seconds::load_sources(f));
+        platform_report = report;
+        std::sync::RwLock::new(std::sync::Arc::new(table))
+    });
+    if let Some(report) = platform_report {
+        leap_seconds::notify_load(&report);
+    }
+    table
+}
+
+// The sources of leap seconds as `load_sources` passes them on: what each one
+// is reported as, what it loaded, its expiry and where it was loaded from. A
+// warning is returned if the source had to be dropped.
+#[cfg(all(feature = "std", any(windows, unix)))]
+type LoadedSource<'a> = &'a mut dyn FnMut(
+    LeapSource,
+    Result<alloc::vec::Vec<leap_seconds::LeapEntry>, leap_seconds::SourceError>,
+    Option<OffsetDateTime>,
+    Option<String>,
+) -> Option<leap_seconds::LoadWarning>;
+
+// Builds the effective table from the built-in table and the sources that
+// `load` passes on, returning whatever `load` returns alongside it.
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn build_effective_table<R>(load: impl FnOnce(LoadedSource<'_>) -> R) -> (EffectiveTable, R) {
+    // Entries are merged in regardless of whether they are before or after
+    // the built-in table's expiry, so a leap second the built-in table
+    // doesn't know about is never skipped. A source that disagrees with the
+    // built-in table or a source with a higher priority at any leap second,
+    // or that would make the merged table invalid otherwise, is dropped as a
+    // whole, including its expiry. Lookups only ever search the merged table,
+    // so the built-in entries and the loaded ones can't both claim an
+    // instant, not even right at the built-in table's expiry.
+    let mut entries = LEAP_SECONDS.to_vec();
+    let mut expires_at = EXPIRES_AT_UTC;
+    let mut source = None;
+    let mut any_attempted = false;
+    let mut any_loaded = false;
+    let mut platform_location = None;
+    let loaded = load(&mut |kind, loaded, expiry, location| {
+        any_attempted = true;
+        let loaded = loaded.ok()?;
+        let conflict = leap_seconds::LoadWarning::ConflictingSource { leap_source: kind };
+        let mut merged = entries.clone();
+        for entry in loaded {
+            let time_stamp = entry.utc_instant.unix_timestamp();
+            match merged.binary_search_by_key(&time_stamp, |&(t, _)| t) {
+                Ok(index) if merged[index].1 != entry.tai_offset_after => return Some(conflict),
+                Ok(_) => {}
+                Err(index) => merged.insert(index, (time_stamp, entry.tai_offset_after)),
+            }
+        }
+        if leap_seconds::check_entries(&merged).is_err() {
+            return Some(conflict);
+        }
+
+        any_loaded = true;
+        if location.is_some() {
+            platform_location = location;
+        }
+        if let Some(expiry) = expiry {
+            expires_at = expires_at.max(expiry.unix_timestamp());
+        }
+        if merged.len() > entries.len() {
+            source = source.or(Some(kind));
+        }
+        entries = merged;
+        None
+    });
+
+    let table = EffectiveTable {
+        entries,
+        provisional: Vec::new(),
+        expires_at,
+        source: match source {
+            Some(source) => source,
+            None if any_loaded || !any_attempted => LeapSource::BuiltIn,
+            None => LeapSource::FallbackStale,
         },
+        loaded: any_loaded,
+        platform_location,
     };
+    (table, loaded)
+}
+
+// The leap second table used for conversions, as pairs of the UTC time stamp
+// from which on a difference between TAI and UTC applies and that difference.
+// The effective table may get replaced at runtime, so this keeps the one that
+// was current when it was looked up alive for as long as it's used.
+#[derive(Clone)]
+enum LeapTableRef {
+    #[cfg(any(feature = "test-util", not(all(feature = "std", any(windows, unix)))))]
+    Static(&'static [(i64, i64)]),
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    Effective(std::sync::Arc<EffectiveTable>),
+}
+
+impl LeapTableRef {
+    // The UTC time stamps of the entries that were announced at runtime.
+    fn provisional(&self) -> &[i64] {
+        match self {
+            #[cfg(any(feature = "test-util", not(all(feature = "std", any(windows, unix)))))]
+            Self::Static(_) => &[],
+            #[cfg(all(feature = "std", any(windows, unix)))]
+            Self::Effective(table) => &table.provisional,
+        }
+    }
+}
+
+impl core::ops::Deref for LeapTableRef {
+    type Target = [(i64, i64)];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            #[cfg(any(feature = "test-util", not(all(feature = "std", any(windows, unix)))))]
+            Self::Static(entries) => entries,
+            #[cfg(all(feature = "std", any(windows, unix)))]
+            Self::Effective(table) => &table.entries,
+        }
+    }
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn leap_table() -> LeapTableRef {
+    #[cfg(feature = "test-util")]
+    if let Some((entries, _)) = mock::leap_table() {
+        return LeapTableRef::Static(entries);
+    }
+    LeapTableRef::Effective(effective_table())
+}
+
+#[cfg(not(all(feature = "std", any(windows, unix))))]
+fn leap_table() -> LeapTableRef {
+    #[cfg(feature = "test-util")]
+    if let Some((entries, _)) = mock::leap_table() {
+        return LeapTableRef::Static(entries);
+    }
+    LeapTableRef::Static(LEAP_SECONDS)
+}
+
+// The UTC time stamp from which on the leap second table can no longer be
+// relied upon.
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn leap_table_expiry() -> i64 {
+    #[cfg(feature = "test-util")]
+    if let Some((_, expires_at)) = mock::leap_table() {
+        return expires_at;
+    }
+    effective_table().expires_at
+}
+
+#[cfg(not(all(feature = "std", any(windows, unix))))]
+fn leap_table_expiry() -> i64 {
+    #[cfg(feature = "test-util")]
+    if let Some((_, expires_at)) = mock::leap_table() {
+        return expires_at;
+    }
+    EXPIRES_AT_UTC
+}
+
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn leap_table_source() -> LeapSource {
+    #[cfg(feature = "test-util")]
+    if mock::leap_table().is_some() {
+        return LeapSource::Custom;
+    }
+    effective_table().source
+}
+
+#[cfg(not(all(feature = "std", any(windows, unix))))]
+fn leap_table_source() -> LeapSource {
+    #[cfg(feature = "test-util")]
+    if mock::leap_table().is_some() {
+        return LeapSource::Custom;
+    }
+    LeapSource::BuiltIn
+}
+
+// Whether any source of leap seconds was loaded and where the platform specific
+// leap seconds were loaded from, if they were.
+#[cfg(all(feature = "std", any(windows, unix)))]
+fn leap_table_provenance() -> (bool, Option<alloc::string::String>) {
+    #[cfg(feature = "test-util")]
+    if mock::leap_table().is_some() {
+        return (true, None);
+    }
+    let table = effective_table();
+    (table.loaded, table.platform_location.clone())
+}
+
+#[cfg(all(feature = "alloc", not(all(feature = "std", any(windows, unix)))))]
+fn leap_table_provenance() -> (bool, Option<alloc::string::String>) {
+    #[cfg(feature = "test-util")]
+    if mock::leap_table().is_some() {
+        return (true, None);
+    }
+    (false, None)
+}
+
+// The whole seconds of the duration, rounded down rather than towards zero, so
+// -0.5 s is within the second starting at -1 s, just like 0.5 s is within the
+// second starting at 0 s.
+fn floor_seconds(duration: Duration) -> i64 {
+    let secs = duration.whole_seconds();
+    if duration.subsec_nanoseconds() < 0 {
+        // Only saturates for durations before `TaiDateTime::MIN`, which are
+        // all way before the first leap second anyway.
+        secs.saturating_sub(1)
+    } else {
+        secs
+    }
+}
 
-    struct RegKey(HKEY);
+// The entry of the leap second table that applies right before the one at the
+// given index, i.e. the one matched by a lookup that found the index, or `None`
+// if no entry applies yet.
+fn entry_before(table: &[(i64, i64)], index: usize) -> Option<(i64, i64)> {
+    index.checked_sub(1).map(|previous| table[previous])
+}
+
+// The difference between TAI and UTC in seconds before the entry of the leap
+// second table at the given index applies.
+fn diff_before(table: &[(i64, i64)], index: usize) -> i64 {
+    entry_before(table, index).map_or(FIRST_LEAP_SECONDS_DIFF, |(_, diff)| diff)
+}
+
+// Finds the number of entries of the leap second table that already apply at
+// the given UTC time stamp. The search starts at `hint`, so it's cheap if the
+// time stamp is close to the one the hint was found for.
+fn seek_utc(table: &[(i64, i64)], hint: usize, unix_time_stamp: i64) -> usize {
+    let mut index = hint.min(table.len());
+    while index > 0 && table[index - 1].0 > unix_time_stamp {
+        index -= 1;
+    }
+    while index < table.len() && table[index].0 <= unix_time_stamp {
+        index += 1;
+    }
+    index
+}
+
+// Finds the number of entries of the leap second table that already apply at
+// the given TAI time stamp. The search starts at `hint`, so it's cheap if the
+// time stamp is close to the one the hint was found for.
+fn seek_tai(table: &[(i64, i64)], hint: usize, tai_time_stamp: i64) -> usize {
+    let mut index = hint.min(table.len());
+    while index > 0 && table[index - 1].0 + table[index - 1].1 > tai_time_stamp {
+        index -= 1;
+    }
+    while index < table.len() && table[index].0 + table[index].1 <= tai_time_stamp {
+        index += 1;
+    }
+    index
+}
+
+// The difference between TAI and UTC in seconds at the given UTC time stamp.
+#[cfg(not(feature = "lookup-cache"))]
+fn leap_seconds_at_utc(unix_time_stamp: i64) -> i64 {
+    #[cfg(feature = "dense-table")]
+    if let Some(diff) = dense::leap_seconds_at_utc(unix_time_stamp) {
+        return diff;
+    }
+    let table = leap_table();
+    diff_before(&table, seek_utc(&table, table.len(), unix_time_stamp))
+}
+
+// The last lookup on this thread: the table it was done in, the span of UTC
+// time stamps between the two surrounding leap seconds, and the difference
+// between TAI and UTC during that span. Keeping the table alive here means its
+// address can't be reused by another one, so the address identifies it. As the
+// span ends right at the next leap second, the day of a leap second is handled
+// like any other.
+#[cfg(feature = "lookup-cache")]
+thread_local! {
+    static LAST_UTC_LOOKUP: core::cell::RefCell<Option<(LeapTableRef, i64, i64, i64)>> =
+        const { core::cell::RefCell::new(None) };
+}
 
-    impl Drop for RegKey {
-        fn drop(&mut self) {
-            unsafe {
-                RegCloseKey(self.0);
+// The difference between TAI and UTC in seconds at the given UTC time stamp.
+#[cfg(feature = "lookup-cache")]
+fn leap_seconds_at_utc(unix_time_stamp: i64) -> i64 {
+    #[cfg(feature = "dense-table")]
+    if let Some(diff) = dense::leap_seconds_at_utc(unix_time_stamp) {
+        return diff;
+    }
+    let table = leap_table();
+    LAST_UTC_LOOKUP.with(|last| {
+        let mut last = last.borrow_mut();
+        if let Some((last_table, start, end, diff)) = &*last {
+            if last_table.as_ptr() == table.as_ptr() && (*start..*end).contains(&unix_time_stamp) {
+                return *diff;
             }
         }
+        let index = seek_utc(&table, table.len(), unix_time_stamp);
+        let start = index.checked_sub(1).map_or(i64::MIN, |i| table[i].0);
+        let end = table.get(index).map_or(i64::MAX, |&(t, _)| t);
+        let diff = diff_before(&table, index);
+        *last = Some((table, start, end, diff));
+        diff
+    })
+}
+
+// The difference between TAI and UTC in seconds at the given TAI time stamp.
+fn leap_seconds_at_tai(tai_time_stamp: i64) -> i64 {
+    let table = leap_table();
+    diff_before(&table, seek_tai(&table, table.len(), tai_time_stamp))
+}
+
+/// Every [`OffsetDateTime`] is representable as a [`TaiDateTime`], so this
+/// conversion never panics, regardless of whether `time`'s `large-dates`
+/// feature is enabled.
+///
+/// A clock that repeats 23:59:59 during an inserted leap second reads the same
+/// for two instants. This conversion always picks the
+/// [earlier](LeapFold::Earlier) one, see
+/// [`from_utc_disambiguated`](TaiDateTime::from_utc_disambiguated).
+impl From<OffsetDateTime> for TaiDateTime {
+    fn from(time: OffsetDateTime) -> Self {
+        utc_to_tai(time, leap_seconds_at_utc)
+    }
+}
+
+/// # Panics
+///
+/// Panics if the resulting UTC date time is outside of the range of
+/// [`OffsetDateTime`], i.e. before -9999-01-01 00:00:00 UTC or after
+/// 9999-12-31 23:59:59.999999999 UTC. With `time`'s `large-dates` feature
+/// enabled, the years -999999 and 999999 are the limits instead. Instants
+/// converted from within [`SUPPORTED_UTC_RANGE`] always convert back. Use
+/// [`TaiDateTime::to_utc_saturating`] to clamp to that range instead.
+impl From<TaiDateTime> for OffsetDateTime {
+    fn from(time: TaiDateTime) -> Self {
+        tai_to_utc(time, leap_seconds_at_tai)
+    }
+}
+
+/// Returns the canonical TAI instant for a UTC date time, which is suitable as
+/// a cache key.
+///
+/// An [`OffsetDateTime`] can't represent an inserted leap second 23:59:60, so
+/// converting to UTC maps each instant inside of the leap second to the same
+/// time of day in the second after it, i.e. two TAI instants share one UTC date
+/// time. Of those, this always picks the one after the leap second, so
+/// `canonical_tai_for_utc(OffsetDateTime::from(t))` is the same for `t` inside
+/// of the leap second and one second later, and converting that to UTC and
+/// back again doesn't change it anymore. Likewise, a UTC second that a removed
+/// leap second skips maps to the same instant as the second after it.
+///
+/// This is the same as the [`From`] conversion, which is guaranteed to keep
+/// working this way.
+pub fn canonical_tai_for_utc(time: OffsetDateTime) -> TaiDateTime {
+    TaiDateTime::from(time)
+}
+
+/// Returns the TAI instant of midnight UTC at the start of the given date. As
+/// leap seconds are at the end of a day, the difference between TAI and UTC
+/// from after any leap second at the end of the previous day applies.
+pub fn tai_at_utc_midnight(date: Date) -> TaiDateTime {
+    TaiDateTime::from(date.midnight().assume_utc())
+}
+
+// Converts a UTC date time to TAI, looking up the difference between TAI and
+// UTC at a UTC time stamp with the given function.
+fn utc_to_tai(time: OffsetDateTime, leap_seconds_at_utc: impl FnOnce(i64) -> i64) -> TaiDateTime {
+    let unix_time_stamp = time - OffsetDateTime::UNIX_EPOCH;
+    let diff = leap_seconds_at_utc(floor_seconds(unix_time_stamp));
+    TaiDateTime(unix_time_stamp + Duration::new(diff, 0))
+}
+
+// Converts a TAI date time to UTC, looking up the difference between TAI and
+// UTC at a TAI time stamp with the given function.
+fn tai_to_utc(time: TaiDateTime, leap_seconds_at_tai: impl FnOnce(i64) -> i64) -> OffsetDateTime {
+    let diff = leap_seconds_at_tai(floor_seconds(time.0));
+    OffsetDateTime::UNIX_EPOCH + (time.0 - Duration::new(diff, 0))
+}
+
+// Checks whether the leap second table covers the given UTC instant.
+fn check_within_table(time: OffsetDateTime) -> Result<(), ConversionError> {
+    let unix_time_stamp = time.unix_timestamp();
+    if unix_time_stamp < UTC_ERA_START {
+        return Err(ConversionError::BeforeTaiEpoch);
     }
+    if unix_time_stamp >= leap_table_expiry() {
+        return Err(ConversionError::BeyondTableExpiry {
+            expiry: leap_seconds::expires_at(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use time::{macros::date, Month};
+
+    use super::*;
 
-    // https://github.com/microsoft/STL/blob/e28f9561233a58d48d893094ed3a6bc0c5ee6ad9/stl/inc/__msvc_tzdb.hpp#L27
-    #[repr(C)]
-    struct LeapInfo {
-        year: u16,
-        month: u16,
-        day: u16,
-        hour: u16,
-        negative: u16,
-        _reserved: u16,
+    // Serializes the tests that change the leap second table used for
+    // conversions with the ones that depend on what it contains beyond the
+    // built-in entries, and the ones that point `TZDIR` elsewhere.
+    #[cfg(feature = "std")]
+    static GLOBAL_TABLE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[cfg(feature = "std")]
+    pub(crate) fn lock_global_table() -> std::sync::MutexGuard<'static, ()> {
+        GLOBAL_TABLE
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
     }
 
-    unsafe {
-        let mut leap_sec_key = MaybeUninit::uninit();
-        let status = RegOpenKeyExW(
-            HKEY_LOCAL_MACHINE,
-            b"SYSTEM\\CurrentControlSet\\Control\\LeapSecondInformation\0"
-                .map(|b| b as _)
-                .as_ptr(),
-            0,
-            KEY_READ,
-            leap_sec_key.as_mut_ptr(),
+    // The built-in leap second table with the given leap seconds added after
+    // it, each with the difference between TAI and UTC from then on.
+    #[cfg(feature = "std")]
+    pub(crate) fn builtin_table_with(
+        leap_seconds: &[(OffsetDateTime, i64)],
+        expires_at: OffsetDateTime,
+    ) -> leap_seconds::LeapTable {
+        let mut entries = LEAP_SECONDS.to_vec();
+        entries.extend(
+            leap_seconds
+                .iter()
+                .map(|&(utc, diff)| (utc.unix_timestamp(), diff)),
         );
-        if status != ERROR_SUCCESS as LSTATUS {
-            return None;
+        leap_seconds::LeapTable::from_entries(entries, expires_at).unwrap()
+    }
+
+    // The leap second table used for conversions with a leap second added at
+    // the start of the year after it expires, so it can be merged into it no
+    // matter what other tests added before. It expires half a year after the
+    // new leap second.
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    pub(crate) fn table_with_next_leap_second() -> leap_seconds::LeapTable {
+        let current = leap_seconds::LeapTable::current();
+        let mut entries = current.entries().to_vec();
+        let year = current.expires_at().year() + 1;
+        let effective = Date::from_calendar_date(year, Month::January, 1)
+            .unwrap()
+            .midnight()
+            .assume_utc();
+        let diff = entries.last().map_or(FIRST_LEAP_SECONDS_DIFF, |&(_, d)| d) + 1;
+        entries.push((effective.unix_timestamp(), diff));
+        leap_seconds::LeapTable::from_entries(entries, effective + Duration::days(180)).unwrap()
+    }
+
+    // Points `TZDIR` at a directory with a `leap-seconds.list` that lists the
+    // given table, for as long as the returned directory lives. The table used
+    // for conversions is built beforehand, so it isn't affected.
+    #[cfg(all(feature = "std", unix, not(feature = "no-os-leap-data")))]
+    pub(crate) fn tzdir_with_leap_seconds(table: &leap_seconds::LeapTable) -> tempfile::TempDir {
+        leap_table();
+        let list = leap_seconds::TableSnapshot {
+            entries: table.iter().collect(),
+            expires_at: Some(table.expires_at()),
+            updated_at: None,
         }
-        let leap_sec_key = RegKey(leap_sec_key.assume_init());
+        .to_leap_seconds_list();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("leap-seconds.list"), list).unwrap();
+        std::env::set_var("TZDIR", dir.path());
+        dir
+    }
 
-        let reg_subkey_name = b"LeapSeconds\0".map(|b| b as _);
+    // Checks the structural invariants of a leap second table, returning the
+    // index of the first entry that violates them, or the length of the table
+    // if it expires before its last entry.
+    fn check_invariants(entries: &[(i64, i64)], expires_at: i64) -> Result<(), usize> {
+        let mut previous = (UTC_ERA_START, FIRST_LEAP_SECONDS_DIFF);
+        for (index, &(t, diff)) in entries.iter().enumerate() {
+            if t <= previous.0 || (diff - previous.1).abs() != 1 {
+                return Err(index);
+            }
+            previous = (t, diff);
+        }
+        if expires_at <= previous.0 {
+            return Err(entries.len());
+        }
+        Ok(())
+    }
 
-        let mut byte_size = 0;
-        let status = RegQueryValueExW(
-            leap_sec_key.0,
-            reg_subkey_name.as_ptr(),
-            ptr::null_mut(),
-            ptr::null_mut(),
-            ptr::null_mut(),
-            &mut byte_size,
+    #[test]
+    fn builtin_table_invariants() {
+        assert_eq!(check_invariants(LEAP_SECONDS, EXPIRES_AT_UTC), Ok(()));
+    }
+
+    #[test]
+    fn builtin_table_dates() {
+        // So far, leap seconds have only been inserted at the end of June or
+        // December.
+        for &(t, _) in LEAP_SECONDS {
+            let time = OffsetDateTime::from_unix_timestamp(t).unwrap();
+            assert_eq!(time.time(), Time::MIDNIGHT);
+            assert_eq!(time.day(), 1);
+            assert!(matches!(time.month(), Month::January | Month::July));
+        }
+        assert_eq!(LEAP_SECONDS.len(), 27);
+        assert_eq!(
+            LEAP_SECONDS.last(),
+            Some(&(datetime!(2017-01-01 0:00 UTC).unix_timestamp(), 37)),
         );
-        if (status != ERROR_SUCCESS as LSTATUS && status != ERROR_MORE_DATA as LSTATUS)
-            || byte_size == 0
-        {
-            return None;
-        }
-
-        let count = byte_size as usize / mem::size_of::<LeapInfo>();
-        if byte_size as usize % mem::size_of::<LeapInfo>() != 0 {
-            return None;
-        }
-
-        let mut elements = Vec::<LeapInfo>::with_capacity(count);
-
-        let mut new_byte_size = byte_size;
-        let status = RegQueryValueExW(
-            leap_sec_key.0,
-            reg_subkey_name.as_ptr(),
-            ptr::null_mut(),
-            ptr::null_mut(),
-            elements.as_mut_ptr().cast(),
-            &mut new_byte_size,
-        );
-        if status != ERROR_SUCCESS as LSTATUS || new_byte_size != byte_size {
-            return None;
-        }
-        elements.set_len(count);
-
-        // The Windows Registry started tracking leap seconds since June 2018.
-        // The initial difference between TAI and UTC is therefore 37 seconds.
-        let mut diff = 37;
-        let mut list = Vec::new();
-        for element in elements {
-            let Ok(year) = element.year.try_into() else { continue };
-            let Ok(month) = u8::try_from(element.month) else { continue };
-            let Ok(month) = month.try_into() else { continue };
-            let Ok(day) = element.day.try_into() else { continue };
-            let Ok(date) = time::Date::from_calendar_date(year, month, day) else { continue };
-            let Ok(hour) = element.hour.try_into() else { continue };
-            let Ok(date_time) = date.with_hms(hour, 59, 59) else { continue };
-            let time_stamp = date_time.assume_utc().unix_timestamp() + 1;
-            if element.negative != 0 {
-                diff -= 1;
-            } else {
-                diff += 1;
-            }
-            if time_stamp >= EXPIRES_AT_UTC {
-                list.push((time_stamp, diff));
-            }
+    }
+
+    #[test]
+    fn expiry_on_tai_scale() {
+        // The built-in table expires on 28 June 2023 UTC, when TAI is 37
+        // seconds ahead.
+        let expires_at_tai = datetime!(2023-06-28 0:00:37 UTC).unix_timestamp();
+        let last_diff = LEAP_SECONDS[LEAP_SECONDS.len() - 1].1;
+        assert_eq!(EXPIRES_AT_UTC + last_diff, expires_at_tai);
+        let index = seek_tai(LEAP_SECONDS, LEAP_SECONDS.len(), expires_at_tai);
+        assert_eq!(index, LEAP_SECONDS.len());
+        assert_eq!(diff_before(LEAP_SECONDS, index), last_diff);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn synthetic_table_invariants() {
+        use alloc::vec::Vec;
+
+        use leap_seconds::{LeapTable, LeapTableError};
+
+        let at = |time: OffsetDateTime| time.unix_timestamp();
+        let mut extended = LEAP_SECONDS.to_vec();
+        extended.push((at(datetime!(2027-01-01 0:00 UTC)), 38));
+        // A negative leap second.
+        extended.push((at(datetime!(2028-01-01 0:00 UTC)), 37));
+        let expires_at = datetime!(2028-06-28 0:00 UTC);
+        assert_eq!(check_invariants(&extended, at(expires_at)), Ok(()));
+        assert!(LeapTable::from_entries(extended.clone(), expires_at).is_ok());
+
+        let last = extended.len() - 1;
+        let with_last = |entry| {
+            let mut entries: Vec<_> = extended[..last].to_vec();
+            entries.push(entry);
+            entries
+        };
+
+        let repeated = with_last((extended[last - 1].0, 37));
+        assert_eq!(check_invariants(&repeated, at(expires_at)), Err(last));
+        assert_eq!(
+            LeapTable::from_entries(repeated, expires_at),
+            Err(LeapTableError::NotSorted { index: last }),
+        );
+
+        let skipped = with_last((extended[last].0, 40));
+        assert_eq!(check_invariants(&skipped, at(expires_at)), Err(last));
+        assert_eq!(
+            LeapTable::from_entries(skipped, expires_at),
+            Err(LeapTableError::InvalidStep { index: last }),
+        );
+
+        let unchanged = with_last((extended[last].0, 38));
+        assert_eq!(check_invariants(&unchanged, at(expires_at)), Err(last));
+
+        let stale = at(datetime!(2027-06-28 0:00 UTC));
+        assert_eq!(check_invariants(&extended, stale), Err(extended.len()));
+    }
+
+    // A source that lists the given leap seconds, effective at the start of
+    // the given years, and expires at the start of the given year.
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    #[derive(Clone, Copy)]
+    struct MockSource(&'static [(i32, i64)], i32);
+
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    fn start_of_year(year: i32) -> OffsetDateTime {
+        Date::from_calendar_date(year, Month::January, 1)
+            .unwrap()
+            .midnight()
+            .assume_utc()
+    }
+
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    impl leap_seconds::LeapSecondSource for MockSource {
+        fn load(&self) -> Result<Vec<leap_seconds::LeapEntry>, leap_seconds::SourceError> {
+            Ok(self
+                .0
+                .iter()
+                .map(|&(year, tai_offset_after)| leap_seconds::LeapEntry {
+                    utc_instant: start_of_year(year),
+                    tai_offset_after,
+                })
+                .collect())
         }
 
-        Some(list.into())
+        fn expiry(&self) -> Option<OffsetDateTime> {
+            Some(start_of_year(self.1))
+        }
     }
-}
 
-#[cfg(all(feature = "std", unix))]
-fn read_additional_leap_seconds() -> Option<Box<[(i64, i64)]>> {
-    use time::Month;
-
-    let file = std::fs::read_to_string("/usr/share/zoneinfo/leapseconds").ok()?;
-    let mut elements = Vec::new();
-    let mut diff = FIRST_LEAP_SECONDS_DIFF;
-    for line in file.split('\n') {
-        let Some(rem) = line.strip_prefix("Leap\t") else { continue };
-
-        let Some((year, rem)) = rem.split_once('\t') else { continue };
-        let Ok(year) = year.parse() else { continue };
-        let Some((month, rem)) = rem.split_once('\t') else { continue };
-        let month = match month {
-            "Jan" => Month::January,
-            "Feb" => Month::February,
-            "Mar" => Month::March,
-            "Apr" => Month::April,
-            "May" => Month::May,
-            "Jun" => Month::June,
-            "Jul" => Month::July,
-            "Aug" => Month::August,
-            "Sep" => Month::September,
-            "Oct" => Month::October,
-            "Nov" => Month::November,
-            "Dec" => Month::December,
-            _ => continue,
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    #[test]
+    fn disagreeing_sources() {
+        // Both sources are valid on their own, but disagree with each other
+        // about 2027.
+        const FIRST: MockSource = MockSource(&[(2027, 38), (2028, 39)], 2029);
+        const SECOND: MockSource = MockSource(&[(2027, 36), (2030, 37)], 2031);
+        // Disagrees with the built-in table about 2017.
+        const BROKEN: MockSource = MockSource(&[(2017, 99)], 2040);
+        // Skips from 37 to 40 seconds, after the built-in table or `SECOND`.
+        const SKIPPING: MockSource = MockSource(&[(2032, 40)], 2040);
+        let build = |registered: &[MockSource], custom: MockSource| {
+            let sources: Vec<Box<dyn leap_seconds::LeapSecondSource + Send + Sync>> =
+                registered.iter().map(|&s| Box::new(s) as _).collect();
+            let (table, report) = build_effective_table(|f| {
+                leap_seconds::load_discovered(
+                    &sources,
+                    leap_seconds::Discovery::Custom(Box::new(custom)),
+                    |_| None,
+                    f,
+                )
+            });
+            (table, report.unwrap().warnings)
         };
-        let Some((day, rem)) = rem.split_once('\t') else { continue };
-        let Ok(day) = day.parse() else { continue };
-        let Ok(date) = time::Date::from_calendar_date(year, month, day) else { continue };
-
-        let Some((hour, rem)) = rem.split_once(':') else { continue };
-        let Ok(hour) = hour.parse() else { continue };
-        let Some((minute, rem)) = rem.split_once(':') else { continue };
-        let Ok(minute) = minute.parse() else { continue };
-        let Some((second, rem)) = rem.split_once('\t') else { continue };
-        let Ok(second) = second.parse() else { continue };
-        let Ok(date_time) = date.with_hms(hour, minute, u8::min(second, 59)) else { continue };
-        let mut time_stamp = date_time.assume_utc().unix_timestamp();
-
-        let Some((plus_minus, _)) = rem.split_once('\t') else { continue };
-        match plus_minus {
-            "+" => {
-                time_stamp += 1;
-                diff += 1
-            }
-            "-" => diff -= 1,
-            _ => continue,
+        let with = |added: &[(i32, i64)]| {
+            let mut entries = LEAP_SECONDS.to_vec();
+            entries.extend(
+                added
+                    .iter()
+                    .map(|&(year, diff)| (start_of_year(year).unix_timestamp(), diff)),
+            );
+            entries
+        };
+        let conflict = leap_seconds::LoadWarning::ConflictingSource {
+            leap_source: LeapSource::Custom,
+        };
+
+        // The first source that gets loaded, i.e. the last one registered,
+        // wins, and any source conflicting with the table so far is dropped
+        // along with its expiry.
+        let (table, warnings) = build(&[FIRST, SECOND], BROKEN);
+        assert_eq!(table.entries, with(&[(2027, 38), (2028, 39)]));
+        assert_eq!(table.expires_at, start_of_year(2029).unix_timestamp());
+        assert_eq!(table.source, LeapSource::Custom);
+        assert!(table.loaded);
+        assert_eq!(warnings, [conflict.clone(), conflict.clone()]);
+
+        let (table, warnings) = build(&[SECOND, FIRST], SKIPPING);
+        assert_eq!(table.entries, with(&[(2027, 36), (2030, 37)]));
+        assert_eq!(table.expires_at, start_of_year(2031).unix_timestamp());
+        assert_eq!(warnings, [conflict.clone(), conflict.clone()]);
+
+        // The merged table that `SKIPPING` would have resulted in.
+        let skipped = with(&[(2027, 36), (2030, 37), (2032, 40)]);
+        assert!(leap_seconds::check_entries(&skipped).is_err());
+
+        let (table, warnings) = build(&[], SKIPPING);
+        assert_eq!(table.entries, LEAP_SECONDS);
+        assert_eq!(table.expires_at, EXPIRES_AT_UTC);
+        assert_eq!(table.source, LeapSource::FallbackStale);
+        assert!(!table.loaded);
+        assert_eq!(warnings, [conflict]);
+    }
+
+    #[cfg(all(feature = "std", any(windows, unix)))]
+    #[test]
+    fn entry_at_builtin_expiry() {
+        // A source with a leap second right when the built-in table expires.
+        let expiry = OffsetDateTime::UNIX_EPOCH + Duration::seconds(EXPIRES_AT_UTC);
+        let source = builtin_table_with(&[(expiry, 38)], datetime!(2024-01-01 0:00 UTC));
+        let sources: Vec<Box<dyn leap_seconds::LeapSecondSource + Send + Sync>> =
+            vec![Box::new(source)];
+        let (table, _) = build_effective_table(|f| {
+            leap_seconds::load_discovered(
+                &sources,
+                leap_seconds::Discovery::BuiltinOnly,
+                |_| None,
+                f,
+            )
+        });
+        assert_eq!(table.entries.len(), LEAP_SECONDS.len() + 1);
+        assert_eq!(table.entries.last(), Some(&(EXPIRES_AT_UTC, 38)));
+
+        // Converting with the merged table, the way the `From` conversions do,
+        // the new offset applies from the expiry on, and only from then on.
+        let at_utc = |t| {
+            diff_before(
+                &table.entries,
+                seek_utc(&table.entries, table.entries.len(), t),
+            )
+        };
+        let at_tai = |t| {
+            diff_before(
+                &table.entries,
+                seek_tai(&table.entries, table.entries.len(), t),
+            )
+        };
+        let expiry = OffsetDateTime::UNIX_EPOCH + Duration::seconds(EXPIRES_AT_UTC);
+        for (utc, diff) in [
+            (expiry - Duration::NANOSECOND, 37),
+            (expiry, 38),
+            (expiry + Duration::NANOSECOND, 38),
+        ] {
+            let tai = utc_to_tai(utc, at_utc);
+            assert_eq!(tai, TaiDateTime::from_utc_with_offset(utc, diff));
+            assert_eq!(tai_to_utc(tai, at_tai), utc);
+        }
+        // The leap second before the expiry is the only instant that maps to
+        // it, rather than both sides claiming the boundary.
+        let tai = utc_to_tai(expiry, at_utc);
+        let leap_second = tai - Duration::SECOND;
+        assert_eq!(at_tai(floor_seconds(leap_second.0)), 37);
+        assert_eq!(tai_to_utc(leap_second, at_tai), expiry);
+        assert_eq!(
+            utc_to_tai(expiry - Duration::SECOND, at_utc),
+            leap_second - Duration::SECOND,
+        );
+    }
+
+    #[cfg(feature = "lookup-cache")]
+    #[test]
+    fn cached_lookups() {
+        let _lock = lock_global_table();
+        let uncached = |unix_time_stamp| {
+            let table = leap_table();
+            diff_before(&table, seek_utc(&table, table.len(), unix_time_stamp))
+        };
+
+        // Every minute of the day of the last leap second and every second
+        // around it, in order, so most lookups hit the cache.
+        let midnight = datetime!(2017-01-01 0:00 UTC).unix_timestamp();
+        for t in (midnight - 86_400..midnight + 86_400).step_by(60) {
+            assert_eq!(leap_seconds_at_utc(t), uncached(t), "at {t}");
+        }
+        for t in midnight - 3..midnight + 3 {
+            assert_eq!(leap_seconds_at_utc(t), uncached(t), "at {t}");
+        }
+        // Back and forth across the leap second, so the cached span is always
+        // the wrong one.
+        for t in [midnight, midnight - 1, midnight, midnight - 1] {
+            assert_eq!(leap_seconds_at_utc(t), uncached(t), "at {t}");
         }
 
-        if time_stamp >= EXPIRES_AT_UTC {
-            elements.push((time_stamp, diff));
+        // Jumping around all over the table.
+        let mut rng = fastrand::Rng::with_seed(318);
+        for _ in 0..10_000 {
+            let t = rng.i64(UTC_ERA_START - 86_400..EXPIRES_AT_UTC + 86_400);
+            assert_eq!(leap_seconds_at_utc(t), uncached(t), "at {t}");
         }
     }
-    Some(elements.into())
-}
 
-#[cfg(all(feature = "std", any(windows, unix)))]
-static ADDITIONAL_LEAP_SECONDS: once_cell::sync::OnceCell<Box<[(i64, i64)]>> =
-    once_cell::sync::OnceCell::new();
+    #[cfg(all(feature = "lookup-cache", feature = "test-util"))]
+    #[test]
+    fn cached_lookups_with_another_table() {
+        // A lookup in one table must not be answered from the span cached for
+        // another one.
+        let next = datetime!(2030-01-01 0:00 UTC);
+        let table = builtin_table_with(&[(next, 38)], datetime!(2030-06-28 0:00 UTC));
 
-impl From<OffsetDateTime> for TaiDateTime {
-    fn from(time: OffsetDateTime) -> Self {
-        let unix_time_stamp = time - OffsetDateTime::UNIX_EPOCH;
+        // Other tests may have added entries to the global table.
+        let _lock = lock_global_table();
+        let global = {
+            let table = leap_table();
+            diff_before(&table, seek_utc(&table, table.len(), next.unix_timestamp()))
+        };
 
-        #[cfg(all(feature = "std", any(windows, unix)))]
-        if unix_time_stamp.whole_seconds() >= EXPIRES_AT_UTC {
-            let leap_seconds = ADDITIONAL_LEAP_SECONDS
-                .get_or_init(|| read_additional_leap_seconds().unwrap_or_default());
+        assert_eq!(leap_seconds_at_utc(next.unix_timestamp()), global);
+        let clock = MockTaiClock::new(TaiDateTime::from(next)).with_table(table);
+        clock.install();
+        assert_eq!(leap_seconds_at_utc(next.unix_timestamp()), 38);
+        assert_eq!(leap_seconds_at_utc(next.unix_timestamp() - 1), 37);
+        drop(clock);
+        assert_eq!(leap_seconds_at_utc(next.unix_timestamp()), global);
+    }
 
-            if let Some((_, diff)) = leap_seconds
-                .iter()
-                .cloned()
-                .rev()
-                .find(|&(t, _)| t <= unix_time_stamp.whole_seconds())
-            {
-                return Self(unix_time_stamp + Duration::new(diff, 0));
+    // Checks that the UNIX time stamp conversions agree with the ones of
+    // `OffsetDateTime` and, before the built-in table expires, with their
+    // constant counterparts.
+    fn check_unix_timestamp(nanos: i128) {
+        let utc = OffsetDateTime::from_unix_timestamp_nanos(nanos).unwrap();
+        let secs = utc.unix_timestamp();
+        let tai = TaiDateTime::from(utc);
+        assert_eq!(TaiDateTime::from_unix_utc_timestamp_nanos(nanos), Ok(tai));
+        assert_eq!(tai.to_unix_utc_timestamp_nanos(), nanos);
+        let whole = TaiDateTime::from(utc.replace_nanosecond(0).unwrap());
+        assert_eq!(TaiDateTime::from_unix_utc_timestamp(secs), whole);
+        assert_eq!(tai.to_unix_utc_timestamp(), secs);
+        if secs < EXPIRES_AT_UTC {
+            assert_eq!(TaiDateTime::from_unix_utc_timestamp_builtin(secs), whole);
+            assert_eq!(tai.to_unix_utc_timestamp_builtin(), secs);
+        }
+    }
+
+    #[test]
+    fn unix_timestamps_at_leap_seconds() {
+        #[cfg(feature = "std")]
+        let _lock = lock_global_table();
+        for &(t, _) in LEAP_SECONDS {
+            for secs in [t - 1, t, t + 1] {
+                for subsec in [0, 1, 500_000_000, 999_999_999] {
+                    check_unix_timestamp(secs as i128 * 1_000_000_000 + subsec);
+                }
             }
         }
+        for secs in [UTC_ERA_START - 1, UTC_ERA_START, EXPIRES_AT_UTC - 1, 0, -1] {
+            check_unix_timestamp(secs as i128 * 1_000_000_000);
+        }
 
-        let (_, diff) = LEAP_SECONDS
-            .iter()
-            .cloned()
-            .rev()
-            .find(|&(t, _)| t <= unix_time_stamp.whole_seconds())
-            .unwrap_or((0, FIRST_LEAP_SECONDS_DIFF));
+        // Instants inside of an inserted leap second have the time stamp of
+        // the midnight right after it.
+        let (t, diff) = LEAP_SECONDS[LEAP_SECONDS.len() - 1];
+        let leap_second = TaiDateTime(Duration::new(t + diff - 1, 250_000_000));
+        assert_eq!(leap_second.to_unix_utc_timestamp(), t);
+        assert_eq!(leap_second.to_unix_utc_timestamp_builtin(), t);
+        assert_eq!(
+            leap_second.to_unix_utc_timestamp_nanos(),
+            t as i128 * 1_000_000_000 + 250_000_000,
+        );
+    }
 
-        Self(unix_time_stamp + Duration::new(diff, 0))
+    #[test]
+    fn unix_timestamps_sampled() {
+        #[cfg(feature = "std")]
+        let _lock = lock_global_table();
+        let mut rng = fastrand::Rng::with_seed(0x7a1);
+        let range = (UTC_ERA_START - 20 * 365 * 86_400) as i128 * 1_000_000_000
+            ..(EXPIRES_AT_UTC + 20 * 365 * 86_400) as i128 * 1_000_000_000;
+        for _ in 0..10_000 {
+            check_unix_timestamp(rng.i128(range.clone()));
+        }
     }
-}
 
-impl From<TaiDateTime> for OffsetDateTime {
-    fn from(time: TaiDateTime) -> Self {
-        #[cfg(all(feature = "std", any(windows, unix)))]
-        if time.0.whole_seconds() >= EXPIRES_AT_TAI {
-            let leap_seconds = ADDITIONAL_LEAP_SECONDS
-                .get_or_init(|| read_additional_leap_seconds().unwrap_or_default());
+    #[test]
+    fn unix_timestamps_in_constants() {
+        const LAST_LEAP: TaiDateTime = TaiDateTime::from_unix_utc_timestamp_builtin(1_483_228_800);
+        const BEFORE: i64 = TaiDateTime::from_unix_utc_timestamp_builtin(1_483_228_799)
+            .to_unix_utc_timestamp_builtin();
+        assert_eq!(LAST_LEAP, TaiDateTime(Duration::seconds(1_483_228_837)));
+        assert_eq!(BEFORE, 1_483_228_799);
 
-            if let Some((_, diff)) = leap_seconds
-                .iter()
-                .cloned()
-                .rev()
-                .find(|&(t, diff)| t + diff <= time.0.whole_seconds())
-            {
-                return OffsetDateTime::UNIX_EPOCH + (time.0 - Duration::new(diff, 0));
+        // They saturate rather than overflowing.
+        assert_eq!(
+            TaiDateTime::from_unix_utc_timestamp_builtin(i64::MAX),
+            TaiDateTime(Duration::seconds(i64::MAX)),
+        );
+        assert_eq!(TaiDateTime::MIN.to_unix_utc_timestamp_builtin(), i64::MIN);
+        assert_eq!(TaiDateTime::MIN.to_unix_utc_timestamp(), i64::MIN);
+    }
+
+    #[test]
+    fn timespecs() {
+        for (time, timespec) in [
+            (Duration::ZERO, (0, 0)),
+            (Duration::new(1, 250_000_000), (1, 250_000_000)),
+            (Duration::new(-1, -250_000_000), (-2, 750_000_000)),
+            (Duration::NANOSECOND * -1, (-1, 999_999_999)),
+            (Duration::seconds(-1), (-1, 0)),
+            (
+                Duration::new(-1_000_000_000, -1),
+                (-1_000_000_001, 999_999_999),
+            ),
+            (TaiDateTime::MIN.0, (i64::MIN, 0)),
+            (TaiDateTime::MIN.0 + Duration::NANOSECOND, (i64::MIN, 1)),
+            (TaiDateTime::MAX.0, (i64::MAX, 999_999_999)),
+        ] {
+            let time = TaiDateTime(time);
+            assert_eq!(time.as_timespec(), timespec);
+            assert_eq!(TaiDateTime::from_timespec(timespec.0, timespec.1), time);
+        }
+
+        // Nanoseconds outside of a second carry.
+        assert_eq!(
+            TaiDateTime::from_timespec(-1, -250_000_000).as_timespec(),
+            (-2, 750_000_000),
+        );
+        assert_eq!(
+            TaiDateTime::from_timespec(1, 1_250_000_000).as_timespec(),
+            (2, 250_000_000),
+        );
+    }
+
+    #[test]
+    #[should_panic = "overflow constructing instant"]
+    fn timespec_before_min() {
+        TaiDateTime::from_timespec(i64::MIN, -1);
+    }
+
+    #[test]
+    fn min_is_a_whole_second() {
+        assert_eq!(TaiDateTime::MIN.0.subsec_nanoseconds(), 0);
+        let min_nanos = TaiDateTime::MIN.unix_tai_nanos();
+        assert_eq!(
+            TaiDateTime::from_unix_tai_nanos(min_nanos),
+            Ok(TaiDateTime::MIN)
+        );
+        assert_eq!(
+            TaiDateTime::from_unix_tai_nanos(min_nanos - 1),
+            Err(OutOfRange)
+        );
+        assert_eq!(
+            TaiDateTime::MIN.checked_sub_std(StdDuration::from_nanos(1)),
+            None,
+        );
+        assert_eq!(TaiDateTime::saturating(Duration::MIN), TaiDateTime::MIN);
+        assert_eq!(TaiDateTime::checked(Duration::MIN), None);
+    }
+
+    #[test]
+    #[should_panic = "overflow when subtracting duration from instant"]
+    fn subtracting_before_min() {
+        let _ = TaiDateTime::MIN - Duration::NANOSECOND;
+    }
+
+    #[test]
+    fn before_1970() {
+        let mut rng = fastrand::Rng::with_seed(0x1900);
+        let start = datetime!(1900-01-01 0:00 UTC).unix_timestamp();
+        for _ in 0..10_000 {
+            let unix_time_stamp = Duration::new(rng.i64(start..0), rng.i32(0..1_000_000_000));
+            let utc = OffsetDateTime::UNIX_EPOCH + unix_time_stamp;
+            let tai = TaiDateTime::from(utc);
+            assert_eq!(tai.0, unix_time_stamp + Duration::seconds(10), "{utc}");
+            assert_eq!(OffsetDateTime::from(tai), utc);
+            assert_eq!(tai.to_utc_saturating(), utc);
+            assert!(tai.is_before_utc_era());
+        }
+        for utc in [
+            datetime!(1900-01-01 0:00 UTC),
+            datetime!(1958-01-01 0:00 UTC),
+            datetime!(1969-12-31 23:59:59.999999999 UTC),
+        ] {
+            // No seconds are gained or lost across 1972.
+            let era_start = datetime!(1972-01-01 0:00 UTC);
+            let tai = TaiDateTime::from(utc);
+            assert_eq!(tai - TaiDateTime::from(era_start), utc - era_start);
+            assert_eq!(OffsetDateTime::from(tai), utc);
+        }
+    }
+
+    #[test]
+    fn unix_tai_nanos() {
+        let (min, max) = (
+            TaiDateTime::MIN.unix_tai_nanos(),
+            TaiDateTime::MAX.unix_tai_nanos(),
+        );
+        assert_eq!(min, i64::MIN as i128 * 1_000_000_000);
+        assert_eq!(max, i64::MAX as i128 * 1_000_000_000 + 999_999_999);
+        for nanos in [min, max, 0, 1, -1, -700_000_000, 1_483_228_837_500_000_000] {
+            let time = TaiDateTime::from_unix_tai_nanos(nanos).unwrap();
+            assert_eq!(time.unix_tai_nanos(), nanos);
+        }
+        for nanos in [min - 1, max + 1, i128::MIN, i128::MAX] {
+            assert_eq!(TaiDateTime::from_unix_tai_nanos(nanos), Err(OutOfRange));
+        }
+    }
+
+    #[test]
+    fn std_durations() {
+        let time = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        let duration = StdDuration::new(1, 500_000_000);
+        assert_eq!(time + duration, time + Duration::milliseconds(1500));
+        assert_eq!(time - duration, time - Duration::milliseconds(1500));
+        assert_eq!(time.checked_add_std(duration), Some(time + duration));
+        assert_eq!(time.checked_sub_std(duration), Some(time - duration));
+
+        let nanosecond = StdDuration::from_nanos(1);
+        assert_eq!(TaiDateTime::MAX.checked_add_std(nanosecond), None);
+        assert_eq!(TaiDateTime::MIN.checked_sub_std(nanosecond), None);
+        // The whole range is exactly the maximum duration.
+        assert_eq!(
+            TaiDateTime::MIN.checked_add_std(StdDuration::MAX),
+            Some(TaiDateTime::MAX),
+        );
+        assert_eq!(
+            TaiDateTime(Duration::ZERO).checked_add_std(StdDuration::MAX),
+            None
+        );
+        assert_eq!(
+            (TaiDateTime::MAX - nanosecond).checked_add_std(nanosecond),
+            Some(TaiDateTime::MAX),
+        );
+
+        assert_eq!((time + duration).duration_since(time), Ok(duration));
+        assert_eq!(time.duration_since(time), Ok(StdDuration::ZERO));
+        assert_eq!(
+            time.duration_since(time + duration),
+            Err(Negative(duration)),
+        );
+        assert_eq!(
+            TaiDateTime::MAX.duration_since(TaiDateTime::MIN),
+            Ok(StdDuration::MAX),
+        );
+        assert_eq!(
+            TaiDateTime::MIN.duration_since(TaiDateTime::MAX),
+            Err(Negative(StdDuration::MAX)),
+        );
+    }
+
+    #[test]
+    #[should_panic = "overflow when adding duration to instant"]
+    fn adding_std_beyond_max() {
+        let _ = TaiDateTime::MAX + StdDuration::from_nanos(1);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn utc_offset_seconds() {
+        for (utc, offset) in [
+            (datetime!(1960-01-01 0:00 UTC), 10),
+            (datetime!(1971-12-31 23:59:59 UTC), 10),
+            (datetime!(1972-01-01 0:00 UTC), 10),
+            (datetime!(1980-01-06 0:00 UTC), 19),
+            (datetime!(2016-12-31 23:59:59 UTC), 36),
+            (datetime!(2017-01-01 0:00 UTC), 37),
+            (datetime!(2017-06-01 0:00 UTC), 37),
+        ] {
+            let time = TaiDateTime::from(utc);
+            assert_eq!(leap_seconds::utc_offset_seconds_at(time), offset, "{utc}");
+        }
+        // Inside of the leap second it's still the difference from before.
+        let midnight = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        for millis in [1, 500, 1000] {
+            let time = midnight - Duration::milliseconds(millis);
+            assert_eq!(leap_seconds::utc_offset_seconds_at(time), 36);
+        }
+    }
+
+    #[test]
+    fn normalized_decompositions() {
+        let expected = TaiDateTime(Duration::milliseconds(-700));
+        let times = [
+            TaiDateTime::from_timespec(-1, 300_000_000),
+            TaiDateTime::from_timespec(0, -700_000_000),
+            TaiDateTime::from_timespec(-2, 1_300_000_000),
+            TaiDateTime(Duration::new(1, -1_700_000_000)),
+            TaiDateTime(Duration::ZERO) - Duration::milliseconds(700),
+            TaiDateTime(Duration::seconds(-1)) + Duration::milliseconds(300),
+            TaiDateTime(Duration::seconds(5)) - Duration::milliseconds(5700),
+            TaiDateTime::from_unix_tai_nanos(-700_000_000).unwrap(),
+            OffsetDateTime::from(expected).into(),
+        ];
+        for time in times {
+            assert!(time.is_normalized(), "{time:?}");
+            assert_eq!(time, expected);
+            assert_eq!(time.0.whole_seconds(), 0);
+            assert_eq!(time.0.subsec_nanoseconds(), -700_000_000);
+            assert_eq!(time.as_timespec(), (-1, 300_000_000));
+        }
+    }
+
+    #[test]
+    fn canonical_tai() {
+        let midnight = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        for half_seconds in -6..6 {
+            let time = midnight + Duration::milliseconds(500 * half_seconds);
+            let canonical = canonical_tai_for_utc(OffsetDateTime::from(time));
+            // Inside of the leap second it's the instant a second later.
+            if (-2..0).contains(&half_seconds) {
+                assert_eq!(canonical, time + Duration::SECOND, "{time:?}");
+            } else {
+                assert_eq!(canonical, time, "{time:?}");
             }
+            assert_eq!(
+                canonical_tai_for_utc(OffsetDateTime::from(canonical)),
+                canonical,
+            );
         }
+    }
 
-        let (_, diff) = LEAP_SECONDS
-            .iter()
-            .cloned()
-            .rev()
-            .find(|&(t, diff)| t + diff <= time.0.whole_seconds())
-            .unwrap_or((0, FIRST_LEAP_SECONDS_DIFF));
+    #[test]
+    fn try_now() {
+        // Only with the standard library is there a clock to read.
+        #[cfg(feature = "std")]
+        {
+            let now = TaiDateTime::try_now().unwrap();
+            assert!(now.approx_eq(TaiDateTime::now(), Duration::SECOND));
+            assert!(now > TaiDateTime::from(datetime!(2024-01-01 0:00 UTC)));
+        }
+        #[cfg(not(feature = "std"))]
+        assert_eq!(TaiDateTime::try_now(), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", target_os = "linux"))]
+    fn kernel_tai_clock() {
+        // Whether the kernel's TAI offset is set up depends on the machine.
+        match TaiDateTime::try_now_kernel() {
+            Ok(kernel) => assert!(kernel.approx_eq(TaiDateTime::now(), Duration::SECOND)),
+            Err(error) => assert_eq!(error, ClockError::Unconfigured),
+        }
+    }
+
+    #[test]
+    fn caller_supplied_offset() {
+        let mut rng = fastrand::Rng::with_seed(344);
+        for _ in 0..1_000 {
+            let utc = OffsetDateTime::UNIX_EPOCH
+                + Duration::new(
+                    rng.i64(-3_000_000_000..3_000_000_000),
+                    rng.i32(0..1_000_000_000),
+                );
+            let offset = rng.i64(-100..100);
+            let tai = TaiDateTime::from_utc_with_offset(utc, offset);
+            assert_eq!(
+                tai.0,
+                utc - OffsetDateTime::UNIX_EPOCH + Duration::seconds(offset)
+            );
+            assert_eq!(tai.to_utc_with_offset(offset), utc);
+        }
+
+        // The table isn't consulted, not even across a leap second.
+        let before = datetime!(2016-12-31 23:59:59 UTC);
+        let after = datetime!(2017-01-01 0:00 UTC);
+        assert_eq!(
+            TaiDateTime::from_utc_with_offset(after, 36)
+                - TaiDateTime::from_utc_with_offset(before, 36),
+            Duration::SECOND,
+        );
+        // With the right offset, it's the same as the table.
+        assert_eq!(
+            TaiDateTime::from_utc_with_offset(after, 37),
+            TaiDateTime::from(after),
+        );
+        assert_eq!(TaiDateTime::from(before).to_utc_with_offset(36), before,);
+    }
+
+    #[test]
+    fn utc_midnights() {
+        // After the leap second at the end of the previous day.
+        assert_eq!(
+            tai_at_utc_midnight(date!(2017 - 01 - 01)),
+            TaiDateTime(Duration::seconds(1_483_228_800 + 37)),
+        );
+        assert_eq!(
+            tai_at_utc_midnight(date!(2016 - 12 - 31)),
+            TaiDateTime(Duration::seconds(1_483_142_400 + 36)),
+        );
+        assert_eq!(
+            tai_at_utc_midnight(date!(2017 - 01 - 01)) - tai_at_utc_midnight(date!(2016 - 12 - 31)),
+            Duration::seconds(86_401),
+        );
+        assert_eq!(
+            tai_at_utc_midnight(date!(1970 - 01 - 01)),
+            TaiDateTime(Duration::seconds(10)),
+        );
+        assert_eq!(
+            tai_at_utc_midnight(date!(1972 - 07 - 01)),
+            TaiDateTime::from(datetime!(1972-07-01 0:00 UTC)),
+        );
+    }
+
+    #[test]
+    fn to_offset() {
+        let offset = UtcOffset::from_hms(9, 0, 0).unwrap();
+        let midnight = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        assert_eq!(midnight.to_offset(offset), datetime!(2017-01-01 9:00 +9),);
+        assert_eq!(
+            (midnight - Duration::milliseconds(1500)).to_offset(offset),
+            datetime!(2017-01-01 8:59:59.5 +9),
+        );
+        // Inside of the leap second it's the second after it.
+        assert_eq!(
+            (midnight - Duration::milliseconds(500)).to_offset(offset),
+            datetime!(2017-01-01 9:00:00.5 +9),
+        );
+        let negative = UtcOffset::from_hms(-5, -30, 0).unwrap();
+        assert_eq!(
+            midnight.to_offset(negative),
+            datetime!(2016-12-31 18:30 -5:30),
+        );
+    }
+
+    #[test]
+    fn mjd_unix_epoch() {
+        // The MJD is the Julian Day Number minus 2400001, counted from
+        // 1858-11-17.
+        let epoch = datetime!(1970-01-01 0:00 UTC);
+        assert_eq!(epoch.to_julian_day() as i64 - 2_400_001, MJD_UNIX_EPOCH);
+        let mjd_epoch = TaiDateTime(Duration::days(-MJD_UNIX_EPOCH));
+        assert_eq!(mjd_epoch.to_primitive_tai(), datetime!(1858-11-17 0:00));
+        assert_eq!(mjd_epoch.to_mjd_parts(), (0, 0));
+
+        let tai_epoch = TaiDateTime(Duration::ZERO);
+        assert_eq!(tai_epoch.to_mjd_parts(), (40587, 0));
+        assert_eq!(tai_epoch.to_mjd_tai(), 40587.0);
+        assert_eq!(TaiDateTime::from_mjd_tai(40587.0), Ok(tai_epoch));
+
+        // TAI was 10 seconds ahead of UTC in 1970 and 32 seconds in 2000.
+        assert_eq!(
+            TaiDateTime::from(epoch).to_mjd_parts(),
+            (40587, 10_000_000_000)
+        );
+        assert_eq!(
+            TaiDateTime::from(datetime!(2000-01-01 0:00 UTC)).to_mjd_parts(),
+            (51544, 32_000_000_000),
+        );
+        assert_eq!(
+            TaiDateTime(-Duration::NANOSECOND).to_mjd_parts(),
+            (40586, NANOS_PER_DAY as u64 - 1),
+        );
+    }
+
+    #[test]
+    fn mjd_day_fraction() {
+        let tai_epoch = TaiDateTime(Duration::ZERO);
+        assert_eq!(tai_epoch.mjd_parts(), (40587, 0.0));
+        assert_eq!((tai_epoch + Duration::hours(18)).mjd_parts(), (40587, 0.75),);
+        // Before the epochs the fraction still counts up from midnight.
+        assert_eq!((tai_epoch - Duration::hours(6)).mjd_parts(), (40586, 0.75),);
+        let mjd_epoch = TaiDateTime(Duration::days(-MJD_UNIX_EPOCH));
+        assert_eq!((mjd_epoch - Duration::hours(12)).mjd_parts(), (-1, 0.5));
+
+        // Far from the epoch, the fraction is still precise to well below a
+        // nanosecond.
+        let time = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC)) + Duration::nanoseconds(1);
+        let (day, fraction) = time.mjd_parts();
+        assert_eq!(day, 57754);
+        let nanos = fraction * NANOS_PER_DAY as f64;
+        assert!((nanos - 37_000_000_001.0).abs() < 0.01, "{nanos}");
+    }
+
+    #[test]
+    fn signed_nanos_since_beyond_i64() {
+        // About 292 years fit into an `i64` of nanoseconds, so these don't.
+        let start = TaiDateTime::from(datetime!(1700-01-01 0:00 UTC));
+        let end = TaiDateTime::from(datetime!(2300-01-01 0:00 UTC));
+        let nanos = (end - start).whole_nanoseconds();
+        assert!(nanos > i64::MAX as i128);
+        assert_eq!(end.signed_nanos_since(start), nanos);
+        assert_eq!(start.signed_nanos_since(end), -nanos);
+        assert_eq!(
+            end.signed_nanos_since(start) + 1,
+            (end + Duration::NANOSECOND).signed_nanos_since(start),
+        );
+
+        let span = (i64::MAX as i128 - i64::MIN as i128) * 1_000_000_000 + 999_999_999;
+        assert_eq!(TaiDateTime::MAX.signed_nanos_since(TaiDateTime::MIN), span,);
+        assert_eq!(TaiDateTime::MIN.signed_nanos_since(TaiDateTime::MAX), -span,);
+        assert_eq!(
+            TaiDateTime::MAX.signed_nanos_since(TaiDateTime::MIN),
+            TaiDateTime::MAX.unix_tai_nanos() - TaiDateTime::MIN.unix_tai_nanos(),
+        );
+    }
+
+    #[test]
+    fn saturating_duration_since() {
+        assert_eq!(
+            TaiDateTime::MAX.saturating_duration_since(TaiDateTime::MIN),
+            Duration::MAX,
+        );
+        assert_eq!(
+            TaiDateTime::MIN.saturating_duration_since(TaiDateTime::MAX),
+            Duration::MIN,
+        );
+        for time in [TaiDateTime::MIN, TaiDateTime::MAX] {
+            assert_eq!(time.saturating_duration_since(time), Duration::ZERO);
+        }
+
+        // Within range it's the same as subtracting.
+        let epoch = TaiDateTime(Duration::ZERO);
+        assert_eq!(
+            TaiDateTime::MAX.saturating_duration_since(epoch),
+            Duration::MAX,
+        );
+        assert_eq!(
+            TaiDateTime::MIN.saturating_duration_since(epoch),
+            Duration::seconds(i64::MIN),
+        );
+        let before = TaiDateTime::from(datetime!(2016-12-31 23:59:59 UTC));
+        let after = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        assert_eq!(after.saturating_duration_since(before), after - before);
+        assert_eq!(before.saturating_duration_since(after), before - after);
+    }
+
+    #[test]
+    fn try_utc_within_table() {
+        for utc in [
+            datetime!(1972-01-01 0:00 UTC),
+            datetime!(1999-06-15 12:34:56.789 UTC),
+            datetime!(2016-12-31 23:59:59.999999999 UTC),
+            datetime!(2017-01-01 0:00 UTC),
+        ] {
+            let tai = TaiDateTime::try_from_utc(utc).unwrap();
+            assert_eq!(tai, TaiDateTime::from(utc));
+            assert_eq!(tai.try_to_utc(), Ok(utc));
+        }
+    }
+
+    #[test]
+    fn try_utc_before_tai_epoch() {
+        for utc in [
+            datetime!(1971-12-31 23:59:59.999999999 UTC),
+            datetime!(1970-01-01 0:00 UTC),
+            datetime!(1900-01-01 0:00 UTC),
+        ] {
+            assert_eq!(
+                TaiDateTime::try_from_utc(utc),
+                Err(ConversionError::BeforeTaiEpoch),
+            );
+            assert_eq!(
+                TaiDateTime::from(utc).try_to_utc(),
+                Err(ConversionError::BeforeTaiEpoch),
+            );
+        }
+    }
+
+    #[test]
+    fn try_utc_beyond_table_expiry() {
+        // Other tests may extend the table.
+        #[cfg(feature = "std")]
+        let _lock = lock_global_table();
+        let expiry = leap_seconds::expires_at();
+        let error = ConversionError::BeyondTableExpiry { expiry };
+
+        for utc in [expiry, expiry + Duration::days(365)] {
+            assert_eq!(TaiDateTime::try_from_utc(utc), Err(error));
+            assert_eq!(TaiDateTime::from(utc).try_to_utc(), Err(error));
+        }
+        let before = expiry - Duration::NANOSECOND;
+        assert_eq!(
+            TaiDateTime::try_from_utc(before).and_then(TaiDateTime::try_to_utc),
+            Ok(before),
+        );
+    }
+
+    #[test]
+    fn try_utc_target_out_of_range() {
+        for tai in [TaiDateTime::MIN, TaiDateTime::MAX] {
+            assert_eq!(tai.try_to_utc(), Err(ConversionError::TargetOutOfRange));
+        }
+    }
+
+    #[test]
+    fn try_utc_inside_leap_second() {
+        let midnight = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        for nanos in [1_000_000_000, 500_000_000, 1] {
+            assert_eq!(
+                (midnight - Duration::nanoseconds(nanos)).try_to_utc(),
+                Err(ConversionError::InsideLeapSecond),
+            );
+        }
+        assert_eq!(
+            (midnight - Duration::nanoseconds(1_000_000_001)).try_to_utc(),
+            Ok(datetime!(2016-12-31 23:59:59.999999999 UTC)),
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "large-dates"))]
+    fn saturating_utc_conversions() {
+        let (min, max) = (*SUPPORTED_UTC_RANGE.start(), *SUPPORTED_UTC_RANGE.end());
+        assert_eq!(min, datetime!(-9999-01-01 0:00 UTC));
+        assert_eq!(max, datetime!(9999-12-31 23:59:59.999999999 UTC));
+
+        for utc in [min, max] {
+            let tai = TaiDateTime::from_utc_saturating(utc);
+            assert_eq!(tai, TaiDateTime::from(utc));
+            assert_eq!(tai.to_utc_saturating(), utc);
+            assert_eq!(OffsetDateTime::from(tai), utc);
+        }
+        // TAI is 10 seconds ahead before 1972. After the table the difference
+        // depends on the leap seconds other tests add to it.
+        assert_eq!(
+            TaiDateTime::from(min) - TaiDateTime::from_utc_with_offset(min, 0),
+            Duration::seconds(10),
+        );
+
+        // Just beyond the range and at the very ends of TAI it clamps.
+        for (tai, utc) in [
+            (TaiDateTime::from(min) - Duration::NANOSECOND, min),
+            (TaiDateTime::from(max) + Duration::NANOSECOND, max),
+            (TaiDateTime::MIN, min),
+            (TaiDateTime::MAX, max),
+            (TaiDateTime(Duration::seconds(i64::MIN / 2)), min),
+            (TaiDateTime(Duration::seconds(i64::MAX / 2)), max),
+        ] {
+            assert_eq!(tai.to_utc_saturating(), utc, "{tai:?}");
+        }
+    }
+
+    #[test]
+    fn half_a_second_before_the_epoch() {
+        let half = Duration::milliseconds(500);
+        assert_eq!(floor_seconds(-half), -1);
+        assert_eq!(floor_seconds(half), 0);
+        assert_eq!(floor_seconds(-Duration::NANOSECOND), -1);
+        assert_eq!(floor_seconds(Duration::seconds(-1)), -1);
+
+        let tai = TaiDateTime(-half);
+        assert_eq!(tai.as_timespec(), (-1, 500_000_000));
+        assert_eq!(
+            OffsetDateTime::from(tai),
+            datetime!(1969-12-31 23:59:49.5 UTC),
+        );
+        assert_eq!(
+            TaiDateTime::from(datetime!(1969-12-31 23:59:59.5 UTC)),
+            TaiDateTime(Duration::seconds(10) - half),
+        );
+        assert_eq!(TaiDateTime::from(datetime!(1969-12-31 23:59:49.5 UTC)), tai,);
+    }
 
-        OffsetDateTime::UNIX_EPOCH + (time.0 - Duration::new(diff, 0))
+    #[test]
+    fn utc_era() {
+        let start = TaiDateTime::from(datetime!(1972-01-01 0:00 UTC));
+        assert_eq!(start.0, Duration::seconds(UTC_ERA_START + 10));
+        assert!(!start.is_before_utc_era());
+        assert!((start - Duration::NANOSECOND).is_before_utc_era());
+        assert!(TaiDateTime(-Duration::milliseconds(500)).is_before_utc_era());
+        assert!(TaiDateTime::MIN.is_before_utc_era());
+        assert!(!TaiDateTime::MAX.is_before_utc_era());
     }
 }
@@ -0,0 +1,314 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use time::Duration;
+
+use crate::{leap_seconds::LeapTable, ClockError, TaiDateTime};
+
+// A leap second table installed by a mock clock, as its entries and the UTC
+// time stamp it expires at.
+type MockTable = (&'static [(i64, i64)], i64);
+
+#[derive(Clone)]
+struct Installed {
+    now: Rc<Cell<TaiDateTime>>,
+    table: Option<MockTable>,
+    kernel_error: Option<ClockError>,
+}
+
+thread_local! {
+    static INSTALLED: RefCell<Option<Installed>> = const { RefCell::new(None) };
+}
+
+/// A clock for tests that only moves when told to, e.g. to step through the
+/// leap second 2016-12-31 23:59:60 UTC deterministically. Available with the
+/// `test-util` feature.
+///
+/// Once [installed](Self::install), [`TaiDateTime::now`] and everything built
+/// on top of it, such as [`TaiDateTime::relative_to_now`], read the clock
+/// instead of the system's clock. A clock is only installed for the thread
+/// that installed it, so tests running in parallel don't interfere with each
+/// other, but threads started by the crate, such as the one of
+/// [`watch`](crate::leap_seconds::watch), keep using the system's clock. It
+/// gets uninstalled when it's dropped.
+///
+/// A clock can also come with its own leap second table, see
+/// [`with_table`](Self::with_table), and simulate a broken kernel TAI clock,
+/// see [`with_kernel_error`](Self::with_kernel_error).
+pub struct MockTaiClock {
+    installed: Installed,
+    is_installed: Cell<bool>,
+}
+
+impl MockTaiClock {
+    /// Creates a clock that starts at the given instant.
+    pub fn new(start: TaiDateTime) -> Self {
+        Self {
+            installed: Installed {
+                now: Rc::new(Cell::new(start)),
+                table: None,
+                kernel_error: None,
+            },
+            is_installed: Cell::new(false),
+        }
+    }
+
+    /// Sets the leap second table that all conversions on the installing thread
+    /// use while the clock is installed, instead of the one that is used
+    /// globally. This allows testing with synthetic leap seconds, such as
+    /// future or negative ones. The entries are leaked, which is fine for
+    /// tests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock is already installed.
+    #[track_caller]
+    pub fn with_table(mut self, table: LeapTable) -> Self {
+        assert!(
+            !self.is_installed.get(),
+            "the leap second table of an installed mock clock can't be changed"
+        );
+        let entries = Box::leak(table.entries().to_vec().into_boxed_slice());
+        self.installed.table = Some((entries, table.expires_at().unix_timestamp()));
+        self
+    }
+
+    /// Makes [`TaiDateTime::try_now_kernel`] fail with the given error while
+    /// the clock is installed, as if the kernel's TAI clock was unavailable or
+    /// not set up. Otherwise it returns the instant the clock is at, just like
+    /// [`TaiDateTime::now`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock is already installed.
+    #[track_caller]
+    pub fn with_kernel_error(mut self, error: ClockError) -> Self {
+        assert!(
+            !self.is_installed.get(),
+            "the kernel clock of an installed mock clock can't be changed"
+        );
+        self.installed.kernel_error = Some(error);
+        self
+    }
+
+    /// The instant the clock is at.
+    pub fn now(&self) -> TaiDateTime {
+        self.installed.now.get()
+    }
+
+    /// Moves the clock by the given duration, which may be negative.
+    pub fn advance(&self, duration: Duration) {
+        self.set(self.now() + duration);
+    }
+
+    /// Moves the clock to the given instant.
+    pub fn set(&self, time: TaiDateTime) {
+        self.installed.now.set(time);
+    }
+
+    /// Makes [`TaiDateTime::now`] read this clock on the current thread, until
+    /// the clock is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a mock clock, including this one, is already installed on the
+    /// current thread.
+    #[track_caller]
+    pub fn install(&self) {
+        let newly_installed = INSTALLED.with(|installed| {
+            let mut installed = installed.borrow_mut();
+            installed.is_none() && installed.replace(self.installed.clone()).is_none()
+        });
+        // Asserted out here, so the panic points at the caller.
+        assert!(
+            newly_installed,
+            "a mock clock is already installed on this thread"
+        );
+        self.is_installed.set(true);
+    }
+}
+
+impl Drop for MockTaiClock {
+    fn drop(&mut self) {
+        if self.is_installed.get() {
+            // The thread local may already be gone if the clock is dropped
+            // while the thread is shutting down, but then there's nothing to
+            // uninstall anyway.
+            let _ = INSTALLED.try_with(|installed| installed.borrow_mut().take());
+        }
+    }
+}
+
+// The instant of the mock clock installed on the current thread, if any.
+pub(crate) fn now() -> Option<TaiDateTime> {
+    INSTALLED.with(|installed| Some(installed.borrow().as_ref()?.now.get()))
+}
+
+// What reading the kernel's TAI clock results in with the mock clock installed
+// on the current thread, if any.
+pub(crate) fn now_kernel() -> Option<Result<TaiDateTime, ClockError>> {
+    INSTALLED.with(|installed| {
+        let installed = installed.borrow();
+        let installed = installed.as_ref()?;
+        Some(match installed.kernel_error {
+            Some(error) => Err(error),
+            None => Ok(installed.now.get()),
+        })
+    })
+}
+
+// The leap second table of the mock clock installed on the current thread, if
+// it has one.
+pub(crate) fn leap_table() -> Option<MockTable> {
+    INSTALLED
+        .try_with(|installed| installed.borrow().as_ref()?.table)
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, OffsetDateTime};
+
+    use super::*;
+    use crate::tests::builtin_table_with;
+
+    fn start() -> TaiDateTime {
+        TaiDateTime::from(datetime!(2016-12-31 23:59:59 UTC))
+    }
+
+    #[test]
+    fn moves_when_told_to() {
+        let clock = MockTaiClock::new(start());
+        assert_eq!(clock.now(), start());
+        clock.install();
+        assert_eq!(TaiDateTime::now(), start());
+        assert_eq!(TaiDateTime::now(), start());
+
+        // Stepping through the leap second.
+        clock.advance(Duration::SECOND);
+        assert_eq!(
+            OffsetDateTime::from(TaiDateTime::now()),
+            datetime!(2017-01-01 0:00 UTC),
+        );
+        clock.advance(Duration::SECOND);
+        assert_eq!(
+            OffsetDateTime::from(TaiDateTime::now()),
+            datetime!(2017-01-01 0:00 UTC),
+        );
+        clock.advance(-Duration::seconds(2));
+        assert_eq!(TaiDateTime::now(), start());
+        clock.set(TaiDateTime::MIN);
+        assert_eq!(TaiDateTime::now(), TaiDateTime::MIN);
+        assert_eq!(TaiDateTime::try_now_kernel(), Ok(TaiDateTime::MIN));
+    }
+
+    #[test]
+    fn kernel_errors() {
+        for error in [ClockError::Unavailable, ClockError::Unconfigured] {
+            let clock = MockTaiClock::new(start()).with_kernel_error(error);
+            clock.install();
+            assert_eq!(TaiDateTime::try_now_kernel(), Err(error));
+            // The other clocks still work.
+            assert_eq!(TaiDateTime::now(), start());
+        }
+    }
+
+    #[test]
+    #[should_panic = "the kernel clock of an installed mock clock can't be changed"]
+    fn kernel_error_once_installed() {
+        let clock = MockTaiClock::new(start());
+        clock.install();
+        let _ = clock.with_kernel_error(ClockError::Unavailable);
+    }
+
+    #[test]
+    fn uninstalled_when_dropped() {
+        let clock = MockTaiClock::new(start());
+        clock.install();
+        drop(clock);
+        assert!(TaiDateTime::now() > start() + Duration::days(365));
+
+        // It can be installed again afterwards.
+        let clock = MockTaiClock::new(start());
+        clock.install();
+        assert_eq!(TaiDateTime::now(), start());
+    }
+
+    #[test]
+    fn only_installed_on_its_thread() {
+        let clock = MockTaiClock::new(start());
+        clock.install();
+        let other = std::thread::spawn(TaiDateTime::now).join().unwrap();
+        assert!(other > start() + Duration::days(365));
+        assert_eq!(TaiDateTime::now(), start());
+    }
+
+    #[test]
+    #[should_panic = "a mock clock is already installed on this thread"]
+    fn installing_twice() {
+        let clock = MockTaiClock::new(start());
+        clock.install();
+        clock.install();
+    }
+
+    #[test]
+    #[should_panic = "a mock clock is already installed on this thread"]
+    fn installing_another() {
+        let clock = MockTaiClock::new(start());
+        clock.install();
+        MockTaiClock::new(start()).install();
+    }
+
+    #[test]
+    fn with_table() {
+        // A negative leap second at the end of 2029.
+        let table = builtin_table_with(
+            &[(datetime!(2030-01-01 0:00 UTC), 36)],
+            datetime!(2030-06-28 0:00 UTC),
+        );
+
+        let utc = datetime!(2030-01-01 0:00 UTC);
+        let clock = MockTaiClock::new(start()).with_table(table);
+        clock.install();
+        assert_eq!(
+            TaiDateTime::from(utc) - TaiDateTime::from(utc - Duration::SECOND),
+            Duration::ZERO,
+        );
+        assert_eq!(
+            crate::leap_seconds::expires_at(),
+            datetime!(2030-06-28 0:00 UTC),
+        );
+        assert_eq!(
+            crate::leap_seconds::leap_source(),
+            crate::leap_seconds::LeapSource::Custom,
+        );
+        drop(clock);
+
+        // Without the clock, the global table applies again, which has no
+        // negative leap seconds.
+        assert!(
+            TaiDateTime::from(utc) - TaiDateTime::from(utc - Duration::SECOND) >= Duration::SECOND
+        );
+    }
+
+    #[test]
+    #[should_panic = "the leap second table of an installed mock clock can't be changed"]
+    fn with_table_when_installed() {
+        let clock = MockTaiClock::new(start());
+        clock.install();
+        let _ = clock.with_table(LeapTable::builtin());
+    }
+
+    #[test]
+    fn with_kernel_error() {
+        for error in [ClockError::Unavailable, ClockError::Unconfigured] {
+            let clock = MockTaiClock::new(start()).with_kernel_error(error);
+            clock.install();
+            assert_eq!(TaiDateTime::try_now_kernel(), Err(error));
+            assert_eq!(TaiDateTime::now(), start());
+        }
+    }
+}
@@ -0,0 +1,615 @@
+use time::{Date, Duration, Month, OffsetDateTime, Time};
+
+use crate::{
+    floor_seconds, leap_table, seek_tai, ConversionError, TaiDateTime, SUPPORTED_UTC_RANGE,
+};
+
+// The TAI instant of the given UTC date and time.
+fn from_utc(date: Date, time: Time) -> TaiDateTime {
+    TaiDateTime::from(date.with_time(time).assume_utc())
+}
+
+// Whether the given UTC date ends with an inserted leap second.
+fn ends_with_leap_second(date: Date) -> bool {
+    let Some(next_day) = date.next_day() else {
+        return false;
+    };
+    let last_second = from_utc(date, Time::from_hms(23, 59, 59).unwrap());
+    from_utc(next_day, Time::MIDNIGHT) - last_second == Duration::seconds(2)
+}
+
+/// Which of two instants is meant by a UTC date time that a clock shows twice
+/// in a row, like the "fold" of Python's PEP 495.
+///
+/// Clocks that can't show 23:59:60, e.g. the Windows clock with leap seconds
+/// enabled or a stepped `CLOCK_REALTIME` on Linux, repeat 23:59:59 during an
+/// inserted leap second instead. The second 23:59:59 is actually the leap
+/// second.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LeapFold {
+    /// The first occurrence, i.e. the actual 23:59:59 right before the leap
+    /// second. This is what the [`From`] conversion picks.
+    Earlier,
+    /// The second occurrence, i.e. the leap second 23:59:60.
+    Later,
+}
+
+impl TaiDateTime {
+    /// Converts a UTC date time to TAI, picking the instant selected by `fold`
+    /// if the date time is within the 23:59:59 right before an inserted leap
+    /// second, which a clock repeating it shows twice. The later one is inside
+    /// of the leap second, one second after the earlier one. Any other date
+    /// time isn't ambiguous, so `fold` is ignored and this is the same as the
+    /// [`From`] conversion.
+    pub fn from_utc_disambiguated(time: OffsetDateTime, fold: LeapFold) -> Self {
+        let earlier = Self::from(time);
+        match fold {
+            LeapFold::Later if earlier.utc_fold() == Some(LeapFold::Earlier) => {
+                earlier + Duration::SECOND
+            }
+            _ => earlier,
+        }
+    }
+
+    /// Returns whether this is the earlier or the later of two instants that a
+    /// clock repeating 23:59:59 during an inserted leap second shows the same,
+    /// or `None` if such a clock shows this instant only once. Both of them are
+    /// reported the same by [`to_utc_date_time`].
+    ///
+    /// [`to_utc_date_time`]: Self::to_utc_date_time
+    pub fn utc_fold(&self) -> Option<LeapFold> {
+        let (_, leap) = self.checked_utc_label().ok()?;
+        if leap {
+            return Some(LeapFold::Later);
+        }
+        let next_second = self.0.checked_add(Duration::SECOND).map(Self)?;
+        let (_, next_leap) = next_second.checked_utc_label().ok()?;
+        next_leap.then_some(LeapFold::Earlier)
+    }
+
+    // The UTC date time the instant is labeled with and whether it's inside of
+    // an inserted leap second, or an error if it's outside of the range of
+    // `OffsetDateTime`.
+    fn checked_utc_label(self) -> Result<(OffsetDateTime, bool), ConversionError> {
+        let start = Self::from(*SUPPORTED_UTC_RANGE.start());
+        let end = Self::from(*SUPPORTED_UTC_RANGE.end());
+        if self < start || self > end {
+            return Err(ConversionError::TargetOutOfRange);
+        }
+        Ok(self.to_utc_label(&mut 0))
+    }
+
+    // The instant at the same UTC time of day as the given label, but on
+    // another date. If the label is inside of a leap second, the result is
+    // too, unless the date doesn't end with one, in which case it's 23:59:59.
+    fn with_utc_date((utc, leap): (OffsetDateTime, bool), date: Date) -> Self {
+        let time = from_utc(date, utc.time());
+        if leap && ends_with_leap_second(date) {
+            time + Duration::SECOND
+        } else {
+            time
+        }
+    }
+
+    /// Returns the instant at which the UTC clock reads the given duration
+    /// later, or earlier if negative. Unlike adding the duration directly,
+    /// which adds SI seconds, this adds seconds of the UTC clock, so across an
+    /// inserted leap second the result is one SI second later, and across a
+    /// removed one, one SI second earlier. Adding [`Duration::DAY`] lands on
+    /// the same UTC wall clock time the next day.
+    ///
+    /// An instant inside of the leap second 23:59:60 counts as the same time
+    /// of day in the second after it, just like with the conversion to
+    /// [`OffsetDateTime`], so adding a zero duration moves it out of the leap
+    /// second. Use [`add_utc_days`](Self::add_utc_days) to keep it there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instant or the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn add_utc_duration(self, duration: Duration) -> Self {
+        Self::from(OffsetDateTime::from(self) + duration)
+    }
+
+    /// Returns the instant at the same UTC wall clock time the given number of
+    /// days later, or earlier if negative. Unlike adding [`Duration::days`],
+    /// this takes the leap seconds into account, so across an inserted leap
+    /// second the result is 86401 SI seconds later per day instead of 86400.
+    ///
+    /// An instant inside of the leap second 23:59:60 maps to 23:59:60 of the
+    /// target day if that day ends with a leap second as well, and otherwise
+    /// to 23:59:59 of the target day, keeping the fraction of the second.
+    ///
+    /// Fails if the instant or the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn add_utc_days(self, days: i64) -> Result<Self, ConversionError> {
+        let label = self.checked_utc_label()?;
+        let date = (label.0.to_julian_day() as i64)
+            .checked_add(days)
+            .and_then(|day| i32::try_from(day).ok())
+            .and_then(|day| Date::from_julian_day(day).ok())
+            .ok_or(ConversionError::TargetOutOfRange)?;
+        Ok(Self::with_utc_date(label, date))
+    }
+
+    /// Returns the instant at the same UTC wall clock time on the same day of
+    /// the month the given number of months later, or earlier if negative. If
+    /// the target month is shorter, the day gets clamped to its last day, so
+    /// January 31 plus one month is February 28 or 29. Leap seconds are taken
+    /// into account just like with [`add_utc_days`](Self::add_utc_days),
+    /// including for an instant inside of the leap second 23:59:60.
+    ///
+    /// Fails if the instant or the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn add_utc_months(self, months: i32) -> Result<Self, ConversionError> {
+        self.add_months(months as i64)
+    }
+
+    /// Returns the instant at the same UTC wall clock time on the same day of
+    /// the year the given number of years later, or earlier if negative.
+    /// February 29 gets clamped to February 28 in years that aren't leap
+    /// years. Leap seconds are taken into account just like with
+    /// [`add_utc_days`](Self::add_utc_days), including for an instant inside
+    /// of the leap second 23:59:60.
+    ///
+    /// Fails if the instant or the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn add_utc_years(self, years: i32) -> Result<Self, ConversionError> {
+        self.add_months(years as i64 * 12)
+    }
+
+    fn add_months(self, months: i64) -> Result<Self, ConversionError> {
+        let label = self.checked_utc_label()?;
+        let date = label.0.date();
+        let month = date.year() as i64 * 12 + date.month() as i64 - 1 + months;
+        let year =
+            i32::try_from(month.div_euclid(12)).map_err(|_| ConversionError::TargetOutOfRange)?;
+        let month = Month::try_from(month.rem_euclid(12) as u8 + 1).unwrap();
+        // The day gets clamped to the last day of the month by trying the days
+        // before it, which takes at most three attempts.
+        let date = (1..=date.day())
+            .rev()
+            .find_map(|day| Date::from_calendar_date(year, month, day).ok())
+            .ok_or(ConversionError::TargetOutOfRange)?;
+        Ok(Self::with_utc_date(label, date))
+    }
+
+    // The UTC date the instant is on. An instant inside of a leap second is
+    // still on the day that ends with it.
+    fn utc_date(self) -> Date {
+        let (utc, _) = self.to_utc_label(&mut 0);
+        utc.date()
+    }
+
+    /// Returns the first UTC midnight after the instant. An instant inside of
+    /// the leap second 23:59:60 is followed by the midnight right after it. An
+    /// instant exactly at midnight is followed by the next one.
+    ///
+    /// The naive approach of converting to UTC, adding a day and converting
+    /// back is off by a second across a leap second. Here the midnight is
+    /// looked up in the leap second table instead, so the result is exact.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn next_utc_midnight(&self) -> Self {
+        let next_day = self.utc_date().next_day().expect("date out of range");
+        from_utc(next_day, Time::MIDNIGHT)
+    }
+
+    /// Returns the last UTC midnight before the instant. An instant exactly at
+    /// midnight is preceded by the one a day earlier. Use
+    /// [`start_of_utc_day`](Self::start_of_utc_day) to include the instant
+    /// itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn prev_utc_midnight(&self) -> Self {
+        let start = self.start_of_utc_day();
+        if start < *self {
+            return start;
+        }
+        let previous_day = self.utc_date().previous_day().expect("date out of range");
+        from_utc(previous_day, Time::MIDNIGHT)
+    }
+
+    /// Returns the UTC midnight that starts the UTC day the instant is on, which
+    /// is the instant itself if it's exactly at midnight. An instant inside of
+    /// the leap second 23:59:60 is on the day that ends with it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn start_of_utc_day(&self) -> Self {
+        from_utc(self.utc_date(), Time::MIDNIGHT)
+    }
+
+    /// Returns the first full UTC hour after the instant. An instant inside of
+    /// the leap second 23:59:60 is followed by the midnight right after it. An
+    /// instant exactly at a full hour is followed by the next one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn next_utc_top_of_hour(&self) -> Self {
+        let (utc, _) = self.to_utc_label(&mut 0);
+        let hour = utc.replace_time(Time::from_hms(utc.hour(), 0, 0).unwrap());
+        Self::from(hour + Duration::HOUR)
+    }
+
+    /// Returns the first instant after this one at which the UTC clock reads
+    /// the given time of day. An instant at exactly that time is followed by
+    /// the same time on the next day. The leap second 23:59:60 can't be
+    /// expressed as a [`Time`], so it's never the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is outside of the range of
+    /// [`OffsetDateTime`].
+    pub fn next_utc_time(&self, time: Time) -> Self {
+        let date = self.utc_date();
+        let candidate = from_utc(date, time);
+        if candidate > *self {
+            return candidate;
+        }
+        from_utc(date.next_day().expect("date out of range"), time)
+    }
+
+    /// Returns the instant the first leap second after this one ends at, i.e.
+    /// the UTC midnight from which on the new difference between TAI and UTC
+    /// applies. Both the leap seconds built into the crate and the ones
+    /// provided by the operating system are taken into account.
+    ///
+    /// Returns `None` if no leap second is known after the instant. Up until
+    /// the [expiry](crate::leap_seconds::expires_at) of the leap second table
+    /// that means there is none, but beyond it one may still get announced.
+    pub fn next_leap_transition(self) -> Option<TaiDateTime> {
+        let table = leap_table();
+        let index = seek_tai(&table, table.len(), floor_seconds(self.0));
+        let &(t, diff) = table.get(index)?;
+        Some(Self(Duration::seconds(t + diff)))
+    }
+
+    /// Returns the instant the last leap second at or before this one ended at,
+    /// i.e. the UTC midnight from which on the current difference between TAI
+    /// and UTC applies. Returns `None` if the instant is before the first leap
+    /// second.
+    pub fn previous_leap_transition(self) -> Option<TaiDateTime> {
+        let table = leap_table();
+        let index = seek_tai(&table, table.len(), floor_seconds(self.0));
+        let &(t, diff) = table.get(index.checked_sub(1)?)?;
+        Some(Self(Duration::seconds(t + diff)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{datetime, time};
+
+    use super::*;
+
+    fn utc(utc: OffsetDateTime) -> TaiDateTime {
+        utc.into()
+    }
+
+    #[test]
+    fn midnights() {
+        let new_year = utc(datetime!(2017-01-01 0:00 UTC));
+        let new_years_eve = utc(datetime!(2016-12-31 0:00 UTC));
+        for (time, prev, next) in [
+            // An ordinary day.
+            (
+                utc(datetime!(2016-06-15 12:00 UTC)),
+                utc(datetime!(2016-06-15 0:00 UTC)),
+                utc(datetime!(2016-06-16 0:00 UTC)),
+            ),
+            // The day that ends with a leap second.
+            (
+                utc(datetime!(2016-12-31 12:00 UTC)),
+                new_years_eve,
+                new_year,
+            ),
+            (
+                new_year - Duration::SECOND - Duration::NANOSECOND,
+                new_years_eve,
+                new_year,
+            ),
+            // Inside of the leap second.
+            (new_year - Duration::SECOND, new_years_eve, new_year),
+            (new_year - Duration::NANOSECOND, new_years_eve, new_year),
+            // Exactly at midnight.
+            (new_year, new_years_eve, utc(datetime!(2017-01-02 0:00 UTC))),
+        ] {
+            assert_eq!(time.next_utc_midnight(), next, "{time:?}");
+            assert_eq!(time.prev_utc_midnight(), prev, "{time:?}");
+        }
+        assert_eq!(new_year - new_years_eve, Duration::seconds(86_401));
+        assert_eq!(new_year.start_of_utc_day(), new_year);
+        assert_eq!(
+            (new_year - Duration::NANOSECOND).start_of_utc_day(),
+            new_years_eve,
+        );
+        assert_eq!(
+            (new_year - Duration::NANOSECOND).next_utc_top_of_hour(),
+            new_year,
+        );
+    }
+
+    #[test]
+    fn times_of_day() {
+        let new_year = utc(datetime!(2017-01-01 0:00 UTC));
+        let noon = utc(datetime!(2016-12-31 12:00 UTC));
+        let inside = new_year - Duration::milliseconds(500);
+
+        assert_eq!(
+            noon.next_utc_time(time!(23:59:59)),
+            new_year - Duration::seconds(2),
+        );
+        // Exactly at that time of day, across the leap second.
+        assert_eq!(
+            noon.next_utc_time(time!(12:00)),
+            utc(datetime!(2017-01-01 12:00 UTC)),
+        );
+        assert_eq!(
+            utc(datetime!(2017-01-01 12:00 UTC)) - noon,
+            Duration::seconds(86_401),
+        );
+        // The leap second is still on the last day of the year.
+        assert_eq!(inside.next_utc_time(time!(0:00)), new_year);
+        assert_eq!(
+            inside.next_utc_time(time!(23:59:59)),
+            utc(datetime!(2017-01-01 23:59:59 UTC)),
+        );
+        assert_eq!(
+            inside.next_utc_time(time!(12:00)),
+            utc(datetime!(2017-01-01 12:00 UTC)),
+        );
+        // An ordinary day.
+        assert_eq!(
+            utc(datetime!(2016-06-15 12:00 UTC)).next_utc_time(time!(6:30)),
+            utc(datetime!(2016-06-16 6:30 UTC)),
+        );
+        assert_eq!(
+            utc(datetime!(2016-06-15 6:00 UTC)).next_utc_time(time!(6:30)),
+            utc(datetime!(2016-06-15 6:30 UTC)),
+        );
+    }
+
+    #[test]
+    fn leap_transitions() {
+        let first = utc(datetime!(1972-07-01 0:00 UTC));
+        let mid_2012 = utc(datetime!(2012-07-01 0:00 UTC));
+        let mid_2015 = utc(datetime!(2015-07-01 0:00 UTC));
+        let new_year = utc(datetime!(2017-01-01 0:00 UTC));
+        for (time, previous, next) in [
+            // Before the first leap second.
+            (utc(datetime!(1960-01-01 0:00 UTC)), None, Some(first)),
+            (first - Duration::seconds(2), None, Some(first)),
+            (first - Duration::NANOSECOND, None, Some(first)),
+            (
+                first,
+                Some(first),
+                Some(utc(datetime!(1973-01-01 0:00 UTC))),
+            ),
+            // Between two leap seconds.
+            (
+                utc(datetime!(2014-01-01 0:00 UTC)),
+                Some(mid_2012),
+                Some(mid_2015),
+            ),
+            (
+                mid_2012 + Duration::NANOSECOND,
+                Some(mid_2012),
+                Some(mid_2015),
+            ),
+            (
+                mid_2015 - Duration::seconds(2),
+                Some(mid_2012),
+                Some(mid_2015),
+            ),
+            // Inside of a leap second.
+            (mid_2015 - Duration::SECOND, Some(mid_2012), Some(mid_2015)),
+            (
+                new_year - Duration::NANOSECOND,
+                Some(mid_2015),
+                Some(new_year),
+            ),
+        ] {
+            assert_eq!(time.previous_leap_transition(), previous, "{time:?}");
+            assert_eq!(time.next_leap_transition(), next, "{time:?}");
+        }
+        // Other tests may add leap seconds after the expiry of the table, but
+        // never before it.
+        assert_eq!(
+            utc(datetime!(2020-01-01 0:00 UTC)).previous_leap_transition(),
+            Some(new_year),
+        );
+    }
+
+    #[test]
+    fn leap_folds() {
+        let new_year = utc(datetime!(2017-01-01 0:00 UTC));
+        let repeated = datetime!(2016-12-31 23:59:59.25 UTC);
+        let earlier = TaiDateTime::from_utc_disambiguated(repeated, LeapFold::Earlier);
+        let later = TaiDateTime::from_utc_disambiguated(repeated, LeapFold::Later);
+        assert_eq!(earlier, utc(repeated));
+        assert_eq!(later, earlier + Duration::SECOND);
+        assert_eq!(later, new_year - Duration::milliseconds(750));
+        assert_eq!(earlier.utc_fold(), Some(LeapFold::Earlier));
+        assert_eq!(later.utc_fold(), Some(LeapFold::Later));
+        assert_eq!(earlier.to_utc_date_time(), later.to_utc_date_time());
+
+        // Nothing else is ambiguous.
+        for time in [
+            datetime!(2016-12-31 23:59:58.999999999 UTC),
+            datetime!(2017-01-01 0:00 UTC),
+            datetime!(2016-06-30 23:59:59 UTC),
+            datetime!(1971-12-31 23:59:59 UTC),
+        ] {
+            let earlier = TaiDateTime::from_utc_disambiguated(time, LeapFold::Earlier);
+            assert_eq!(earlier, utc(time));
+            assert_eq!(
+                TaiDateTime::from_utc_disambiguated(time, LeapFold::Later),
+                earlier,
+            );
+            assert_eq!(earlier.utc_fold(), None, "{time}");
+        }
+        assert_eq!(TaiDateTime::MIN.utc_fold(), None);
+        assert_eq!(TaiDateTime::MAX.utc_fold(), None);
+    }
+
+    #[test]
+    fn utc_durations() {
+        let noon = utc(datetime!(2016-12-31 12:00 UTC));
+        // A UTC day across the leap second takes 86401 SI seconds.
+        assert_eq!(
+            noon.add_utc_duration(Duration::DAY),
+            utc(datetime!(2017-01-01 12:00 UTC)),
+        );
+        assert_eq!(
+            noon.add_utc_duration(Duration::DAY) - noon,
+            Duration::seconds(86_401),
+        );
+        assert_eq!(
+            utc(datetime!(2017-01-01 12:00 UTC)).add_utc_duration(-Duration::DAY),
+            noon,
+        );
+        // Without a leap second in between it's the same as adding directly.
+        let duration = Duration::new(3_600, 250_000_000);
+        assert_eq!(noon.add_utc_duration(duration), noon + duration);
+
+        // Out of the leap second, even with a zero duration.
+        let new_year = utc(datetime!(2017-01-01 0:00 UTC));
+        let leap_second = new_year - Duration::milliseconds(500);
+        assert_eq!(
+            leap_second.add_utc_duration(Duration::ZERO),
+            new_year + Duration::milliseconds(500),
+        );
+        assert_eq!(
+            (new_year - Duration::seconds(2)).add_utc_duration(Duration::SECOND),
+            new_year,
+        );
+    }
+
+    #[test]
+    fn utc_days_and_months() {
+        let noon = utc(datetime!(2016-12-31 12:00 UTC));
+        let next_noon = utc(datetime!(2017-01-01 12:00 UTC));
+        assert_eq!(noon.add_utc_days(1), Ok(next_noon));
+        assert_eq!(next_noon - noon, Duration::seconds(86_401));
+        assert_eq!(next_noon.add_utc_days(-1), Ok(noon));
+        assert_eq!(noon.add_utc_days(0), Ok(noon));
+        assert_eq!(
+            noon.add_utc_months(1),
+            Ok(utc(datetime!(2017-01-31 12:00 UTC))),
+        );
+        assert_eq!(
+            utc(datetime!(2017-12-31 12:00 UTC)).add_utc_months(-12),
+            Ok(noon),
+        );
+        assert_eq!(
+            utc(datetime!(2015-12-31 12:00 UTC)).add_utc_years(1),
+            Ok(noon),
+        );
+        assert_eq!(
+            noon.add_utc_days(i64::MAX),
+            Err(ConversionError::TargetOutOfRange),
+        );
+        assert_eq!(
+            noon.add_utc_months(i32::MAX),
+            Err(ConversionError::TargetOutOfRange),
+        );
+    }
+
+    #[test]
+    fn utc_days_and_months_inside_leap_second() {
+        let new_year = utc(datetime!(2017-01-01 0:00 UTC));
+        let inside = new_year - Duration::milliseconds(500);
+
+        // Days without a leap second end with 23:59:59.
+        assert_eq!(
+            inside.add_utc_days(1),
+            Ok(utc(datetime!(2017-01-01 23:59:59.5 UTC))),
+        );
+        assert_eq!(
+            inside.add_utc_days(-1),
+            Ok(utc(datetime!(2016-12-30 23:59:59.5 UTC))),
+        );
+        assert_eq!(
+            inside.add_utc_months(1),
+            Ok(utc(datetime!(2017-01-31 23:59:59.5 UTC))),
+        );
+        assert_eq!(inside.add_utc_days(0), Ok(inside));
+
+        // Days with one keep the leap second, with December 31 clamped to June
+        // 30.
+        let mid_2015 = utc(datetime!(2015-07-01 0:00 UTC));
+        assert_eq!(
+            inside.add_utc_months(-18),
+            Ok(mid_2015 - Duration::milliseconds(500)),
+        );
+        assert_eq!(
+            inside.add_utc_days(-550),
+            Ok(mid_2015 - Duration::milliseconds(500)),
+        );
+        assert_eq!(
+            (mid_2015 - Duration::milliseconds(500)).add_utc_months(18),
+            Ok(utc(datetime!(2016-12-30 23:59:59.5 UTC))),
+        );
+        assert_eq!(
+            (mid_2015 - Duration::milliseconds(500)).add_utc_days(550),
+            Ok(inside),
+        );
+    }
+
+    #[test]
+    fn month_end_clamp() {
+        for (start, months, end) in [
+            (
+                datetime!(2016-01-31 12:00 UTC),
+                1,
+                datetime!(2016-02-29 12:00 UTC),
+            ),
+            (
+                datetime!(2017-01-31 12:00 UTC),
+                1,
+                datetime!(2017-02-28 12:00 UTC),
+            ),
+            (
+                datetime!(2017-03-31 12:00 UTC),
+                -1,
+                datetime!(2017-02-28 12:00 UTC),
+            ),
+            (
+                datetime!(2016-03-30 12:00 UTC),
+                -13,
+                datetime!(2015-02-28 12:00 UTC),
+            ),
+            (
+                datetime!(2016-02-29 12:00 UTC),
+                12,
+                datetime!(2017-02-28 12:00 UTC),
+            ),
+            (
+                datetime!(2016-02-29 12:00 UTC),
+                48,
+                datetime!(2020-02-29 12:00 UTC),
+            ),
+        ] {
+            assert_eq!(
+                utc(start).add_utc_months(months),
+                Ok(utc(end)),
+                "{start} {months}",
+            );
+        }
+        assert_eq!(
+            utc(datetime!(2016-02-29 12:00 UTC)).add_utc_years(1),
+            Ok(utc(datetime!(2017-02-28 12:00 UTC))),
+        );
+    }
+}
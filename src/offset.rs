@@ -0,0 +1,166 @@
+use core::{
+    fmt,
+    ops::{Add, Neg, Sub},
+};
+
+use time::{Duration, OffsetDateTime};
+
+use crate::TaiDateTime;
+
+/// A difference between TAI and UTC, i.e. how far TAI is ahead of UTC, e.g. 37
+/// seconds since 2017. It's a [`Duration`], so it can represent fractions of a
+/// second as well.
+///
+/// Subtracting it from a [`TaiDateTime`] results in the UTC date time and
+/// adding it to an [`OffsetDateTime`] in the TAI date time. Those apply the
+/// offset as is, without consulting the leap second table. Subtracting an
+/// [`OffsetDateTime`] from a [`TaiDateTime`] results in the offset between
+/// them.
+///
+/// Its [`Display`](fmt::Display) implementation always includes the sign, e.g.
+/// `+37s` or `+4.21317s`. A precision, e.g. `{:.6}`, sets the number of
+/// fractional digits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaiUtcOffset(Duration);
+
+impl TaiUtcOffset {
+    /// No difference at all.
+    pub const ZERO: Self = Self(Duration::ZERO);
+
+    /// Creates an offset of the given number of whole seconds.
+    pub const fn from_whole_seconds(seconds: i64) -> Self {
+        Self(Duration::seconds(seconds))
+    }
+
+    /// Creates an offset of the given number of nanoseconds.
+    pub const fn from_nanos(nanos: i64) -> Self {
+        Self(Duration::nanoseconds(nanos))
+    }
+
+    /// Creates an offset from a [`Duration`].
+    pub const fn from_duration(duration: Duration) -> Self {
+        Self(duration)
+    }
+
+    /// Returns the offset as a [`Duration`].
+    pub const fn as_duration(self) -> Duration {
+        self.0
+    }
+
+    /// Returns the number of whole seconds, rounded towards zero.
+    pub const fn whole_seconds(self) -> i64 {
+        self.0.whole_seconds()
+    }
+
+    /// Returns the offset in seconds, including the fractional part.
+    pub fn as_seconds_f64(self) -> f64 {
+        self.0.as_seconds_f64()
+    }
+}
+
+impl fmt::Display for TaiUtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.as_seconds_f64();
+        match f.precision() {
+            Some(precision) => write!(f, "{seconds:+.precision$}s"),
+            None => write!(f, "{seconds:+}s"),
+        }
+    }
+}
+
+impl Add for TaiUtcOffset {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for TaiUtcOffset {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Neg for TaiUtcOffset {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+/// Converts to UTC by removing the offset.
+///
+/// # Panics
+///
+/// Panics if the resulting UTC date time is outside of the range of
+/// [`OffsetDateTime`].
+impl Sub<TaiUtcOffset> for TaiDateTime {
+    type Output = OffsetDateTime;
+
+    fn sub(self, rhs: TaiUtcOffset) -> Self::Output {
+        OffsetDateTime::UNIX_EPOCH + (self.0 - rhs.0)
+    }
+}
+
+/// Converts to TAI by adding the offset.
+impl Add<TaiUtcOffset> for OffsetDateTime {
+    type Output = TaiDateTime;
+
+    fn add(self, rhs: TaiUtcOffset) -> Self::Output {
+        TaiDateTime((self - OffsetDateTime::UNIX_EPOCH) + rhs.0)
+    }
+}
+
+/// Returns the offset between a TAI and a UTC date time.
+impl Sub<OffsetDateTime> for TaiDateTime {
+    type Output = TaiUtcOffset;
+
+    fn sub(self, rhs: OffsetDateTime) -> Self::Output {
+        TaiUtcOffset(self.0 - (rhs - OffsetDateTime::UNIX_EPOCH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn operators() {
+        const OFFSET: TaiUtcOffset = TaiUtcOffset::from_whole_seconds(37);
+        let utc = datetime!(2020-01-01 0:00 UTC);
+        let tai = utc + OFFSET;
+        assert_eq!(tai, TaiDateTime::from(utc));
+        assert_eq!(tai - OFFSET, utc);
+        assert_eq!(tai - utc, OFFSET);
+        assert_eq!(OFFSET.whole_seconds(), 37);
+        assert_eq!(OFFSET.as_duration(), Duration::seconds(37));
+
+        let fraction = TaiUtcOffset::from_nanos(4_213_170_000);
+        assert_eq!(fraction.as_seconds_f64(), 4.21317);
+        assert_eq!(
+            OFFSET - fraction,
+            TaiUtcOffset::from_duration(Duration::new(32, 786_830_000)),
+        );
+        assert_eq!(OFFSET + -OFFSET, TaiUtcOffset::ZERO);
+        assert!(fraction < OFFSET && -OFFSET < TaiUtcOffset::ZERO);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn display() {
+        use alloc::string::ToString;
+
+        let fraction = TaiUtcOffset::from_nanos(4_213_170_000);
+        assert_eq!(TaiUtcOffset::from_whole_seconds(37).to_string(), "+37s");
+        assert_eq!(fraction.to_string(), "+4.21317s");
+        assert_eq!(alloc::format!("{fraction:.6}"), "+4.213170s");
+        assert_eq!((-fraction).to_string(), "-4.21317s");
+        assert_eq!(TaiUtcOffset::ZERO.to_string(), "+0s");
+    }
+}
@@ -0,0 +1,359 @@
+use core::fmt;
+
+use time::{macros::datetime, Date, Duration, Month, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::TaiDateTime;
+
+/// The reason a date time could not be parsed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The text is not a date time in the expected format.
+    InvalidFormat,
+    /// One of the components, such as the month or the hour, is out of range.
+    InvalidComponent,
+    /// The text doesn't state whether it is a UTC or a TAI date time.
+    Ambiguous,
+    /// The date time has a 60th second, but there is no leap second at that
+    /// time.
+    NotALeapSecond,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => f.write_str("the date time is malformed"),
+            Self::InvalidComponent => f.write_str("a component of the date time is out of range"),
+            Self::Ambiguous => f.write_str("the date time is neither marked as UTC nor as TAI"),
+            Self::NotALeapSecond => f.write_str("there is no leap second at that time"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+// The time scale a date time is marked with.
+enum Scale {
+    Tai,
+    Utc(UtcOffset),
+    Unspecified,
+}
+
+// The components of a date time. The second may be 60.
+struct Fields {
+    date: Date,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    scale: Scale,
+}
+
+impl Fields {
+    // The date time with the second limited to 59.
+    fn date_time(&self) -> Result<PrimitiveDateTime, ParseError> {
+        let time = Time::from_hms_nano(
+            self.hour,
+            self.minute,
+            u8::min(self.second, 59),
+            self.nanosecond,
+        )
+        .map_err(|_| ParseError::InvalidComponent)?;
+        Ok(self.date.with_time(time))
+    }
+}
+
+fn number<T: TryFrom<u32>>(text: &mut &str, digits: usize) -> Result<T, ParseError> {
+    let Some(field) = text.get(..digits) else {
+        return Err(ParseError::InvalidFormat);
+    };
+    if !field.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseError::InvalidFormat);
+    }
+    *text = &text[digits..];
+    let value: u32 = field.parse().map_err(|_| ParseError::InvalidFormat)?;
+    value.try_into().map_err(|_| ParseError::InvalidComponent)
+}
+
+fn separator(text: &mut &str, options: &[char]) -> Result<(), ParseError> {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if options.contains(&c) => {
+            *text = chars.as_str();
+            Ok(())
+        }
+        _ => Err(ParseError::InvalidFormat),
+    }
+}
+
+// Parses a year of four digits, or of a sign and at least four digits as in
+// the expanded format of ISO 8601.
+fn year(text: &mut &str) -> Result<i32, ParseError> {
+    let negative = text.starts_with('-');
+    if separator(text, &['+', '-']).is_err() {
+        return number(text, 4);
+    }
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    if digits < 4 {
+        return Err(ParseError::InvalidFormat);
+    }
+    let year: i32 = number(text, digits)?;
+    Ok(if negative { -year } else { year })
+}
+
+// Parses `[±]YYYY-MM-DDTHH:MM:SS[.fffffffff]` followed by `Z`, a UTC offset such
+// as `+01:00`, `TAI`, `(TAI)` or nothing.
+fn fields(mut text: &str) -> Result<Fields, ParseError> {
+    let text = &mut text;
+    let year = year(text)?;
+    separator(text, &['-'])?;
+    let month: u8 = number(text, 2)?;
+    separator(text, &['-'])?;
+    let day: u8 = number(text, 2)?;
+    separator(text, &['T', 't', ' '])?;
+    let hour = number(text, 2)?;
+    separator(text, &[':'])?;
+    let minute = number(text, 2)?;
+    separator(text, &[':'])?;
+    let second = number(text, 2)?;
+
+    let mut nanosecond = 0;
+    if separator(text, &['.']).is_ok() {
+        let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+        if !(1..=9).contains(&digits) {
+            return Err(ParseError::InvalidFormat);
+        }
+        let fraction: u32 = number(text, digits)?;
+        nanosecond = fraction * 10u32.pow(9 - digits as u32);
+    }
+
+    let month = Month::try_from(month).map_err(|_| ParseError::InvalidComponent)?;
+    let date =
+        Date::from_calendar_date(year, month, day).map_err(|_| ParseError::InvalidComponent)?;
+    if second > 60 {
+        return Err(ParseError::InvalidComponent);
+    }
+
+    let text = &mut text.trim_start();
+    let scale = match *text {
+        "" => Scale::Unspecified,
+        "Z" | "z" => Scale::Utc(UtcOffset::UTC),
+        "TAI" | "(TAI)" => Scale::Tai,
+        _ => {
+            let negative = text.starts_with('-');
+            separator(text, &['+', '-'])?;
+            let hours: i8 = number(text, 2)?;
+            separator(text, &[':'])?;
+            let minutes: i8 = number(text, 2)?;
+            if !text.is_empty() {
+                return Err(ParseError::InvalidFormat);
+            }
+            let (hours, minutes) = if negative {
+                (-hours, -minutes)
+            } else {
+                (hours, minutes)
+            };
+            let offset =
+                UtcOffset::from_hms(hours, minutes, 0).map_err(|_| ParseError::InvalidComponent)?;
+            Scale::Utc(offset)
+        }
+    };
+
+    Ok(Fields {
+        date,
+        hour,
+        minute,
+        second,
+        nanosecond,
+        scale,
+    })
+}
+
+// Converts a UTC date time, which may be inside of a leap second.
+fn from_utc_fields(fields: &Fields, offset: UtcOffset) -> Result<TaiDateTime, ParseError> {
+    let utc = fields.date_time()?.assume_offset(offset);
+    let tai = TaiDateTime::from(utc);
+    if fields.second < 60 {
+        return Ok(tai);
+    }
+
+    // A leap second follows if the next UTC second takes two TAI seconds.
+    let last_second = utc.replace_nanosecond(0).unwrap_or(utc);
+    let next_second = last_second
+        .checked_add(Duration::SECOND)
+        .ok_or(ParseError::InvalidComponent)?;
+    if TaiDateTime::from(next_second) - TaiDateTime::from(last_second) != Duration::seconds(2) {
+        return Err(ParseError::NotALeapSecond);
+    }
+    Ok(tai + Duration::SECOND)
+}
+
+impl TaiDateTime {
+    /// Parses a date time in the format of `2016-12-31T23:59:60.5Z`, figuring
+    /// out from the text whether it is on the UTC or on the TAI time scale.
+    ///
+    /// - A date time ending in `TAI` or `(TAI)` is on the TAI time scale, so
+    ///   `2017-01-01T00:00:36 TAI` is the same instant as
+    ///   `2016-12-31T23:59:60Z`.
+    /// - A date time ending in `Z` or a UTC offset such as `+01:00` is on the
+    ///   UTC time scale and gets converted with the leap second table.
+    /// - A date time with a 60th second refers to an inserted leap second,
+    ///   which only exists in UTC, so it's on the UTC time scale even without
+    ///   a `Z`. It's an error if there is no leap second at that time.
+    ///
+    /// Any other date time is ambiguous, so it's rejected.
+    ///
+    /// Years before 0000 or after 9999 have a sign, such as in
+    /// `-0001-12-31T00:00:00 TAI`, which is how they are displayed.
+    pub fn parse_auto(text: &str) -> Result<Self, ParseError> {
+        let fields = fields(text.trim())?;
+        match fields.scale {
+            Scale::Tai if fields.second == 60 => Err(ParseError::NotALeapSecond),
+            Scale::Tai => Ok(Self(fields.date_time()? - datetime!(1970-01-01 0:00))),
+            Scale::Utc(offset) => from_utc_fields(&fields, offset),
+            Scale::Unspecified if fields.second == 60 => from_utc_fields(&fields, UtcOffset::UTC),
+            Scale::Unspecified => Err(ParseError::Ambiguous),
+        }
+    }
+
+    /// Parses a strict RFC 3339 UTC date time ending in `Z`, such as
+    /// `2016-12-31T23:59:60.25Z`, as written by
+    /// [`to_rfc3339_tai`](Self::to_rfc3339_tai). Unlike the parsers of
+    /// [`OffsetDateTime`](time::OffsetDateTime), this accepts a 60th second,
+    /// which refers to the inserted leap second at that time, so the instant
+    /// is recovered exactly. It's an error if there is no leap second at that
+    /// time. Date times with a UTC offset other than `Z` or marked as TAI are
+    /// rejected, use [`parse_auto`](Self::parse_auto) for those.
+    pub fn from_rfc3339_tai(text: &str) -> Result<Self, ParseError> {
+        // `fields` also accepts signed years, a space between the date and the
+        // time and in front of the `Z`, which RFC 3339 doesn't.
+        let strict = text
+            .strip_suffix(['Z', 'z'])
+            .is_some_and(|rest| rest.ends_with(|c: char| c.is_ascii_digit()));
+        let unsigned = text.starts_with(|c: char| c.is_ascii_digit());
+        if !strict || !unsigned || text.as_bytes().get(10) == Some(&b' ') {
+            return Err(ParseError::InvalidFormat);
+        }
+        let fields = fields(text)?;
+        from_utc_fields(&fields, UtcOffset::UTC)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn tai(utc: time::OffsetDateTime) -> TaiDateTime {
+        TaiDateTime::from(utc)
+    }
+
+    #[test]
+    fn tai_suffix() {
+        let expected =
+            TaiDateTime(datetime!(2017-01-01 0:00:36.5 UTC) - datetime!(1970-01-01 0:00 UTC));
+        for text in [
+            "2017-01-01T00:00:36.5 TAI",
+            "2017-01-01T00:00:36.5TAI",
+            "2017-01-01 00:00:36.500000000 (TAI)",
+            " 2017-01-01T00:00:36.5 TAI ",
+        ] {
+            assert_eq!(TaiDateTime::parse_auto(text), Ok(expected), "{text}");
+        }
+        assert_eq!(
+            TaiDateTime::parse_auto("2016-12-31T23:59:60 TAI"),
+            Err(ParseError::NotALeapSecond),
+        );
+        assert_eq!(
+            TaiDateTime::parse_auto("2017-01-01T00:00:36 tai"),
+            Err(ParseError::InvalidFormat),
+        );
+    }
+
+    #[test]
+    fn utc_suffix() {
+        let expected = tai(datetime!(2017-01-01 0:00:00.5 UTC));
+        for text in [
+            "2017-01-01T00:00:00.5Z",
+            "2017-01-01t00:00:00.5z",
+            "2017-01-01T00:00:00.5 Z",
+            "2017-01-01T01:00:00.5+01:00",
+            "2017-01-01T01:00:00.5 +01:00",
+            "2016-12-31T22:30:00.5-01:30",
+            " 2016-12-31T22:30:00.5 -01:30 ",
+        ] {
+            assert_eq!(TaiDateTime::parse_auto(text), Ok(expected), "{text}");
+        }
+
+        // The leap second in a different time zone.
+        assert_eq!(
+            TaiDateTime::parse_auto("2017-01-01T00:59:60+01:00"),
+            Ok(tai(datetime!(2017-01-01 0:00 UTC)) - Duration::SECOND),
+        );
+
+        for (text, error) in [
+            ("2017-01-01T00:00:00+1:00", ParseError::InvalidFormat),
+            ("2017-01-01T00:00:00+01:00:00", ParseError::InvalidFormat),
+            ("2017-01-01T00:00:00 UTC", ParseError::InvalidFormat),
+            ("2017-01-01T00:00:00+25:00", ParseError::InvalidComponent),
+            ("2017-01-01T00:00:60Z", ParseError::NotALeapSecond),
+        ] {
+            assert_eq!(TaiDateTime::parse_auto(text), Err(error), "{text}");
+        }
+    }
+
+    #[test]
+    fn bare_leap_second() {
+        let end_of_2016 = tai(datetime!(2017-01-01 0:00 UTC));
+        assert_eq!(
+            TaiDateTime::parse_auto("2016-12-31T23:59:60"),
+            Ok(end_of_2016 - Duration::SECOND),
+        );
+        assert_eq!(
+            TaiDateTime::parse_auto("2016-12-31T23:59:60.999999999"),
+            Ok(end_of_2016 - Duration::NANOSECOND),
+        );
+        assert_eq!(
+            TaiDateTime::parse_auto("2016-12-31T23:59:60.25"),
+            TaiDateTime::parse_auto("2016-12-31T23:59:60.25Z"),
+        );
+        assert_eq!(
+            TaiDateTime::parse_auto("2017-12-31T23:59:60"),
+            Err(ParseError::NotALeapSecond),
+        );
+    }
+
+    #[test]
+    fn ambiguous() {
+        for text in [
+            "2017-01-01T00:00:00",
+            "2016-12-31T23:59:59.5",
+            " 2017-01-01 00:00:00 ",
+        ] {
+            assert_eq!(
+                TaiDateTime::parse_auto(text),
+                Err(ParseError::Ambiguous),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        for (text, error) in [
+            ("", ParseError::InvalidFormat),
+            ("2017-01-01", ParseError::InvalidFormat),
+            ("2017-1-01T00:00:00Z", ParseError::InvalidFormat),
+            ("2017-01-01T00:00:00.Z", ParseError::InvalidFormat),
+            ("2017-01-01T00:00:00.1234567890Z", ParseError::InvalidFormat),
+            ("2017-13-01T00:00:00Z", ParseError::InvalidComponent),
+            ("2017-02-29T00:00:00Z", ParseError::InvalidComponent),
+            ("2017-01-01T24:00:00Z", ParseError::InvalidComponent),
+            ("2017-01-01T00:00:61Z", ParseError::InvalidComponent),
+        ] {
+            assert_eq!(TaiDateTime::parse_auto(text), Err(error), "{text}");
+        }
+    }
+}
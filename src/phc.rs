@@ -0,0 +1,136 @@
+//! Reading PTP hardware clocks (PHC) on Linux, e.g. the one of a network card
+//! that `ptp4l` keeps in sync with a grandmaster. Such clocks run on the TAI
+//! time scale, so their readings are used as is.
+//!
+//! The clocks are only ever read when asked for explicitly. Neither
+//! [`TaiDateTime::now`] nor any other function falls back to them.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{platform, TaiDateTime};
+
+// The clock type of a dynamic POSIX clock that's referred to by a file
+// descriptor, as in the kernel's `CLOCKFD`.
+const CLOCKFD: libc::clockid_t = 3;
+
+// The clock ID of the dynamic POSIX clock of an open character device, as
+// computed by the kernel's `FD_TO_CLOCKID` macro.
+pub(crate) fn fd_to_clockid(fd: std::os::fd::RawFd) -> libc::clockid_t {
+    (!fd << 3) | CLOCKFD
+}
+
+impl TaiDateTime {
+    /// Reads the PTP hardware clock of the given device, e.g. `/dev/ptp0`,
+    /// interpreting it as TAI. Fails if the device can't be opened, e.g.
+    /// because it doesn't exist or because of missing permissions, or if it
+    /// isn't a clock. Available with the `phc` feature on Linux.
+    pub fn now_from_phc(device: &Path) -> io::Result<Self> {
+        platform::phc_time(device).map(Self)
+    }
+}
+
+/// Lists the devices of the PTP hardware clocks of the system, e.g.
+/// `/dev/ptp0`, in the order of their numbers, by looking at
+/// `/sys/class/ptp`. If that doesn't exist, as the kernel has no PTP support,
+/// there are no clocks. Available with the `phc` feature on Linux.
+pub fn list_phc_devices() -> io::Result<Vec<PathBuf>> {
+    list_phc_devices_in(Path::new("/sys/class/ptp"), Path::new("/dev"))
+}
+
+// Lists the devices of the PTP hardware clocks in `dev` that have an entry in
+// the given sysfs class directory.
+fn list_phc_devices_in(class: &Path, dev: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(class) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut clocks = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let number = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("ptp"))
+            .and_then(|number| number.parse::<u32>().ok());
+        if let Some(number) = number {
+            clocks.push((number, dev.join(name)));
+        }
+    }
+    clocks.sort_unstable();
+    Ok(clocks.into_iter().map(|(_, path)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_ids() {
+        assert_eq!(fd_to_clockid(0), -5);
+        assert_eq!(fd_to_clockid(3), -29);
+        for fd in [0, 1, 3, 42, 1 << 20] {
+            let clock = fd_to_clockid(fd);
+            assert_eq!(clock & 7, CLOCKFD);
+            // The kernel's `CLOCKID_TO_FD`.
+            assert_eq!(!(clock >> 3), fd);
+        }
+    }
+
+    #[test]
+    fn lists_devices_in_order() {
+        let root = tempfile::tempdir().unwrap();
+        let class = root.path().join("class");
+        let dev = root.path().join("dev");
+        for name in ["ptp10", "ptp1", "ptp0", "ptpx", "pps0", "ptp"] {
+            std::fs::create_dir_all(class.join(name)).unwrap();
+        }
+        assert_eq!(
+            list_phc_devices_in(&class, &dev).unwrap(),
+            [dev.join("ptp0"), dev.join("ptp1"), dev.join("ptp10")],
+        );
+    }
+
+    #[test]
+    fn lists_no_devices_without_class() {
+        let root = tempfile::tempdir().unwrap();
+        let class = root.path().join("class");
+        assert!(list_phc_devices_in(&class, root.path()).unwrap().is_empty());
+
+        std::fs::write(&class, "").unwrap();
+        assert!(list_phc_devices_in(&class, root.path()).is_err());
+    }
+
+    #[test]
+    fn rejects_devices_that_are_no_clocks() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let error = TaiDateTime::now_from_phc(file.path()).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::EINVAL));
+        assert_eq!(
+            TaiDateTime::now_from_phc(Path::new("/dev/ptp-missing"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound,
+        );
+    }
+
+    // Needs a PTP hardware clock, e.g. the one of a network card or the one of
+    // KVM with the `ptp_kvm` module, and permission to read it.
+    #[test]
+    #[ignore]
+    fn reads_phc_devices() {
+        let devices = list_phc_devices().unwrap();
+        assert!(!devices.is_empty());
+        for device in devices {
+            let before = TaiDateTime::now();
+            let time = TaiDateTime::now_from_phc(&device).unwrap();
+            assert!(
+                time > before - time::Duration::minutes(1),
+                "{}",
+                device.display()
+            );
+        }
+    }
+}
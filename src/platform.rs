@@ -0,0 +1,274 @@
+//! The few calls into the operating system that the crate needs, kept behind
+//! functions that don't depend on the target, so the rest of the crate doesn't
+//! need to care about which ones are available.
+
+use time::Duration;
+
+// Reads the given clock, if it exists.
+#[cfg(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "linux"
+))]
+fn clock_gettime(clock: libc::clockid_t) -> Option<Duration> {
+    let mut time = core::mem::MaybeUninit::<libc::timespec>::uninit();
+    // SAFETY: The pointer is valid for writing a `timespec`, which is fully
+    // initialized if the call succeeds.
+    let time = unsafe {
+        if libc::clock_gettime(clock, time.as_mut_ptr()) != 0 {
+            return None;
+        }
+        time.assume_init()
+    };
+    // `time_t` is only 32 bits wide on some of these targets.
+    #[allow(clippy::unnecessary_cast)]
+    Some(Duration::new(time.tv_sec as i64, time.tv_nsec as i32))
+}
+
+// The time since 1970-01-01 00:00:00 according to the PTP hardware clock of
+// the given device, whose file descriptor doubles as a dynamic POSIX clock.
+#[cfg(all(feature = "phc", target_os = "linux"))]
+pub(crate) fn phc_time(device: &std::path::Path) -> std::io::Result<Duration> {
+    use std::os::fd::AsRawFd;
+
+    let file = std::fs::File::open(device)?;
+    let clock = crate::phc::fd_to_clockid(file.as_raw_fd());
+    let mut time = core::mem::MaybeUninit::<libc::timespec>::uninit();
+    // SAFETY: The pointer is valid for writing a `timespec`, which is fully
+    // initialized if the call succeeds. The file, and with it the clock, stays
+    // open until after the call.
+    let time = unsafe {
+        if libc::clock_gettime(clock, time.as_mut_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        time.assume_init()
+    };
+    // `time_t` is only 32 bits wide on some of these targets.
+    #[allow(clippy::unnecessary_cast)]
+    Ok(Duration::new(time.tv_sec as i64, time.tv_nsec as i32))
+}
+
+// The time since 1970-01-01 00:00:00 TAI according to the kernel's TAI clock,
+// if there is one.
+#[cfg(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "linux"
+))]
+pub(crate) fn clock_tai() -> Option<Duration> {
+    clock_gettime(libc::CLOCK_TAI)
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "linux"
+)))]
+pub(crate) fn clock_tai() -> Option<Duration> {
+    None
+}
+
+// The time since the UNIX epoch according to the system's real time clock, if
+// the standard library can read it. On `wasm32-unknown-unknown` it can't and
+// panics instead.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn clock_utc() -> Option<Duration> {
+    Some(time::OffsetDateTime::now_utc() - time::OffsetDateTime::UNIX_EPOCH)
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn clock_utc() -> Option<Duration> {
+    None
+}
+
+// The time since the UNIX epoch according to the system's real time clock, on
+// the targets that have a TAI clock to compare it to.
+#[cfg(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "linux"
+))]
+pub(crate) fn clock_realtime() -> Option<Duration> {
+    clock_gettime(libc::CLOCK_REALTIME)
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "linux"
+)))]
+pub(crate) fn clock_realtime() -> Option<Duration> {
+    None
+}
+
+// The time since the UNIX epoch according to the system's real time clock,
+// with the resolution of the clock tick, if there is such a coarse clock.
+#[cfg(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "linux"
+))]
+pub(crate) fn clock_realtime_coarse() -> Option<Duration> {
+    clock_gettime(libc::CLOCK_REALTIME_COARSE)
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "linux"
+)))]
+pub(crate) fn clock_realtime_coarse() -> Option<Duration> {
+    None
+}
+
+// Sets the modification time of the file at the given path, following symbolic
+// links and leaving the access time unchanged.
+#[cfg(unix)]
+pub(crate) fn set_mtime(path: &std::path::Path, mtime: libc::timespec) -> std::io::Result<()> {
+    use std::{ffi::CString, io, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the path contains a nul byte"))?;
+    // SAFETY: A `timespec` is plain old data, so all zeros are valid.
+    let mut atime: libc::timespec = unsafe { core::mem::zeroed() };
+    atime.tv_nsec = libc::UTIME_OMIT;
+    let times = [atime, mtime];
+    // SAFETY: The path is nul terminated and there are two times, as required.
+    if unsafe { libc::utimensat(libc::AT_FDCWD, path.as_ptr(), times.as_ptr(), 0) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// A leap second as stored in the Windows registry.
+//
+// https://github.com/microsoft/STL/blob/e28f9561233a58d48d893094ed3a6bc0c5ee6ad9/stl/inc/__msvc_tzdb.hpp#L27
+#[cfg(any(all(feature = "windows", windows), all(test, any(windows, unix))))]
+#[repr(C)]
+pub(crate) struct LeapInfo {
+    pub(crate) year: u16,
+    pub(crate) month: u16,
+    pub(crate) day: u16,
+    pub(crate) hour: u16,
+    pub(crate) negative: u16,
+    pub(crate) reserved: u16,
+}
+
+// Reads the leap seconds stored in the Windows registry. The reason it fails
+// is added to the warnings.
+#[cfg(all(feature = "windows", windows))]
+pub(crate) fn registry_leap_seconds(
+    warnings: &mut Vec<crate::leap_seconds::LoadWarning>,
+) -> Result<Vec<LeapInfo>, crate::leap_seconds::SourceError> {
+    use std::{
+        mem::{self, MaybeUninit},
+        ptr,
+    };
+
+    use windows_sys::Win32::{
+        Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS},
+        System::Registry::{
+            RegCloseKey, RegOpenKeyExW, RegQueryValueExW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ,
+        },
+    };
+
+    use crate::leap_seconds::{LoadWarning, SourceError};
+
+    struct RegKey(HKEY);
+
+    impl Drop for RegKey {
+        fn drop(&mut self) {
+            unsafe {
+                RegCloseKey(self.0);
+            }
+        }
+    }
+
+    unsafe {
+        let mut leap_sec_key = MaybeUninit::uninit();
+        let status = RegOpenKeyExW(
+            HKEY_LOCAL_MACHINE,
+            b"SYSTEM\\CurrentControlSet\\Control\\LeapSecondInformation\0"
+                .map(|b| b as _)
+                .as_ptr(),
+            0,
+            KEY_READ,
+            leap_sec_key.as_mut_ptr(),
+        );
+        if status != ERROR_SUCCESS {
+            warnings.push(LoadWarning::RegistryStatus {
+                function: "RegOpenKeyExW",
+                status,
+            });
+            return Err(SourceError::Unavailable);
+        }
+        let leap_sec_key = RegKey(leap_sec_key.assume_init());
+
+        let reg_subkey_name = b"LeapSeconds\0".map(|b| b as _);
+
+        let mut byte_size = 0;
+        let status = RegQueryValueExW(
+            leap_sec_key.0,
+            reg_subkey_name.as_ptr(),
+            ptr::null(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut byte_size,
+        );
+        if status != ERROR_SUCCESS && status != ERROR_MORE_DATA {
+            warnings.push(LoadWarning::RegistryStatus {
+                function: "RegQueryValueExW",
+                status,
+            });
+            return Err(SourceError::Unavailable);
+        }
+        if byte_size == 0 {
+            return Err(SourceError::Unavailable);
+        }
+
+        let count = byte_size as usize / mem::size_of::<LeapInfo>();
+        if count * mem::size_of::<LeapInfo>() != byte_size as usize {
+            warnings.push(LoadWarning::RegistrySize {
+                size: byte_size as usize,
+                entry_size: mem::size_of::<LeapInfo>(),
+            });
+            return Err(SourceError::Invalid);
+        }
+
+        let mut elements = Vec::<LeapInfo>::with_capacity(count);
+
+        let mut new_byte_size = byte_size;
+        let status = RegQueryValueExW(
+            leap_sec_key.0,
+            reg_subkey_name.as_ptr(),
+            ptr::null(),
+            ptr::null_mut(),
+            elements.as_mut_ptr().cast(),
+            &mut new_byte_size,
+        );
+        if status != ERROR_SUCCESS {
+            warnings.push(LoadWarning::RegistryStatus {
+                function: "RegQueryValueExW",
+                status,
+            });
+            return Err(SourceError::Unavailable);
+        }
+        if new_byte_size != byte_size {
+            warnings.push(LoadWarning::RegistrySizeChanged {
+                before: byte_size as usize,
+                after: new_byte_size as usize,
+            });
+            return Err(SourceError::Unavailable);
+        }
+        elements.set_len(count);
+
+        Ok(elements)
+    }
+}
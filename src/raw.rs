@@ -0,0 +1,60 @@
+//! Conversions of whole second time stamps in bulk, for when going through
+//! [`TaiDateTime`](crate::TaiDateTime) one element at a time is too slow.
+//!
+//! The time stamps are looked up in the leap second table one run at a time,
+//! where a run is a sequence of time stamps between the same two leap seconds.
+//! The offset then gets added to the whole run in a plain loop the compiler
+//! can vectorize, so sorted input is a lot faster than shuffled input.
+
+use crate::{diff_before, leap_table, seek_tai, seek_utc};
+
+// Adds `diff` to the run at the start of `values` whose elements are all within
+// `start..end` and returns the rest.
+fn apply_run(values: &mut [i64], start: i64, end: i64, diff: i64) -> &mut [i64] {
+    let len = values
+        .iter()
+        .position(|&t| t < start || t >= end)
+        .unwrap_or(values.len());
+    let (run, rest) = values.split_at_mut(len);
+    for t in run {
+        *t = t.saturating_add(diff);
+    }
+    rest
+}
+
+/// Converts UNIX time stamps to seconds since 1970-01-01 00:00:00 TAI in place.
+/// Each element ends up the same as the whole seconds of converting it as an
+/// [`OffsetDateTime`](time::OffsetDateTime), except that the result saturates
+/// instead of overflowing.
+pub fn apply_tai_offset_in_place(utc_seconds: &mut [i64]) {
+    let table = leap_table();
+    let mut index = 0;
+    let mut rest = utc_seconds;
+    while let Some(&first) = rest.first() {
+        index = seek_utc(&table, index, first);
+        let start = index.checked_sub(1).map_or(i64::MIN, |i| table[i].0);
+        let end = table.get(index).map_or(i64::MAX, |&(t, _)| t);
+        rest = apply_run(rest, start, end, diff_before(&table, index));
+    }
+}
+
+/// Converts seconds since 1970-01-01 00:00:00 TAI to UNIX time stamps in
+/// place. This is the inverse of [`apply_tai_offset_in_place`]. Each element
+/// ends up the same as the UNIX time stamp of converting it as a
+/// [`TaiDateTime`](crate::TaiDateTime) to an
+/// [`OffsetDateTime`](time::OffsetDateTime), except that the result saturates
+/// instead of overflowing. Just like there, a time stamp inside of an inserted
+/// leap second becomes the midnight right after it.
+pub fn remove_tai_offset_in_place(tai_seconds: &mut [i64]) {
+    let table = leap_table();
+    let mut index = 0;
+    let mut rest = tai_seconds;
+    while let Some(&first) = rest.first() {
+        index = seek_tai(&table, index, first);
+        let start = index
+            .checked_sub(1)
+            .map_or(i64::MIN, |i| table[i].0 + table[i].1);
+        let end = table.get(index).map_or(i64::MAX, |&(t, diff)| t + diff);
+        rest = apply_run(rest, start, end, -diff_before(&table, index));
+    }
+}
@@ -0,0 +1,241 @@
+use core::fmt;
+
+use time::Duration;
+
+use crate::TaiDateTime;
+
+// The units a relative time is made up of, from the largest to the smallest,
+// as their length in seconds, their compact symbol and their verbose name.
+const UNITS: [(u128, &str, &str); 4] = [
+    (24 * 60 * 60, "d", "day"),
+    (60 * 60, "h", "hour"),
+    (60, "m", "minute"),
+    (1, "s", "second"),
+];
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// How a [`RelativeTime`] spells out its units.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RelativeStyle {
+    /// Units are abbreviated, e.g. `in 2m 3s`.
+    #[default]
+    Compact,
+    /// Units are spelled out, e.g. `in 2 minutes 3 seconds`.
+    Verbose,
+}
+
+/// The time between two instants in a human friendly form, such as `in 2m 3s`,
+/// `7s ago` or `now`. Created with [`TaiDateTime::relative_to`].
+///
+/// As both instants are on the TAI time scale, the relative time is the actual
+/// elapsed time, which doesn't jump around leap seconds like it would with UTC.
+///
+/// The time is shown in days, hours, minutes and seconds, with at most two of
+/// them by default, starting with the largest non-zero one. The smallest shown
+/// unit is rounded half away from zero, i.e. 59.5 s shows as `1m` and 59.4 s as
+/// `59s`. Units that end up zero are left out, so 1 h 0 m 10 s shows as `1h`.
+/// If the time is shorter than the [threshold](Self::now_threshold), or rounds
+/// to zero, it shows as `now`.
+///
+/// Formatting writes directly to the formatter, so it doesn't allocate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RelativeTime {
+    duration: Duration,
+    style: RelativeStyle,
+    now_threshold: Duration,
+    max_units: usize,
+}
+
+impl RelativeTime {
+    /// The signed time from the reference to the instant, i.e. positive if the
+    /// instant is after the reference.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Sets how the units are spelled out. The default is
+    /// [`RelativeStyle::Compact`].
+    pub fn style(self, style: RelativeStyle) -> Self {
+        Self { style, ..self }
+    }
+
+    /// Sets how close the instants have to be to show as `now`. The default is
+    /// one second.
+    pub fn now_threshold(self, now_threshold: Duration) -> Self {
+        Self {
+            now_threshold,
+            ..self
+        }
+    }
+
+    /// Sets how many units are shown at most. The default is two. Zero is
+    /// treated as one.
+    pub fn max_units(self, max_units: usize) -> Self {
+        Self {
+            max_units: max_units.max(1),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for RelativeTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.duration.whole_nanoseconds().unsigned_abs();
+        if nanos < self.now_threshold.whole_nanoseconds().unsigned_abs() {
+            return f.write_str("now");
+        }
+
+        let largest_unit = |nanos: u128| {
+            UNITS
+                .iter()
+                .position(|&(secs, ..)| nanos >= secs * NANOS_PER_SECOND)
+                .unwrap_or(UNITS.len() - 1)
+        };
+
+        // Round to the smallest shown unit, half away from zero. That may carry
+        // into a larger unit, in which case the rounded time is exactly one of
+        // that unit.
+        let smallest = (largest_unit(nanos) + self.max_units - 1).min(UNITS.len() - 1);
+        let step = UNITS[smallest].0 * NANOS_PER_SECOND;
+        let rounded = (nanos + step / 2) / step * step;
+        if rounded == 0 {
+            return f.write_str("now");
+        }
+        let largest = largest_unit(rounded);
+
+        let future = self.duration.is_positive();
+        if future {
+            f.write_str("in ")?;
+        }
+        let mut remaining = rounded / NANOS_PER_SECOND;
+        let mut first = true;
+        for &(secs, symbol, name) in UNITS.iter().skip(largest).take(self.max_units) {
+            let count = remaining / secs;
+            remaining %= secs;
+            if count == 0 {
+                continue;
+            }
+            if !first {
+                f.write_str(" ")?;
+            }
+            first = false;
+            match self.style {
+                RelativeStyle::Compact => write!(f, "{count}{symbol}")?,
+                RelativeStyle::Verbose if count == 1 => write!(f, "1 {name}")?,
+                RelativeStyle::Verbose => write!(f, "{count} {name}s")?,
+            }
+        }
+        if !future {
+            f.write_str(" ago")?;
+        }
+        Ok(())
+    }
+}
+
+impl TaiDateTime {
+    /// Describes the time from `reference` to this instant in a human friendly
+    /// form, such as `in 2m 3s` if this instant is after the reference, or
+    /// `7s ago` if it's before it. See [`RelativeTime`] for the details.
+    pub fn relative_to(&self, reference: TaiDateTime) -> RelativeTime {
+        RelativeTime {
+            duration: *self - reference,
+            style: RelativeStyle::Compact,
+            now_threshold: Duration::SECOND,
+            max_units: 2,
+        }
+    }
+
+    /// Describes the time from now to this instant in a human friendly form,
+    /// like [`relative_to`](Self::relative_to).
+    #[cfg(feature = "std")]
+    pub fn relative_to_now(&self) -> RelativeTime {
+        self.relative_to(Self::now())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    fn relative(millis: i64) -> RelativeTime {
+        let reference = TaiDateTime(Duration::seconds(1_000_000));
+        (reference + Duration::milliseconds(millis)).relative_to(reference)
+    }
+
+    fn check(relative: RelativeTime, future: &str, past: &str) {
+        assert_eq!(relative.to_string(), future);
+        let negative = RelativeTime {
+            duration: -relative.duration,
+            ..relative
+        };
+        assert_eq!(negative.to_string(), past);
+    }
+
+    #[test]
+    fn rounding() {
+        check(relative(59_400), "in 59s", "59s ago");
+        check(relative(59_500), "in 1m", "1m ago");
+        check(relative(59_600), "in 1m", "1m ago");
+        check(relative(60_499), "in 1m", "1m ago");
+        check(relative(60_500), "in 1m 1s", "1m 1s ago");
+        check(relative(1_000), "in 1s", "1s ago");
+        check(relative(999), "now", "now");
+    }
+
+    #[test]
+    fn carry() {
+        // Rounded to seconds, which carries into the hours.
+        check(relative(3_599_400), "in 59m 59s", "59m 59s ago");
+        check(relative(3_599_500), "in 1h", "1h ago");
+        // Rounded to minutes.
+        check(relative(7_169_999), "in 1h 59m", "1h 59m ago");
+        check(relative(7_170_000), "in 2h", "2h ago");
+        check(relative(86_370_000), "in 1d", "1d ago");
+        check(relative(86_369_999), "in 23h 59m", "23h 59m ago");
+        // Rounded to seconds, with just a single unit.
+        check(relative(59_500).max_units(1), "in 1m", "1m ago");
+        check(relative(89_999).max_units(0), "in 1m", "1m ago");
+        check(relative(90_000).max_units(1), "in 2m", "2m ago");
+    }
+
+    #[test]
+    fn zero_units() {
+        check(relative(3_610_000), "in 1h", "1h ago");
+        check(relative(3_610_000).max_units(3), "in 1h 10s", "1h 10s ago");
+        check(
+            relative(90_061_000).max_units(4),
+            "in 1d 1h 1m 1s",
+            "1d 1h 1m 1s ago",
+        );
+    }
+
+    #[test]
+    fn now() {
+        check(relative(0), "now", "now");
+        check(
+            relative(59_000).now_threshold(Duration::MINUTE),
+            "now",
+            "now",
+        );
+        // Without a threshold, it's only rounding to zero.
+        let exact = |millis| relative(millis).now_threshold(Duration::ZERO);
+        check(exact(400), "now", "now");
+        check(exact(500), "in 1s", "1s ago");
+    }
+
+    #[test]
+    fn verbose() {
+        let verbose = |millis| relative(millis).style(RelativeStyle::Verbose);
+        check(verbose(1_000), "in 1 second", "1 second ago");
+        check(verbose(59_500), "in 1 minute", "1 minute ago");
+        check(
+            verbose(7_380_000),
+            "in 2 hours 3 minutes",
+            "2 hours 3 minutes ago",
+        );
+        check(verbose(90_000_000), "in 1 day 1 hour", "1 day 1 hour ago");
+    }
+}
@@ -0,0 +1,141 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use time::OffsetDateTime;
+
+use crate::TaiDateTime;
+
+// Displays an instant as an RFC 3339 UTC date time with nanosecond precision.
+// Instants within an inserted leap second are displayed with 60 seconds.
+pub(crate) struct Rfc3339 {
+    utc: OffsetDateTime,
+    leap: bool,
+}
+
+impl Rfc3339 {
+    pub(crate) fn new(time: TaiDateTime, hint: &mut usize) -> Self {
+        let (utc, leap) = time.to_utc_label(hint);
+        Self { utc, leap }
+    }
+}
+
+impl fmt::Display for Rfc3339 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let utc = self.utc;
+        let second = if self.leap { 60 } else { utc.second() };
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:09}Z",
+            utc.year(),
+            utc.month() as u8,
+            utc.day(),
+            utc.hour(),
+            utc.minute(),
+            second,
+            utc.nanosecond(),
+        )
+    }
+}
+
+/// Formats the current time as an RFC 3339 UTC date time with nanosecond
+/// precision, such as `2016-12-31T23:59:60.250000000Z`. As the current time is
+/// determined on the TAI time scale, leap seconds show up as a 60th second
+/// instead of being smeared or repeated.
+#[cfg(feature = "std")]
+pub fn format_now_rfc3339() -> String {
+    Rfc3339::new(TaiDateTime::now(), &mut 0).to_string()
+}
+
+impl TaiDateTime {
+    /// Formats the instant as an RFC 3339 UTC date time with nanosecond
+    /// precision, such as `2016-12-31T23:59:60.250000000Z`. Unlike with
+    /// [`OffsetDateTime`], an instant inside of an inserted leap second is
+    /// written with a 60th second, so every instant gets its own label and
+    /// [`from_rfc3339_tai`](Self::from_rfc3339_tai) recovers it exactly.
+    pub fn to_rfc3339_tai(self) -> String {
+        Rfc3339::new(self, &mut 0).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use super::*;
+    use crate::ParseError;
+
+    #[test]
+    fn leap_second() {
+        let new_year = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        for (time, text) in [
+            (
+                new_year - Duration::seconds(2),
+                "2016-12-31T23:59:59.000000000Z",
+            ),
+            (
+                new_year - Duration::SECOND,
+                "2016-12-31T23:59:60.000000000Z",
+            ),
+            (
+                new_year - Duration::milliseconds(750),
+                "2016-12-31T23:59:60.250000000Z",
+            ),
+            (
+                new_year - Duration::NANOSECOND,
+                "2016-12-31T23:59:60.999999999Z",
+            ),
+            (new_year, "2017-01-01T00:00:00.000000000Z"),
+        ] {
+            assert_eq!(time.to_rfc3339_tai(), text);
+            assert_eq!(TaiDateTime::from_rfc3339_tai(text), Ok(time));
+        }
+        assert_eq!(
+            TaiDateTime::from_rfc3339_tai("2016-12-31T23:59:60.25Z"),
+            Ok(new_year - Duration::milliseconds(750)),
+        );
+    }
+
+    fn check_round_trip(time: TaiDateTime) {
+        let text = time.to_rfc3339_tai();
+        assert_eq!(TaiDateTime::from_rfc3339_tai(&text), Ok(time), "{text}");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = fastrand::Rng::with_seed(0x3339);
+        // Inside of each of the leap seconds.
+        for &(t, diff) in crate::LEAP_SECONDS {
+            let nanos = rng.i32(0..1_000_000_000);
+            check_round_trip(TaiDateTime(Duration::new(t + diff - 1, nanos)));
+        }
+
+        let (start, _) = TaiDateTime::from(datetime!(1900-01-01 0:00 UTC)).as_timespec();
+        let (end, _) = TaiDateTime::from(datetime!(2100-01-01 0:00 UTC)).as_timespec();
+        for _ in 0..10_000 {
+            let (secs, nanos) = (rng.i64(start..end), rng.i32(0..1_000_000_000));
+            check_round_trip(TaiDateTime(Duration::new(secs, nanos)));
+        }
+    }
+
+    #[test]
+    fn strict() {
+        for (text, error) in [
+            ("2016-12-31T23:59:59Z", None),
+            ("2016-12-31t23:59:59.5z", None),
+            ("2016-12-31T23:59:59+00:00", Some(ParseError::InvalidFormat)),
+            ("2016-12-31T23:59:59 Z", Some(ParseError::InvalidFormat)),
+            ("2016-12-31 23:59:59Z", Some(ParseError::InvalidFormat)),
+            ("2016-12-31T23:59:59 TAI", Some(ParseError::InvalidFormat)),
+            ("+2016-12-31T23:59:59Z", Some(ParseError::InvalidFormat)),
+            ("2016-12-31T23:59:59", Some(ParseError::InvalidFormat)),
+            ("2017-12-31T23:59:60Z", Some(ParseError::NotALeapSecond)),
+            ("2016-12-32T23:59:59Z", Some(ParseError::InvalidComponent)),
+        ] {
+            let result = TaiDateTime::from_rfc3339_tai(text);
+            match error {
+                None => assert!(result.is_ok(), "{text}"),
+                Some(error) => assert_eq!(result, Err(error), "{text}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,502 @@
+//! Date times on time scales other than TAI, with a single [`ScaledDateTime`]
+//! type that's generic over the [`TimeScale`].
+//!
+//! Each scale only defines how its readings relate to TAI, so converting
+//! between any two scales goes through TAI and there is only one conversion
+//! per scale rather than one per pair of scales. Other crates can add their
+//! own scales by implementing [`TimeScale`] for a marker type.
+//!
+//! Besides TAI and UTC, there are GPS time, Terrestrial Time, the
+//! relativistic coordinate and dynamical time scales TCG, TDB and TCB, and
+//! UT1, which follows the rotation of the Earth.
+//!
+//! [`TaiDateTime`] stays the main type of the crate. It converts to and from
+//! `ScaledDateTime<Tai>` without any loss.
+
+use core::{
+    fmt,
+    hash::Hash,
+    marker::PhantomData,
+    ops::{Add, Sub},
+};
+
+use time::{Duration, OffsetDateTime};
+
+mod dynamical;
+mod ut1;
+
+pub use self::dynamical::*;
+pub use self::ut1::*;
+
+use crate::{
+    display::write_date_time, floor_seconds, leap_seconds_at_tai, leap_seconds_at_utc, OutOfRange,
+    TaiDateTime, NANOS_PER_DAY, TT_MINUS_TAI,
+};
+
+/// A time scale, i.e. a way of labeling instants, defined by how its readings
+/// relate to TAI. A reading is the time since the scale's own reading of
+/// 1970-01-01 00:00:00, i.e. since the instant its clock showed that.
+///
+/// Implement this for a marker type to add a scale of your own.
+pub trait TimeScale: Copy + fmt::Debug + Eq + Ord + Hash {
+    /// The short name of the scale, e.g. `TAI`, which is used when formatting
+    /// date times on it.
+    const NAME: &'static str;
+
+    /// Converts a reading of this scale to the instant on the TAI time scale.
+    fn to_tai(reading: Duration) -> TaiDateTime;
+
+    /// Converts an instant on the TAI time scale to a reading of this scale.
+    fn from_tai(time: TaiDateTime) -> Duration;
+
+    /// Converts a UTC date time to a reading of this scale. By default this
+    /// goes through [`TaiDateTime`]'s [`From`] conversion.
+    fn from_utc(time: OffsetDateTime) -> Duration {
+        Self::from_tai(time.into())
+    }
+
+    /// Converts a reading of this scale to a UTC date time. By default this
+    /// goes through [`TaiDateTime`]'s [`From`] conversion, so it panics the
+    /// same way.
+    fn to_utc(reading: Duration) -> OffsetDateTime {
+        Self::to_tai(reading).into()
+    }
+}
+
+/// International Atomic Time (TAI), which all other scales are defined
+/// relative to.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tai;
+
+impl TimeScale for Tai {
+    const NAME: &'static str = "TAI";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        TaiDateTime::saturating(reading)
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        time.0
+    }
+}
+
+/// Coordinated Universal Time (UTC). Its readings don't count the leap
+/// seconds, just like UNIX time stamps, so they are converted with the leap
+/// second table, just like the [`From`] conversions between [`TaiDateTime`]
+/// and [`OffsetDateTime`]. In particular an instant inside of an inserted leap
+/// second is read as the same time of day in the second after it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utc;
+
+impl TimeScale for Utc {
+    const NAME: &'static str = "UTC";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        let diff = leap_seconds_at_utc(floor_seconds(reading));
+        TaiDateTime::saturating(reading.saturating_add(Duration::seconds(diff)))
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        let diff = leap_seconds_at_tai(floor_seconds(time.0));
+        time.0.saturating_sub(Duration::seconds(diff))
+    }
+
+    fn from_utc(time: OffsetDateTime) -> Duration {
+        time - OffsetDateTime::UNIX_EPOCH
+    }
+
+    fn to_utc(reading: Duration) -> OffsetDateTime {
+        OffsetDateTime::UNIX_EPOCH + reading
+    }
+}
+
+/// GPS time, which is always exactly 19 seconds behind TAI, as it matched UTC
+/// when it started at 1980-01-06 00:00:00 UTC. See [`GpsDateTime`] for GPS
+/// weeks.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gps;
+
+// How far TAI is ahead of GPS time, by definition.
+const TAI_MINUS_GPS: Duration = Duration::seconds(19);
+
+impl TimeScale for Gps {
+    const NAME: &'static str = "GPS";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        TaiDateTime::saturating(reading.saturating_add(TAI_MINUS_GPS))
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        time.0.saturating_sub(TAI_MINUS_GPS)
+    }
+}
+
+/// Terrestrial Time (TT), which is always exactly 32.184 seconds ahead of TAI.
+/// See [`TtDateTime`] for Julian Dates.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tt;
+
+impl TimeScale for Tt {
+    const NAME: &'static str = "TT";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        TaiDateTime::saturating(reading.saturating_sub(TT_MINUS_TAI))
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        time.0.saturating_add(TT_MINUS_TAI)
+    }
+}
+
+/// A date time on the time scale `S`, stored as its reading, i.e. the time
+/// since the scale's own reading of 1970-01-01 00:00:00.
+///
+/// Date times on the same scale can be compared and subtracted directly. Use
+/// [`to_scale`](Self::to_scale) to convert to another scale.
+///
+/// Its [`Display`](fmt::Display) implementation writes the reading as a date
+/// time on the scale, followed by its name, e.g.
+/// `2017-01-01T00:00:17.000000000 GPS`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScaledDateTime<S: TimeScale> {
+    reading: Duration,
+    scale: PhantomData<S>,
+}
+
+impl<S: TimeScale> ScaledDateTime<S> {
+    /// Creates a date time from its reading, i.e. the time since the scale's
+    /// own reading of 1970-01-01 00:00:00.
+    pub const fn from_reading(reading: Duration) -> Self {
+        Self {
+            reading,
+            scale: PhantomData,
+        }
+    }
+
+    /// Returns the reading, i.e. the time since the scale's own reading of
+    /// 1970-01-01 00:00:00.
+    pub const fn reading(self) -> Duration {
+        self.reading
+    }
+
+    /// Converts the date time to the instant on the TAI time scale.
+    pub fn to_tai(self) -> TaiDateTime {
+        S::to_tai(self.reading)
+    }
+
+    /// Converts an instant on the TAI time scale to a date time on this
+    /// scale.
+    pub fn from_tai(time: TaiDateTime) -> Self {
+        Self::from_reading(S::from_tai(time))
+    }
+
+    /// Converts the date time to another time scale, by way of TAI.
+    pub fn to_scale<T: TimeScale>(self) -> ScaledDateTime<T> {
+        ScaledDateTime::from_tai(self.to_tai())
+    }
+}
+
+impl<S: TimeScale> fmt::Display for ScaledDateTime<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_date_time(f, self.reading, S::NAME)
+    }
+}
+
+impl<S: TimeScale> From<TaiDateTime> for ScaledDateTime<S> {
+    fn from(time: TaiDateTime) -> Self {
+        Self::from_tai(time)
+    }
+}
+
+impl<S: TimeScale> From<ScaledDateTime<S>> for TaiDateTime {
+    fn from(time: ScaledDateTime<S>) -> Self {
+        time.to_tai()
+    }
+}
+
+/// Converts with [`TimeScale::from_utc`], so for the scales of this crate,
+/// every [`OffsetDateTime`] is representable and this never panics.
+impl<S: TimeScale> From<OffsetDateTime> for ScaledDateTime<S> {
+    fn from(time: OffsetDateTime) -> Self {
+        Self::from_reading(S::from_utc(time))
+    }
+}
+
+/// Converts with [`TimeScale::to_utc`].
+///
+/// # Panics
+///
+/// Panics if the UTC date time is outside of the range of [`OffsetDateTime`].
+impl<S: TimeScale> From<ScaledDateTime<S>> for OffsetDateTime {
+    fn from(time: ScaledDateTime<S>) -> Self {
+        S::to_utc(time.reading)
+    }
+}
+
+/// Returns the time elapsed between two readings of the scale. For scales
+/// whose readings skip leap seconds, like [`Utc`], the leap seconds in between
+/// aren't included.
+impl<S: TimeScale> Sub for ScaledDateTime<S> {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.reading - rhs.reading
+    }
+}
+
+impl<S: TimeScale> Add<Duration> for ScaledDateTime<S> {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self::from_reading(self.reading + rhs)
+    }
+}
+
+impl<S: TimeScale> Sub<Duration> for ScaledDateTime<S> {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self::from_reading(self.reading - rhs)
+    }
+}
+
+/// A date time on the GPS time scale. GPS receivers usually count weeks and
+/// seconds within a week from the GPS epoch, 1980-01-06 00:00:00 UTC, which
+/// are available as [`week`](Self::week) and
+/// [`time_of_week`](Self::time_of_week).
+pub type GpsDateTime = ScaledDateTime<Gps>;
+
+impl ScaledDateTime<Gps> {
+    /// The GPS epoch, 1980-01-06 00:00:00 UTC, at which GPS time started,
+    /// matching UTC at the time.
+    pub const GPS_EPOCH: Self = Self::from_reading(Duration::seconds(315_964_800));
+
+    /// Creates a date time from the full number of weeks since the
+    /// [GPS epoch](Self::GPS_EPOCH), i.e. without any rollover of the 10 or 13
+    /// bit week numbers of the broadcast signals, and the time within the
+    /// week.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the date time can't be represented, which takes trillions of
+    /// weeks.
+    pub fn from_week_and_time(week: i64, time_of_week: Duration) -> Self {
+        Self::GPS_EPOCH + Duration::weeks(week) + time_of_week
+    }
+
+    /// Returns the time since the [GPS epoch](Self::GPS_EPOCH).
+    pub fn since_gps_epoch(self) -> Duration {
+        self - Self::GPS_EPOCH
+    }
+
+    /// Returns the full number of weeks since the
+    /// [GPS epoch](Self::GPS_EPOCH), which is negative before it.
+    pub fn week(self) -> i64 {
+        floor_seconds(self.since_gps_epoch()).div_euclid(Duration::WEEK.whole_seconds())
+    }
+
+    /// Returns the time since the start of the [week](Self::week), which is
+    /// always less than a week, also before the GPS epoch.
+    pub fn time_of_week(self) -> Duration {
+        self.since_gps_epoch() - Duration::weeks(self.week())
+    }
+}
+
+/// A date time on the Terrestrial Time (TT) scale, the time scale of
+/// geocentric ephemerides. It's always exactly 32.184 seconds ahead of TAI.
+pub type TtDateTime = ScaledDateTime<Tt>;
+
+// The Julian Date of J2000.0.
+const JD_J2000: f64 = 2_451_545.0;
+
+// The Julian Date of the MJD epoch.
+const JD_MJD_EPOCH: f64 = 2_400_000.5;
+
+// The days in a Julian century.
+const DAYS_PER_JULIAN_CENTURY: f64 = 36_525.0;
+
+impl ScaledDateTime<Tt> {
+    /// The standard epoch J2000.0, i.e. 2000-01-01 12:00:00 TT, which is
+    /// Julian Date 2451545.0.
+    pub const J2000: Self = Self::from_reading(Duration::seconds(946_728_000));
+
+    // The days since J2000.0, computed from the exact nanoseconds, so they
+    // are as precise as an `f64` allows.
+    fn days_since_j2000(self) -> f64 {
+        (self - Self::J2000).whole_nanoseconds() as f64 / NANOS_PER_DAY as f64
+    }
+
+    /// Returns the Julian Date on the TT scale as two parts whose sum is the
+    /// date, namely the Julian Date of the whole days since
+    /// [J2000.0](Self::J2000) and the fraction of a day on top of it. Like in
+    /// the SOFA library, splitting the date like that keeps its precision at
+    /// around 10 picoseconds, where a single `f64` only gets to around 40 µs
+    /// for present day instants.
+    pub fn to_julian_date_parts(self) -> (f64, f64) {
+        let nanos = (self - Self::J2000).whole_nanoseconds();
+        let days = nanos.div_euclid(NANOS_PER_DAY);
+        let fraction = nanos.rem_euclid(NANOS_PER_DAY);
+        (
+            JD_J2000 + days as f64,
+            fraction as f64 / NANOS_PER_DAY as f64,
+        )
+    }
+
+    /// Returns the Julian Date on the TT scale as a single number. Use
+    /// [`to_julian_date_parts`](Self::to_julian_date_parts) where precision
+    /// matters.
+    pub fn to_julian_date(self) -> f64 {
+        JD_J2000 + self.days_since_j2000()
+    }
+
+    /// Creates a date time from a Julian Date on the TT scale, rounded to the
+    /// nearest nanosecond. Returns an error if the date is not finite or
+    /// outside of the range of a [`TtDateTime`].
+    pub fn from_julian_date(jd: f64) -> Result<Self, OutOfRange> {
+        // A reading is the same on any scale, so the one of the Modified
+        // Julian Date on the TAI scale is the right one here as well.
+        let reading = TaiDateTime::from_mjd_tai(jd - JD_MJD_EPOCH)?.0;
+        Ok(Self::from_reading(reading))
+    }
+
+    /// Returns the Julian centuries since [J2000.0](Self::J2000), the time
+    /// argument of most ephemeris and precession models.
+    pub fn julian_centuries_since_j2000(self) -> f64 {
+        self.days_since_j2000() / DAYS_PER_JULIAN_CENTURY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn scale_readings() {
+        #[cfg(feature = "std")]
+        let _lock = crate::tests::lock_global_table();
+        let utc = datetime!(2017-01-01 0:00 UTC);
+        let unix = utc - OffsetDateTime::UNIX_EPOCH;
+        let tai = TaiDateTime::from(utc);
+
+        assert_eq!(ScaledDateTime::<Utc>::from(utc).reading(), unix);
+        assert_eq!(
+            ScaledDateTime::<Tai>::from(utc).reading(),
+            unix + Duration::seconds(37)
+        );
+        assert_eq!(
+            ScaledDateTime::<Gps>::from(utc).reading(),
+            unix + Duration::seconds(18)
+        );
+        assert_eq!(
+            ScaledDateTime::<Tt>::from(utc).reading(),
+            unix + Duration::seconds(37) + TT_MINUS_TAI,
+        );
+
+        let gps = ScaledDateTime::<Gps>::from_tai(tai);
+        assert_eq!(gps.to_tai(), tai);
+        assert_eq!(gps.to_scale::<Utc>().reading(), unix);
+        assert_eq!(gps.to_scale::<Tt>(), ScaledDateTime::<Tt>::from(utc));
+        assert_eq!(OffsetDateTime::from(gps), utc);
+        assert_eq!(TaiDateTime::from(ScaledDateTime::<Tai>::from(tai)), tai);
+
+        // The readings of UTC skip the leap second, so it's the only scale on
+        // which less time passes.
+        let before = utc - Duration::SECOND;
+        assert_eq!(
+            ScaledDateTime::<Gps>::from(utc) - ScaledDateTime::<Gps>::from(before),
+            Duration::seconds(2)
+        );
+        assert_eq!(
+            ScaledDateTime::<Utc>::from(utc) - ScaledDateTime::<Utc>::from(before),
+            Duration::SECOND,
+        );
+    }
+
+    #[test]
+    fn utc_inside_leap_second() {
+        #[cfg(feature = "std")]
+        let _lock = crate::tests::lock_global_table();
+        let utc = datetime!(2017-01-01 0:00:00.5 UTC);
+        let tai = TaiDateTime::from(utc) - Duration::SECOND;
+        assert_eq!(
+            ScaledDateTime::<Utc>::from_tai(tai).reading(),
+            utc - OffsetDateTime::UNIX_EPOCH,
+        );
+        assert_eq!(
+            ScaledDateTime::<Gps>::from_tai(tai).to_scale::<Utc>(),
+            ScaledDateTime::from(utc)
+        );
+    }
+
+    #[test]
+    fn gps_weeks() {
+        #[cfg(feature = "std")]
+        let _lock = crate::tests::lock_global_table();
+        let epoch = GpsDateTime::GPS_EPOCH;
+        assert_eq!(GpsDateTime::from(datetime!(1980-01-06 0:00 UTC)), epoch);
+        assert_eq!((epoch.week(), epoch.time_of_week()), (0, Duration::ZERO));
+
+        // 2017-01-01 was a Sunday, so the GPS week started 18 seconds before,
+        // as GPS time was 18 seconds ahead of UTC by then.
+        let time = GpsDateTime::from(datetime!(2017-01-01 0:00 UTC));
+        assert_eq!(time.week(), 1930);
+        assert_eq!(time.time_of_week(), Duration::seconds(18));
+        assert_eq!(
+            time.since_gps_epoch(),
+            Duration::weeks(1930) + Duration::seconds(18)
+        );
+        assert_eq!(
+            GpsDateTime::from_week_and_time(1930, Duration::seconds(18)),
+            time
+        );
+
+        let before = epoch - Duration::milliseconds(1_500);
+        assert_eq!(before.week(), -1);
+        assert_eq!(
+            before.time_of_week(),
+            Duration::WEEK - Duration::milliseconds(1_500)
+        );
+        assert_eq!(
+            GpsDateTime::from_week_and_time(before.week(), before.time_of_week()),
+            before,
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn display() {
+        use alloc::string::ToString;
+
+        let utc = datetime!(2017-01-01 0:00 UTC);
+        assert_eq!(
+            ScaledDateTime::<Utc>::from(utc).to_string(),
+            "2017-01-01T00:00:00.000000000 UTC",
+        );
+        assert_eq!(
+            ScaledDateTime::<Tt>::from_reading(Duration::new(-1, -500_000_000)).to_string(),
+            "1969-12-31T23:59:58.500000000 TT",
+        );
+    }
+
+    #[test]
+    fn julian_date_parts() {
+        let j2000 = TtDateTime::J2000;
+        assert_eq!(j2000.to_julian_date_parts(), (JD_J2000, 0.0));
+
+        // A nanosecond is far below the precision of a single `f64`, but not
+        // of the fraction of the day.
+        let time = j2000 + Duration::days(9_000) + Duration::hours(6) + Duration::NANOSECOND;
+        let (days, fraction) = time.to_julian_date_parts();
+        assert_eq!(days, JD_J2000 + 9_000.0);
+        let nanos = fraction * NANOS_PER_DAY as f64 - 6.0 * 3_600e9;
+        assert!((nanos - 1.0).abs() < 0.01, "{nanos}");
+        assert_eq!(days + fraction, time.to_julian_date());
+
+        let (days, fraction) = (j2000 - Duration::NANOSECOND).to_julian_date_parts();
+        assert_eq!(days, JD_J2000 - 1.0);
+        let nanos = (1.0 - fraction) * NANOS_PER_DAY as f64;
+        assert!((nanos - 1.0).abs() < 0.01, "{nanos}");
+    }
+}
@@ -0,0 +1,194 @@
+use time::Duration;
+
+use super::TimeScale;
+#[cfg(feature = "std")]
+use super::{ScaledDateTime, Tt};
+use crate::{TaiDateTime, TT_MINUS_TAI};
+
+// The instant 1977-01-01 00:00:00 TAI, at which TT, TCG and TCB all read
+// 1977-01-01 00:00:32.184, as a reading of those scales.
+const T0: Duration = Duration::new(220_924_832, 184_000_000);
+
+// The rate at which TCG runs ahead of TT, by definition.
+const L_G: f64 = 6.969_290_134e-10;
+
+// The rate at which TCB runs ahead of TDB, by definition.
+#[cfg(feature = "std")]
+const L_B: f64 = 1.550_519_768e-8;
+
+// How far TDB is ahead of TCB at `T0`, by definition.
+#[cfg(feature = "std")]
+const TDB_0: Duration = Duration::nanoseconds(-65_500);
+
+// Scales the time since `T0` by the given factor.
+fn scale_since_t0(reading: Duration, factor: f64) -> Duration {
+    Duration::seconds_f64(reading.saturating_sub(T0).as_seconds_f64() * factor)
+}
+
+/// Geocentric Coordinate Time (TCG), the coordinate time of the geocentric
+/// reference system. It runs faster than TT by a fixed rate of about 0.7 ns
+/// per second, so it's ahead by about 1.1 s in 2025, and matched it in 1977.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tcg;
+
+impl TimeScale for Tcg {
+    const NAME: &'static str = "TCG";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        let tt = reading - scale_since_t0(reading, L_G);
+        TaiDateTime::saturating(tt.saturating_sub(TT_MINUS_TAI))
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        let tt = time.0.saturating_add(TT_MINUS_TAI);
+        tt + scale_since_t0(tt, L_G / (1.0 - L_G))
+    }
+}
+
+/// Barycentric Dynamical Time (TDB), the time scale of barycentric
+/// ephemerides such as the JPL DE series. It differs from TT by periodic terms
+/// of up to about 1.7 ms, which are approximated with the series of USNO
+/// Circular 179, accurate to about 10 µs from 1600 to 2200. Available with the
+/// `std` feature.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tdb;
+
+// How far TDB is ahead of TT at the given reading of either scale. The
+// difference barely changes within the time the two scales differ by, so it
+// doesn't matter which one it is.
+#[cfg(feature = "std")]
+fn tdb_minus_tt(reading: Duration) -> Duration {
+    let t = ScaledDateTime::<Tt>::from_reading(reading).julian_centuries_since_j2000();
+    let seconds = 0.001_657 * (628.3076 * t + 6.2401).sin()
+        + 0.000_022 * (575.3385 * t + 4.2970).sin()
+        + 0.000_014 * (1256.6152 * t + 6.1969).sin()
+        + 0.000_005 * (606.9777 * t + 4.0212).sin()
+        + 0.000_005 * (52.9691 * t + 0.4444).sin()
+        + 0.000_002 * (21.3299 * t + 5.5431).sin()
+        + 0.000_010 * t * (628.3076 * t + 4.2490).sin();
+    Duration::seconds_f64(seconds)
+}
+
+#[cfg(feature = "std")]
+impl TimeScale for Tdb {
+    const NAME: &'static str = "TDB";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        let tt = reading - tdb_minus_tt(reading);
+        TaiDateTime::saturating(tt.saturating_sub(TT_MINUS_TAI))
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        let tt = time.0.saturating_add(TT_MINUS_TAI);
+        tt + tdb_minus_tt(tt)
+    }
+}
+
+/// Barycentric Coordinate Time (TCB), the coordinate time of the barycentric
+/// reference system. It runs faster than TDB by a fixed rate of about 15.5 ns
+/// per second, so it's ahead by about 23 s in 2025. It's derived from
+/// [`Tdb`], so it's just as accurate. Available with the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tcb;
+
+#[cfg(feature = "std")]
+impl TimeScale for Tcb {
+    const NAME: &'static str = "TCB";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        let tdb = reading - scale_since_t0(reading, L_B) + TDB_0;
+        Tdb::to_tai(tdb)
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        let tdb = Tdb::from_tai(time) - TDB_0;
+        tdb + scale_since_t0(tdb, L_B / (1.0 - L_B))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::scale::{ScaledDateTime, Tt};
+
+    fn assert_close(a: Duration, b: Duration, tolerance: Duration) {
+        assert!((a - b).abs() <= tolerance, "{a} vs {b}");
+    }
+
+    #[test]
+    fn tcg_matches_sofa() {
+        // From the test suite of SOFA's `iauTcgtt`, which takes the Julian
+        // Date 2453750.892862531 TCG to 2453750.8928551387488816828 TT.
+        let tcg = ScaledDateTime::<Tcg>::from_reading(Duration::new(1_137_360_343, 322_678_400));
+        let tt = tcg.to_scale::<Tt>().reading();
+        assert_close(
+            tt,
+            Duration::new(1_137_360_342, 683_987_903),
+            Duration::NANOSECOND,
+        );
+    }
+
+    #[test]
+    fn coordinate_times_at_t0() {
+        assert_eq!(Tcg::from_tai(Tt::to_tai(T0)), T0);
+        assert_eq!(Tcg::to_tai(T0), Tt::to_tai(T0));
+        #[cfg(feature = "std")]
+        assert_close(
+            Tcb::from_tai(Tt::to_tai(T0)) - Tdb::from_tai(Tt::to_tai(T0)),
+            -TDB_0,
+            Duration::NANOSECOND,
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn offsets_in_2025() {
+        let tai = TaiDateTime(datetime!(2025-01-01 0:00 UTC) - time::OffsetDateTime::UNIX_EPOCH);
+        let tt = Tt::from_tai(tai);
+        let tcg = Tcg::from_tai(tai) - tt;
+        assert!(
+            tcg > Duration::milliseconds(1_050) && tcg < Duration::milliseconds(1_060),
+            "{tcg}"
+        );
+        let tdb = Tdb::from_tai(tai) - tt;
+        assert!(tdb.abs() < Duration::microseconds(1_700), "{tdb}");
+        let tcb = Tcb::from_tai(tai) - tt;
+        assert!(
+            tcb > Duration::milliseconds(23_400) && tcb < Duration::milliseconds(23_600),
+            "{tcb}"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut rng = fastrand::Rng::with_seed(0x7cb);
+        let start = datetime!(1900-01-01 0:00 UTC) - time::OffsetDateTime::UNIX_EPOCH;
+        let end = datetime!(2100-01-01 0:00 UTC) - time::OffsetDateTime::UNIX_EPOCH;
+        for _ in 0..1_000 {
+            let nanos = rng.i128(start.whole_nanoseconds()..end.whole_nanoseconds());
+            let tai = TaiDateTime::from_unix_tai_nanos(nanos).unwrap();
+            assert_close(
+                Tcg::to_tai(Tcg::from_tai(tai)).0,
+                tai.0,
+                Duration::NANOSECOND,
+            );
+            #[cfg(feature = "std")]
+            {
+                assert_close(
+                    Tdb::to_tai(Tdb::from_tai(tai)).0,
+                    tai.0,
+                    Duration::NANOSECOND,
+                );
+                assert_close(
+                    Tcb::to_tai(Tcb::from_tai(tai)).0,
+                    tai.0,
+                    Duration::NANOSECOND,
+                );
+            }
+        }
+    }
+}
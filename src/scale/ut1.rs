@@ -0,0 +1,345 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+use time::{Duration, OffsetDateTime};
+
+#[cfg(feature = "std")]
+use super::{ScaledDateTime, TimeScale, Utc};
+#[cfg(feature = "std")]
+use crate::TaiDateTime;
+#[cfg(feature = "alloc")]
+use crate::{floor_seconds, leap_seconds_at_utc, MJD_UNIX_EPOCH};
+
+/// Somewhere to look up the difference between UT1 and UTC (DUT1) from, e.g.
+/// the Earth orientation data of the IERS in a [`Dut1Table`].
+pub trait Dut1Provider {
+    /// Returns UT1 − UTC at the given UTC instant, or `None` if the data
+    /// doesn't cover it.
+    fn dut1_at(&self, utc: OffsetDateTime) -> Option<Duration>;
+}
+
+/// The reason a [`Dut1Table`] could not be created.
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Dut1TableError {
+    /// The entry at the given index is not after the previous one.
+    NotSorted {
+        /// The index of the entry.
+        index: usize,
+    },
+    /// UT1 − UTC of the entry at the given index is not within ±1 s, so it
+    /// can't be right, as UTC is kept within 0.9 s of UT1.
+    OutOfRange {
+        /// The index of the entry.
+        index: usize,
+    },
+    /// A line of the IERS data is malformed.
+    MalformedLine {
+        /// The (1-based) number of the line.
+        line: usize,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Dut1TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSorted { index } => {
+                write!(f, "entry {index} is not after the previous one")
+            }
+            Self::OutOfRange { index } => {
+                write!(f, "UT1 - UTC of entry {index} is not within one second")
+            }
+            Self::MalformedLine { line } => write!(f, "line {line} of the IERS data is malformed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Dut1TableError {}
+
+/// A table of daily values of UT1 − UTC at 0h UTC, as published by the IERS,
+/// which is interpolated linearly in between. Instants before the first or
+/// after the last entry aren't covered.
+///
+/// UT1 − UTC jumps by a second at each leap second, so the interpolation is
+/// done on UT1 − TAI instead, with the leap seconds used for conversions.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dut1Table {
+    entries: Vec<(i64, Duration)>,
+}
+
+#[cfg(feature = "alloc")]
+impl Dut1Table {
+    /// Creates a table from pairs of the Modified Julian Date of a day and
+    /// UT1 − UTC at its start. The days have to be strictly increasing, but
+    /// don't need to be consecutive.
+    pub fn from_entries(entries: Vec<(i64, Duration)>) -> Result<Self, Dut1TableError> {
+        let mut previous = None;
+        for (index, &(mjd, dut1)) in entries.iter().enumerate() {
+            if previous.is_some_and(|previous| mjd <= previous) {
+                return Err(Dut1TableError::NotSorted { index });
+            }
+            if dut1.abs() >= Duration::SECOND {
+                return Err(Dut1TableError::OutOfRange { index });
+            }
+            previous = Some(mjd);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Parses the IERS `finals2000A` format, e.g. `finals2000A.all` or
+    /// `finals2000A.daily`, taking the Bulletin A values of UT1 − UTC. Those
+    /// include predictions for the coming months. Lines without a value of
+    /// UT1 − UTC, i.e. the ones past the predictions, are skipped.
+    pub fn parse_iers_finals(text: &str) -> Result<Self, Dut1TableError> {
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let malformed = Dut1TableError::MalformedLine { line: index + 1 };
+            if line.trim().is_empty() {
+                continue;
+            }
+            // The value of UT1 − UTC is in columns 59 to 68, and the MJD in
+            // columns 8 to 15, with a fractional part that is always zero.
+            let dut1 = line.get(58..68).unwrap_or_default().trim();
+            if dut1.is_empty() {
+                continue;
+            }
+            let mjd = line.get(7..15).ok_or(malformed)?.trim();
+            let mjd = mjd.strip_suffix(".00").unwrap_or(mjd);
+            let mjd = mjd.parse().map_err(|_| malformed)?;
+            let dut1: f64 = dut1.parse().map_err(|_| malformed)?;
+            // Way out of range values are rejected by `from_entries` anyway,
+            // but they must not make the conversion panic first.
+            let dut1 = (dut1.is_finite() && dut1.abs() < 1e6)
+                .then(|| Duration::seconds_f64(dut1))
+                .ok_or(malformed)?;
+            entries.push((mjd, dut1));
+        }
+        Self::from_entries(entries)
+    }
+
+    /// The pairs of the Modified Julian Date of a day and UT1 − UTC at its
+    /// start.
+    pub fn entries(&self) -> &[(i64, Duration)] {
+        &self.entries
+    }
+}
+
+// The UTC reading at the start of the day with the given Modified Julian Date.
+#[cfg(feature = "alloc")]
+fn start_of_mjd(mjd: i64) -> Duration {
+    Duration::days(mjd - MJD_UNIX_EPOCH)
+}
+
+// UT1 − TAI at the UTC reading, given UT1 − UTC there.
+#[cfg(feature = "alloc")]
+fn ut1_minus_tai(reading: Duration, dut1: Duration) -> Duration {
+    dut1 - Duration::seconds(leap_seconds_at_utc(floor_seconds(reading)))
+}
+
+#[cfg(feature = "alloc")]
+impl Dut1Provider for Dut1Table {
+    fn dut1_at(&self, utc: OffsetDateTime) -> Option<Duration> {
+        let reading = utc - OffsetDateTime::UNIX_EPOCH;
+        let day = floor_seconds(reading).div_euclid(86_400) + MJD_UNIX_EPOCH;
+        let index = self.entries.partition_point(|&(mjd, _)| mjd <= day);
+        let (mjd, dut1) = self.entries[index.checked_sub(1)?];
+        let start = start_of_mjd(mjd);
+        let Some(&(next_mjd, next_dut1)) = self.entries.get(index) else {
+            return (reading == start).then_some(dut1);
+        };
+        let end = start_of_mjd(next_mjd);
+        let before = ut1_minus_tai(start, dut1);
+        let after = ut1_minus_tai(end, next_dut1);
+        let fraction = (reading - start).as_seconds_f64() / (end - start).as_seconds_f64();
+        let ut1_minus_tai = before + (after - before) * fraction;
+        Some(ut1_minus_tai + Duration::seconds(leap_seconds_at_utc(floor_seconds(reading))))
+    }
+}
+
+#[cfg(feature = "std")]
+type BoxedDut1Provider = Box<dyn Dut1Provider + Send + Sync>;
+
+// The provider used by the conversions of the `Ut1` scale.
+#[cfg(feature = "std")]
+static DUT1_PROVIDER: std::sync::RwLock<Option<BoxedDut1Provider>> = std::sync::RwLock::new(None);
+
+/// Sets the provider of UT1 − UTC that the conversions of the [`Ut1`] scale
+/// use, replacing the previous one. Unlike the leap second sources, this can
+/// happen at any time, e.g. whenever newer IERS data got downloaded.
+#[cfg(feature = "std")]
+pub fn set_dut1_provider(provider: BoxedDut1Provider) {
+    *DUT1_PROVIDER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(provider);
+}
+
+/// Universal Time (UT1), the time scale of the rotation of the Earth, as
+/// needed for pointing telescopes and antennas. Its readings are the ones of
+/// UTC plus UT1 − UTC, which is looked up with the provider set with
+/// [`set_dut1_provider`]. Available with the `std` feature.
+///
+/// If there is no provider or it doesn't cover an instant, UT1 − UTC is taken
+/// to be zero, which is still within 0.9 s, as UTC is kept that close to UT1.
+/// Use [`from_tai_with`](ScaledDateTime::from_tai_with) and
+/// [`to_tai_with`](ScaledDateTime::to_tai_with) to detect that instead.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ut1;
+
+#[cfg(feature = "std")]
+impl TimeScale for Ut1 {
+    const NAME: &'static str = "UT1";
+
+    fn to_tai(reading: Duration) -> TaiDateTime {
+        let provider = DUT1_PROVIDER
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        provider
+            .as_deref()
+            .and_then(|provider| ut1_to_tai(reading, provider))
+            .unwrap_or_else(|| Utc::to_tai(reading))
+    }
+
+    fn from_tai(time: TaiDateTime) -> Duration {
+        let provider = DUT1_PROVIDER
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        provider
+            .as_deref()
+            .and_then(|provider| ut1_from_tai(time, provider))
+            .unwrap_or_else(|| Utc::from_tai(time))
+    }
+}
+
+// UT1 − TAI at the given instant. UT1 − UTC jumps by a second at each leap
+// second, just like TAI − UTC does, so the two jumps cancel out and UT1 − TAI
+// is continuous. Inside of an inserted leap second, UT1 − UTC is looked up in
+// the second after it, which is off by a few microseconds at most.
+#[cfg(feature = "std")]
+fn ut1_minus_tai_at(
+    time: TaiDateTime,
+    provider: &(impl Dut1Provider + ?Sized),
+) -> Option<Duration> {
+    let utc = Utc::from_tai(time);
+    let dut1 = provider.dut1_at(OffsetDateTime::UNIX_EPOCH.checked_add(utc)?)?;
+    Some(ut1_minus_tai(utc, dut1))
+}
+
+#[cfg(feature = "std")]
+fn ut1_from_tai(time: TaiDateTime, provider: &(impl Dut1Provider + ?Sized)) -> Option<Duration> {
+    time.0.checked_add(ut1_minus_tai_at(time, provider)?)
+}
+
+#[cfg(feature = "std")]
+fn ut1_to_tai(reading: Duration, provider: &(impl Dut1Provider + ?Sized)) -> Option<TaiDateTime> {
+    // UT1 − TAI changes by a few milliseconds per day at most, so looking it
+    // up at the UT1 reading and then once more at the resulting TAI instant is
+    // plenty.
+    let mut time = TaiDateTime::saturating(reading);
+    for _ in 0..2 {
+        time = TaiDateTime::checked(reading.checked_sub(ut1_minus_tai_at(time, provider)?)?)?;
+    }
+    Some(time)
+}
+
+/// A date time on the UT1 time scale. Available with the `std` feature.
+#[cfg(feature = "std")]
+pub type Ut1DateTime = ScaledDateTime<Ut1>;
+
+#[cfg(feature = "std")]
+impl ScaledDateTime<Ut1> {
+    /// Converts an instant on the TAI time scale to UT1 with the given
+    /// provider of UT1 − UTC, rather than the one set with
+    /// [`set_dut1_provider`]. Returns `None` if it doesn't cover the instant.
+    pub fn from_tai_with(
+        time: TaiDateTime,
+        provider: &(impl Dut1Provider + ?Sized),
+    ) -> Option<Self> {
+        ut1_from_tai(time, provider).map(Self::from_reading)
+    }
+
+    /// Converts the date time to the instant on the TAI time scale with the
+    /// given provider of UT1 − UTC, rather than the one set with
+    /// [`set_dut1_provider`]. Returns `None` if it doesn't cover the instant.
+    pub fn to_tai_with(self, provider: &(impl Dut1Provider + ?Sized)) -> Option<TaiDateTime> {
+        ut1_to_tai(self.reading, provider)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    // The values of UT1 − UTC around the leap second at the end of 2016.
+    fn table() -> Dut1Table {
+        Dut1Table::from_entries(vec![
+            (57752, Duration::seconds_f64(-0.4084)),
+            (57753, Duration::seconds_f64(-0.4092)),
+            (57754, Duration::seconds_f64(0.5900)),
+            (57755, Duration::seconds_f64(0.5892)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn continuous_across_leap_second() {
+        let table = table();
+        let start = TaiDateTime::from(datetime!(2016-12-31 23:59:58 UTC));
+        let mut previous = Ut1DateTime::from_tai_with(start, &table).unwrap();
+        for step in 1..=20 {
+            let time = start + Duration::milliseconds(250 * step);
+            let ut1 = Ut1DateTime::from_tai_with(time, &table).unwrap();
+            let elapsed = ut1.reading() - previous.reading();
+            assert!(
+                (elapsed - Duration::milliseconds(250)).abs() < Duration::microseconds(10),
+                "{elapsed} at step {step}",
+            );
+            let back = ut1.to_tai_with(&table).unwrap();
+            assert!((back - time).abs() < Duration::microseconds(1));
+            previous = ut1;
+        }
+    }
+
+    #[test]
+    fn inside_leap_second() {
+        let table = table();
+        // 23:59:60.5 UTC, which is 00:00:00.09 UT1.
+        let time = TaiDateTime::from(datetime!(2016-12-31 23:59:59.5 UTC)) + Duration::SECOND;
+        let ut1 = Ut1DateTime::from_tai_with(time, &table).unwrap();
+        let expected = datetime!(2017-01-01 00:00:00.09 UTC) - OffsetDateTime::UNIX_EPOCH;
+        assert!((ut1.reading() - expected).abs() < Duration::microseconds(10));
+    }
+
+    #[test]
+    fn interpolates_dut1() {
+        let table = table();
+        let noon = datetime!(2016-12-30 12:00 UTC);
+        let dut1 = table.dut1_at(noon).unwrap();
+        assert!((dut1 - Duration::seconds_f64(-0.4088)).abs() < Duration::microseconds(1));
+        assert_eq!(table.dut1_at(datetime!(2016-12-28 12:00 UTC)), None);
+        assert_eq!(
+            table.dut1_at(datetime!(2017-01-02 00:00 UTC)),
+            Some(Duration::seconds_f64(0.5892)),
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        assert_eq!(
+            Dut1Table::from_entries(vec![(57753, Duration::ZERO), (57753, Duration::ZERO)]),
+            Err(Dut1TableError::NotSorted { index: 1 }),
+        );
+        assert_eq!(
+            Dut1Table::from_entries(vec![(57753, Duration::SECOND)]),
+            Err(Dut1TableError::OutOfRange { index: 0 }),
+        );
+    }
+}
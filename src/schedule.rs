@@ -0,0 +1,372 @@
+//! Cron-like schedules on the UTC calendar, whose fire times are computed as
+//! TAI instants, so the time between two fires is the actual elapsed time.
+
+use core::{fmt, iter};
+
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime};
+
+use crate::TaiDateTime;
+
+// The bits of the seconds 0 to 59.
+const ALL_SECONDS: u64 = (1 << 60) - 1;
+
+// How many years ahead a matching date is searched for before giving up. The
+// Gregorian calendar repeats every 400 years, so there is no match after that
+// if there is none before.
+const SEARCH_YEARS: i32 = 400;
+
+/// The reason a schedule could not be parsed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ScheduleError {
+    /// The schedule doesn't consist of exactly six fields.
+    FieldCount,
+    /// The field with the given (0-based) index is malformed or out of range.
+    InvalidField {
+        /// The index of the field, 0 being the seconds and 5 the day of the
+        /// week.
+        index: usize,
+    },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FieldCount => f.write_str("the schedule doesn't have six fields"),
+            Self::InvalidField { index } => write!(f, "field {index} of the schedule is malformed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ScheduleError {}
+
+/// Whether an inserted leap second, 23:59:60 UTC, is a fire time of its own.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LeapPolicy {
+    /// The leap second never matches, so a schedule firing every second fires
+    /// 60 times in the minute with the leap second.
+    #[default]
+    Skip,
+    /// The leap second matches if the seconds field is `*`, i.e. the schedule
+    /// fires every second, and the rest of the fields match 23:59 of that day.
+    /// A schedule firing every second then fires 61 times in the minute with
+    /// the leap second. A schedule only matching the 59th second still fires
+    /// just once, at 23:59:59.
+    Include,
+}
+
+/// A schedule in the 6 field cron syntax with a resolution of seconds, such as
+/// `*/5 * * * * *`, evaluated on the UTC calendar.
+///
+/// The fields are the second (0-59), the minute (0-59), the hour (0-23), the
+/// day of the month (1-31), the month (1-12) and the day of the week (0-7,
+/// where both 0 and 7 are Sunday). Each field is either `*` or a comma
+/// separated list of values `a`, ranges `a-b` and steps `*/s`, `a-b/s` or
+/// `a/s`, the latter going up to the end of the field's range. Names of months
+/// and days of the week are not supported. Just like with cron, if both the
+/// day of the month and the day of the week are restricted, i.e. not `*`, a day
+/// matches if either of them matches.
+///
+/// Matches are found on the UTC calendar and mapped to TAI with the leap second
+/// table. See [`LeapPolicy`] for how leap seconds themselves are handled. A
+/// UTC second that doesn't exist, because a leap second removes it, never
+/// matches.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UtcSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_or_weekdays: bool,
+    leap_policy: LeapPolicy,
+}
+
+// Parses a field into the bits of the values it matches.
+fn parse_field(text: &str, min: u8, max: u8) -> Option<u64> {
+    let mut bits = 0;
+    for item in text.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u8>().ok()?)),
+            None => (item, None),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse().ok()?, end.parse().ok()?)
+        } else {
+            let value = range.parse().ok()?;
+            (value, if step.is_some() { max } else { value })
+        };
+        let step = step.unwrap_or(1);
+        if start < min || end > max || start > end || step == 0 {
+            return None;
+        }
+        for value in (start..=end).step_by(step.into()) {
+            bits |= 1 << value;
+        }
+    }
+    Some(bits)
+}
+
+// The first value of at least `from` whose bit is set.
+fn next_value(bits: u64, from: u8, max: u8) -> Option<u8> {
+    (from..=max).find(|&value| bits & 1 << value != 0)
+}
+
+impl UtcSchedule {
+    /// Parses a schedule in the 6 field cron syntax, such as `*/5 * * * * *`.
+    pub fn parse(text: &str) -> Result<Self, ScheduleError> {
+        let mut parts = text.split_whitespace();
+        let mut fields = [""; 6];
+        for field in &mut fields {
+            *field = parts.next().ok_or(ScheduleError::FieldCount)?;
+        }
+        if parts.next().is_some() {
+            return Err(ScheduleError::FieldCount);
+        }
+
+        let ranges = [(0, 59), (0, 59), (0, 23), (1, 31), (1, 12), (0, 7)];
+        let mut bits = [0; 6];
+        for (index, ((field, (min, max)), bits)) in
+            fields.iter().zip(ranges).zip(&mut bits).enumerate()
+        {
+            *bits = parse_field(field, min, max).ok_or(ScheduleError::InvalidField { index })?;
+        }
+
+        // Sunday may be either 0 or 7.
+        let weekdays = (bits[5] | bits[5] >> 7) & 0x7F;
+
+        Ok(Self {
+            seconds: bits[0],
+            minutes: bits[1],
+            hours: bits[2],
+            days: bits[3],
+            months: bits[4],
+            weekdays,
+            days_or_weekdays: !fields[3].starts_with('*') && !fields[5].starts_with('*'),
+            leap_policy: LeapPolicy::Skip,
+        })
+    }
+
+    /// Sets whether an inserted leap second is a fire time of its own. The
+    /// default is [`LeapPolicy::Skip`].
+    pub fn with_leap_policy(self, leap_policy: LeapPolicy) -> Self {
+        Self {
+            leap_policy,
+            ..self
+        }
+    }
+
+    fn matches_date(&self, date: Date) -> bool {
+        let day = self.days & 1 << date.day() != 0;
+        let weekday = self.weekdays & 1 << date.weekday().number_days_from_sunday() != 0;
+        let day = if self.days_or_weekdays {
+            day || weekday
+        } else {
+            day && weekday
+        };
+        day && self.months & 1 << date.month() as u8 != 0
+    }
+
+    // The first UTC date time at or after `start` that matches, ignoring leap
+    // seconds.
+    fn next_match(&self, start: PrimitiveDateTime) -> Option<PrimitiveDateTime> {
+        let last_year = start.year().checked_add(SEARCH_YEARS)?;
+        let mut date = start.date();
+        let (mut hour, mut minute, mut second) = (start.hour(), start.minute(), start.second());
+        loop {
+            if date.year() > last_year {
+                return None;
+            }
+            if self.months & 1 << date.month() as u8 == 0 {
+                let year = match date.month() {
+                    Month::December => date.year() + 1,
+                    _ => date.year(),
+                };
+                date = Date::from_calendar_date(year, date.month().next(), 1).ok()?;
+                (hour, minute, second) = (0, 0, 0);
+                continue;
+            }
+            if !self.matches_date(date) {
+                date = date.next_day()?;
+                (hour, minute, second) = (0, 0, 0);
+                continue;
+            }
+
+            let Some(next_hour) = next_value(self.hours, hour, 23) else {
+                date = date.next_day()?;
+                (hour, minute, second) = (0, 0, 0);
+                continue;
+            };
+            if next_hour != hour {
+                (hour, minute, second) = (next_hour, 0, 0);
+            }
+            let Some(next_minute) = next_value(self.minutes, minute, 59) else {
+                (hour, minute, second) = (hour + 1, 0, 0);
+                continue;
+            };
+            if next_minute != minute {
+                (minute, second) = (next_minute, 0);
+            }
+            let Some(second) = next_value(self.seconds, second, 59) else {
+                (minute, second) = (minute + 1, 0);
+                continue;
+            };
+            return date.with_hms(hour, minute, second).ok();
+        }
+    }
+
+    // The inserted leap second after `time` and before `next`, if the schedule
+    // matches it.
+    fn leap_second_between(&self, time: TaiDateTime, next: TaiDateTime) -> Option<TaiDateTime> {
+        if self.leap_policy != LeapPolicy::Include || self.seconds != ALL_SECONDS {
+            return None;
+        }
+        let midnight = time.next_leap_transition()?;
+        let leap_second = midnight - Duration::SECOND;
+        if leap_second <= time || leap_second >= next {
+            return None;
+        }
+        let utc_midnight = OffsetDateTime::from(midnight);
+        let last_second = TaiDateTime::from(utc_midnight - Duration::SECOND);
+        if midnight - last_second != Duration::seconds(2) {
+            // A removed leap second.
+            return None;
+        }
+        let date = utc_midnight.date().previous_day()?;
+        let matches =
+            self.matches_date(date) && self.hours & 1 << 23 != 0 && self.minutes & 1 << 59 != 0;
+        matches.then_some(leap_second)
+    }
+
+    /// Returns the first fire time after the given instant, or `None` if there
+    /// is none within the next 400 years, e.g. because the schedule only
+    /// matches the 30th of February.
+    pub fn next_after(&self, time: TaiDateTime) -> Option<TaiDateTime> {
+        let (utc, _) = time.to_utc_label(&mut 0);
+        let utc = utc.replace_nanosecond(0).ok()?;
+        let mut start =
+            PrimitiveDateTime::new(utc.date(), utc.time()).checked_add(Duration::SECOND)?;
+        loop {
+            let candidate = self.next_match(start)?.assume_utc();
+            let next = TaiDateTime::from(candidate);
+            // A removed leap second maps to the same instant as the midnight
+            // after it.
+            if OffsetDateTime::from(next) != candidate {
+                start = PrimitiveDateTime::new(candidate.date(), candidate.time())
+                    .checked_add(Duration::SECOND)?;
+                continue;
+            }
+            return Some(self.leap_second_between(time, next).unwrap_or(next));
+        }
+    }
+
+    /// Returns the fire times after the given instant, in chronological order.
+    pub fn iter_from(&self, time: TaiDateTime) -> impl Iterator<Item = TaiDateTime> {
+        let schedule = *self;
+        iter::successors(schedule.next_after(time), move |&time| {
+            schedule.next_after(time)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use time::macros::datetime;
+
+    use super::*;
+
+    // The fire times within the minute starting at the given UTC
+    // date time.
+    fn fires_in_minute(schedule: &UtcSchedule, start: OffsetDateTime) -> Vec<TaiDateTime> {
+        let end = TaiDateTime::from(start + Duration::MINUTE);
+        schedule
+            .iter_from(TaiDateTime::from(start) - Duration::NANOSECOND)
+            .take_while(|&time| time < end)
+            .collect()
+    }
+
+    #[test]
+    fn leap_policy() {
+        let every_second = UtcSchedule::parse("* * * * * *").unwrap();
+        let leap_minute = datetime!(2016-12-31 23:59 UTC);
+        let midnight = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+
+        let skipped = fires_in_minute(&every_second, leap_minute);
+        assert_eq!(skipped.len(), 60);
+        assert_eq!(skipped[59], midnight - Duration::seconds(2));
+
+        let included = every_second.with_leap_policy(LeapPolicy::Include);
+        let fires = fires_in_minute(&included, leap_minute);
+        assert_eq!(fires.len(), 61);
+        assert_eq!(fires[..60], skipped);
+        assert_eq!(fires[60], midnight - Duration::SECOND);
+        assert!(fires.windows(2).all(|w| w[1] - w[0] == Duration::SECOND));
+        assert_eq!(included.next_after(fires[60]), Some(midnight));
+
+        // Minutes without a leap second are the same either way.
+        let ordinary_minute = datetime!(2016-12-30 23:59 UTC);
+        assert_eq!(fires_in_minute(&every_second, ordinary_minute).len(), 60);
+        assert_eq!(fires_in_minute(&included, ordinary_minute).len(), 60);
+
+        // The leap second only matches if every second does.
+        let last_second = UtcSchedule::parse("59 * * * * *")
+            .unwrap()
+            .with_leap_policy(LeapPolicy::Include);
+        assert_eq!(
+            fires_in_minute(&last_second, leap_minute),
+            [midnight - Duration::seconds(2)],
+        );
+
+        // Nor if the rest of the fields don't match 23:59.
+        let other_day = UtcSchedule::parse("* * * 30 * *")
+            .unwrap()
+            .with_leap_policy(LeapPolicy::Include);
+        assert_eq!(
+            other_day.next_after(midnight - Duration::seconds(2)),
+            Some(TaiDateTime::from(datetime!(2017-01-30 0:00 UTC))),
+        );
+    }
+
+    #[test]
+    fn field_count() {
+        for text in ["", "* * * * *", "* * * * * * *"] {
+            assert_eq!(
+                UtcSchedule::parse(text),
+                Err(ScheduleError::FieldCount),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_fields() {
+        let valid = ["0-59", "0-59", "0-23", "1-31", "1-12", "0-7"];
+        let invalid: [&[&str]; 6] = [
+            &["60", "a", "*/0", "5-3", "1,", "-1"],
+            &["60", "0-60", "*/x"],
+            &["24", "12-24", "*/"],
+            &["0", "32", "0-31"],
+            &["0", "13", "Jan"],
+            &["8", "0-8", "Mon"],
+        ];
+        for (index, fields) in invalid.iter().enumerate() {
+            for field in *fields {
+                let mut parts = valid;
+                parts[index] = field;
+                let text = parts.join(" ");
+                assert_eq!(
+                    UtcSchedule::parse(&text),
+                    Err(ScheduleError::InvalidField { index }),
+                    "{text}",
+                );
+            }
+        }
+        assert!(UtcSchedule::parse(&valid.join(" ")).is_ok());
+    }
+}
@@ -0,0 +1,74 @@
+//! Modules for use with serde's `#[serde(with = "...")]` attribute.
+
+/// Serializes a [`TaiDateTime`](crate::TaiDateTime) as a single `i128` of
+/// nanoseconds since 1970-01-01 00:00:00 TAI. This is an exact and compact
+/// representation that sorts the same way as the instants themselves.
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Record {
+///     #[serde(with = "tai_stuff::serde::tai_nanos")]
+///     time: TaiDateTime,
+/// }
+/// ```
+pub mod tai_nanos {
+    use ::serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::TaiDateTime;
+
+    pub fn serialize<S: Serializer>(time: &TaiDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i128(time.0.whole_nanoseconds())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<TaiDateTime, D::Error> {
+        let nanos = i128::deserialize(deserializer)?;
+        TaiDateTime::from_unix_tai_nanos(nanos)
+            .map_err(|_| de::Error::custom("TAI nanoseconds out of range"))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+
+    use time::{macros::datetime, Duration};
+
+    use crate::TaiDateTime;
+
+    fn to_json(time: TaiDateTime) -> String {
+        let mut json = Vec::new();
+        super::tai_nanos::serialize(&time, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        String::from_utf8(json).unwrap()
+    }
+
+    fn from_json(json: &str) -> serde_json::Result<TaiDateTime> {
+        super::tai_nanos::deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn round_trip() {
+        for (time, json) in [
+            (TaiDateTime(Duration::ZERO), "0"),
+            (TaiDateTime(Duration::nanoseconds(1)), "1"),
+            (TaiDateTime(-Duration::milliseconds(500)), "-500000000"),
+            (TaiDateTime(Duration::seconds(-1)), "-1000000000"),
+            (
+                TaiDateTime::from(datetime!(2017-01-01 0:00 UTC)),
+                "1483228837000000000",
+            ),
+            (TaiDateTime::MIN, "-9223372036854775808000000000"),
+            (TaiDateTime::MAX, "9223372036854775807999999999"),
+        ] {
+            assert_eq!(to_json(time), json);
+            assert_eq!(from_json(json).unwrap(), time);
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert!(from_json("9223372036854775808000000000").is_err());
+        assert!(from_json("-9223372036854775808000000001").is_err());
+    }
+}
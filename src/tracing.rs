@@ -0,0 +1,118 @@
+//! Stamping `tracing` events with the time determined on the TAI time scale.
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::{rfc3339::Rfc3339, TaiDateTime};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Format {
+    Rfc3339,
+    Seconds,
+}
+
+/// A timer for `tracing_subscriber` that determines the time on the TAI time
+/// scale, so leap seconds neither get smeared nor repeated in the logs.
+///
+/// ```ignore
+/// tracing_subscriber::fmt()
+///     .with_timer(tai_stuff::tracing::TaiTime::rfc3339())
+///     .init();
+/// ```
+#[derive(Debug)]
+pub struct TaiTime {
+    format: Format,
+    // The position in the leap second table of the previous time stamp. As
+    // log lines are mostly written in order, this is almost always still
+    // correct, so no table lookup is necessary.
+    hint: AtomicUsize,
+}
+
+impl TaiTime {
+    /// Writes the time as an RFC 3339 UTC date time with nanosecond precision,
+    /// such as `2016-12-31T23:59:60.250000000Z`.
+    pub fn rfc3339() -> Self {
+        Self {
+            format: Format::Rfc3339,
+            hint: AtomicUsize::new(0),
+        }
+    }
+
+    /// Writes the time as seconds and nanoseconds since 1970-01-01 00:00:00
+    /// TAI, such as `1483228836.250000000`.
+    pub fn seconds() -> Self {
+        Self {
+            format: Format::Seconds,
+            hint: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Default for TaiTime {
+    fn default() -> Self {
+        Self::rfc3339()
+    }
+}
+
+impl FormatTime for TaiTime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = TaiDateTime::now();
+        match self.format {
+            Format::Rfc3339 => {
+                let mut hint = self.hint.load(Ordering::Relaxed);
+                let formatted = Rfc3339::new(now, &mut hint);
+                self.hint.store(hint, Ordering::Relaxed);
+                write!(w, "{formatted}")
+            }
+            Format::Seconds => {
+                let (secs, nanos) = now.as_timespec();
+                write!(w, "{secs}.{nanos:09}")
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use alloc::string::String;
+
+    use time::{macros::datetime, Duration};
+
+    use super::*;
+    use crate::mock::MockTaiClock;
+
+    fn format(timer: &TaiTime) -> String {
+        let mut text = String::new();
+        timer.format_time(&mut Writer::new(&mut text)).unwrap();
+        text
+    }
+
+    #[test]
+    fn around_leap_second() {
+        let clock = MockTaiClock::new(TaiDateTime::from(datetime!(2016-12-31 23:59:59.25 UTC)));
+        clock.install();
+        let (rfc3339, seconds) = (TaiTime::rfc3339(), TaiTime::seconds());
+
+        for (text, secs) in [
+            ("2016-12-31T23:59:59.250000000Z", "1483228835.250000000"),
+            ("2016-12-31T23:59:60.250000000Z", "1483228836.250000000"),
+            ("2017-01-01T00:00:00.250000000Z", "1483228837.250000000"),
+        ] {
+            assert_eq!(format(&rfc3339), text);
+            assert_eq!(format(&seconds), secs);
+            clock.advance(Duration::SECOND);
+        }
+
+        // Going back across the leap second, which moves the position in the
+        // table the other way.
+        clock.set(TaiDateTime::from(datetime!(2016-12-31 0:00 UTC)));
+        assert_eq!(format(&rfc3339), "2016-12-31T00:00:00.000000000Z");
+        clock.advance(Duration::seconds(86_400));
+        assert_eq!(format(&rfc3339), "2016-12-31T23:59:60.000000000Z");
+        assert_eq!(format(&TaiTime::default()), format(&rfc3339));
+    }
+}
@@ -0,0 +1,293 @@
+use core::fmt;
+
+use time::Duration;
+
+use crate::TaiDateTime;
+
+// The version byte of the current format.
+const VERSION: u8 = 1;
+
+// The version byte reserved for a future variant with a resolution finer than
+// nanoseconds. It's rejected like any other unknown version for now.
+const RESERVED_HIGH_RES_VERSION: u8 = 2;
+
+/// The reason an instant could not be encoded in the wire format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// The buffer is shorter than [`TaiDateTime::WIRE_SIZE`] bytes.
+    BufferTooSmall,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BufferTooSmall => f.write_str("the buffer is too small"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
+/// The reason an instant could not be decoded from the wire format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The input is shorter than [`TaiDateTime::WIRE_SIZE`] bytes.
+    TooShort,
+    /// The version byte is not one this version of the crate can decode. This
+    /// includes the version reserved for a future high resolution variant.
+    UnknownVersion {
+        /// The version byte.
+        version: u8,
+    },
+    /// The nanoseconds are not within `0..1_000_000_000`.
+    InvalidNanoseconds,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort => f.write_str("the input is too short"),
+            Self::UnknownVersion { version } if *version == RESERVED_HIGH_RES_VERSION => {
+                f.write_str("the high resolution wire format is not supported yet")
+            }
+            Self::UnknownVersion { version } => write!(f, "unknown wire format version {version}"),
+            Self::InvalidNanoseconds => f.write_str("the nanoseconds are out of range"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// # Wire format
+///
+/// The wire format is a versioned binary encoding meant for exchanging
+/// instants with other languages. It's 13 bytes long:
+///
+/// | Bytes | Content                                                          |
+/// |-------|------------------------------------------------------------------|
+/// | 0     | The version, currently always 1.                                 |
+/// | 1..9  | The whole TAI seconds since 1970-01-01 00:00:00 TAI as a little endian `i64`, rounded down. |
+/// | 9..13 | The nanoseconds within that second as a little endian `u32`, always less than 1 000 000 000. |
+///
+/// The seconds are rounded down just like with
+/// [`as_timespec`](Self::as_timespec), so the nanoseconds are never negative.
+/// Version 2 is reserved for a future variant with a finer resolution. Decoding
+/// rejects any version other than 1. Version 1 will never change.
+///
+/// Instants and their encoding to test other implementations against are in
+/// the tests at the end of `src/wire.rs`.
+impl TaiDateTime {
+    /// The size of the wire format in bytes.
+    pub const WIRE_SIZE: usize = 13;
+
+    // The instant in the wire format.
+    fn to_wire(self) -> [u8; Self::WIRE_SIZE] {
+        let (secs, nanos) = self.as_timespec();
+        let mut bytes = [0; Self::WIRE_SIZE];
+        bytes[0] = VERSION;
+        bytes[1..9].copy_from_slice(&secs.to_le_bytes());
+        bytes[9..].copy_from_slice(&(nanos as u32).to_le_bytes());
+        bytes
+    }
+
+    /// Appends the instant in the [wire format](#wire-format) to the given
+    /// collection of bytes, e.g. a `Vec<u8>`.
+    pub fn encode(&self, out: &mut impl Extend<u8>) {
+        out.extend(self.to_wire());
+    }
+
+    /// Writes the instant in the [wire format](#wire-format) to the start of
+    /// the buffer and returns the number of bytes written, which is always
+    /// [`WIRE_SIZE`](Self::WIRE_SIZE).
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let buf = buf
+            .get_mut(..Self::WIRE_SIZE)
+            .ok_or(EncodeError::BufferTooSmall)?;
+        buf.copy_from_slice(&self.to_wire());
+        Ok(Self::WIRE_SIZE)
+    }
+
+    /// Decodes an instant in the [wire format](#wire-format) from the start of
+    /// the input and returns it alongside the number of bytes read, which is
+    /// always [`WIRE_SIZE`](Self::WIRE_SIZE) for the current version.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let &version = bytes.first().ok_or(DecodeError::TooShort)?;
+        if version != VERSION {
+            return Err(DecodeError::UnknownVersion { version });
+        }
+        let bytes = bytes.get(..Self::WIRE_SIZE).ok_or(DecodeError::TooShort)?;
+        let secs = i64::from_le_bytes(bytes[1..9].try_into().unwrap());
+        let nanos = u32::from_le_bytes(bytes[9..].try_into().unwrap());
+        if nanos >= 1_000_000_000 {
+            return Err(DecodeError::InvalidNanoseconds);
+        }
+        Ok((Self(Duration::new(secs, nanos as i32)), Self::WIRE_SIZE))
+    }
+}
+
+impl TaiDateTime {
+    /// Encodes the instant as 12 bytes, without a version byte, e.g. for an
+    /// append-only journal. Unlike the [wire format](#wire-format), this is the
+    /// inner [`Duration`] as is:
+    ///
+    /// | Bytes | Content                                                          |
+    /// |-------|------------------------------------------------------------------|
+    /// | 0..8  | The whole TAI seconds since 1970-01-01 00:00:00 TAI as a little endian `i64`, rounded towards zero. |
+    /// | 8..12 | The nanoseconds within that second as a little endian `i32`, with the same sign as the seconds. |
+    ///
+    /// So 1.5 seconds before the epoch are -1 seconds and -500 000 000
+    /// nanoseconds:
+    ///
+    /// ```text
+    /// ff ff ff ff ff ff ff ff 00 9b 32 e2
+    /// ```
+    ///
+    /// This layout will never change.
+    pub fn to_le_bytes(self) -> [u8; 12] {
+        let mut bytes = [0; 12];
+        bytes[..8].copy_from_slice(&self.0.whole_seconds().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.0.subsec_nanoseconds().to_le_bytes());
+        bytes
+    }
+
+    /// Decodes an instant encoded with [`to_le_bytes`](Self::to_le_bytes).
+    /// Bytes that weren't produced by it, i.e. with the nanoseconds out of
+    /// range or of a different sign than the seconds, are added up, saturating
+    /// at [`MIN`](Self::MIN) and [`MAX`](Self::MAX).
+    pub fn from_le_bytes(bytes: [u8; 12]) -> Self {
+        let secs = i64::from_le_bytes(bytes[..8].try_into().unwrap());
+        let nanos = i32::from_le_bytes(bytes[8..].try_into().unwrap());
+        Self::saturating(
+            Duration::seconds(secs).saturating_add(Duration::nanoseconds(nanos.into())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    // Instants and their encoding in the wire format, to test other
+    // implementations against.
+    const GOLDEN: [(TaiDateTime, [u8; TaiDateTime::WIRE_SIZE]); 4] = [
+        // 1970-01-01 00:00:00 TAI
+        (
+            TaiDateTime(Duration::ZERO),
+            [0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ),
+        // 2016-12-31 23:59:60 UTC, i.e. the last leap second, 2017-01-01
+        // 00:00:36 TAI
+        (
+            TaiDateTime(Duration::seconds(1_483_228_836)),
+            [0x01, 0xa4, 0x46, 0x68, 0x58, 0, 0, 0, 0, 0, 0, 0, 0],
+        ),
+        // 1969-12-31 23:59:58.5 TAI, i.e. 1.5 seconds before the epoch
+        (
+            TaiDateTime(Duration::new(-1, -500_000_000)),
+            [
+                0x01, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x65, 0xcd, 0x1d,
+            ],
+        ),
+        // 2001-09-09 01:46:40.123456789 TAI
+        (
+            TaiDateTime(Duration::new(1_000_000_000, 123_456_789)),
+            [
+                0x01, 0x00, 0xca, 0x9a, 0x3b, 0, 0, 0, 0, 0x15, 0xcd, 0x5b, 0x07,
+            ],
+        ),
+    ];
+
+    #[test]
+    fn golden_vectors() {
+        assert_eq!(
+            GOLDEN[1].0,
+            TaiDateTime::from(datetime!(2017-01-01 0:00 UTC)) - Duration::SECOND,
+        );
+        for (time, bytes) in GOLDEN {
+            let mut buf = [0xaa; TaiDateTime::WIRE_SIZE + 1];
+            assert_eq!(time.write_to(&mut buf), Ok(TaiDateTime::WIRE_SIZE));
+            assert_eq!(buf[..TaiDateTime::WIRE_SIZE], bytes, "{time:?}");
+            assert_eq!(buf[TaiDateTime::WIRE_SIZE], 0xaa);
+            assert_eq!(
+                TaiDateTime::decode(&buf),
+                Ok((time, TaiDateTime::WIRE_SIZE)),
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_input() {
+        let (_, bytes) = GOLDEN[3];
+        assert_eq!(
+            GOLDEN[3].0.write_to(&mut [0; TaiDateTime::WIRE_SIZE - 1]),
+            Err(EncodeError::BufferTooSmall),
+        );
+        assert_eq!(TaiDateTime::decode(&[]), Err(DecodeError::TooShort));
+        assert_eq!(
+            TaiDateTime::decode(&bytes[..TaiDateTime::WIRE_SIZE - 1]),
+            Err(DecodeError::TooShort),
+        );
+        let mut high_res = bytes;
+        high_res[0] = RESERVED_HIGH_RES_VERSION;
+        assert_eq!(
+            TaiDateTime::decode(&high_res),
+            Err(DecodeError::UnknownVersion { version: 2 }),
+        );
+        let mut nanos = bytes;
+        nanos[9..].copy_from_slice(&1_000_000_000u32.to_le_bytes());
+        assert_eq!(
+            TaiDateTime::decode(&nanos),
+            Err(DecodeError::InvalidNanoseconds),
+        );
+    }
+
+    #[test]
+    fn le_bytes() {
+        let time = TaiDateTime(Duration::new(-1, -500_000_000));
+        let bytes = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x9b, 0x32, 0xe2,
+        ];
+        assert_eq!(time.to_le_bytes(), bytes);
+        assert_eq!(TaiDateTime::from_le_bytes(bytes), time);
+    }
+
+    #[test]
+    fn le_bytes_round_trip() {
+        for time in [
+            TaiDateTime(Duration::ZERO),
+            TaiDateTime(Duration::new(1_000_000_000, 123_456_789)),
+            TaiDateTime(Duration::new(-1, -500_000_000)),
+            TaiDateTime::from(datetime!(2017-01-01 0:00 UTC)) - Duration::NANOSECOND,
+            TaiDateTime::MIN,
+            TaiDateTime::MAX,
+        ] {
+            assert_eq!(TaiDateTime::from_le_bytes(time.to_le_bytes()), time);
+        }
+    }
+
+    #[test]
+    fn non_canonical_le_bytes() {
+        let encode = |secs: i64, nanos: i32| {
+            let mut bytes = [0; 12];
+            bytes[..8].copy_from_slice(&secs.to_le_bytes());
+            bytes[8..].copy_from_slice(&nanos.to_le_bytes());
+            TaiDateTime::from_le_bytes(bytes)
+        };
+        // The parts are added up.
+        assert_eq!(
+            encode(1, -1_500_000_000),
+            TaiDateTime(Duration::milliseconds(-500)),
+        );
+        assert_eq!(encode(-1, 2_000_000_000), TaiDateTime(Duration::SECOND),);
+        // Beyond the limits, they saturate.
+        assert_eq!(encode(i64::MAX, i32::MAX), TaiDateTime::MAX);
+        assert_eq!(encode(i64::MIN, -1), TaiDateTime::MIN);
+        assert_eq!(encode(i64::MIN, i32::MIN), TaiDateTime::MIN);
+    }
+}
@@ -0,0 +1,39 @@
+//! `Discovery::BuiltinOnly` can only be set once per process, so it gets a test
+//! binary of its own.
+#![cfg(all(unix, feature = "std", not(feature = "no-os-leap-data")))]
+
+use tai_stuff::leap_seconds::{
+    compare_leap_sources, expires_at, leap_source, set_discovery, Discovery, LeapSource, LeapTable,
+    SourceError, TableSnapshot,
+};
+use time::macros::datetime;
+
+#[test]
+fn builtin_only_never_reads_the_tzdb() {
+    let builtin = LeapTable::builtin();
+    let mut entries = builtin.entries().to_vec();
+    entries.push((datetime!(2027-01-01 0:00 UTC).unix_timestamp(), 38));
+    let table = LeapTable::from_entries(entries, datetime!(2027-07-01 0:00 UTC)).unwrap();
+    let list = TableSnapshot {
+        entries: table.iter().collect(),
+        expires_at: Some(table.expires_at()),
+        updated_at: None,
+    }
+    .to_leap_seconds_list();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("leap-seconds.list"), list).unwrap();
+    std::env::set_var("TZDIR", dir.path());
+
+    set_discovery(Discovery::BuiltinOnly).unwrap();
+    assert_eq!(compare_leap_sources().os_source, None);
+
+    assert_eq!(leap_source(), LeapSource::BuiltIn);
+    assert_eq!(expires_at(), builtin.expires_at());
+
+    let comparison = compare_leap_sources();
+    assert_eq!(comparison.os_source, None);
+    assert_eq!(comparison.os_location, None);
+    assert_eq!(comparison.os_outcome, Err(SourceError::Unavailable));
+    assert!(!comparison.os_is_newer());
+    assert_eq!(LeapTable::from_system(), Err(SourceError::Unavailable));
+}
@@ -0,0 +1,168 @@
+//! Exercises what each feature gates, so running the tests with any
+//! combination of features, e.g. `cargo test --no-default-features --features
+//! alloc,dense-table`, covers everything that combination enables. The
+//! conversions themselves are tested in every combination.
+
+use tai_stuff::TaiDateTime;
+use time::{macros::datetime, Duration, OffsetDateTime};
+
+// The leap seconds built into the crate, as the UTC instants they take effect
+// at and the difference between TAI and UTC from then on.
+const LEAP_SECONDS: [(OffsetDateTime, i64); 4] = [
+    (datetime!(1972-07-01 0:00 UTC), 11),
+    (datetime!(1999-01-01 0:00 UTC), 32),
+    (datetime!(2015-07-01 0:00 UTC), 36),
+    (datetime!(2017-01-01 0:00 UTC), 37),
+];
+
+#[test]
+fn conversions() {
+    for (utc, diff) in LEAP_SECONDS {
+        let tai = TaiDateTime::from(utc);
+        assert_eq!(
+            tai - TaiDateTime::from(utc - Duration::SECOND),
+            Duration::seconds(2),
+        );
+        assert_eq!(
+            tai - TaiDateTime::from_utc_with_offset(utc, 0),
+            Duration::seconds(diff),
+        );
+        assert_eq!(OffsetDateTime::from(tai), utc);
+    }
+}
+
+#[test]
+#[cfg(any(feature = "lookup-cache", feature = "dense-table"))]
+fn repeated_lookups() {
+    // Looking up the same spans over and over, both with cached lookups and
+    // with the expanded table, gives the same results as the first time.
+    for _ in 0..3 {
+        conversions();
+    }
+}
+
+#[test]
+#[cfg(feature = "large-dates")]
+fn large_dates() {
+    let utc = time::Date::from_calendar_date(10_000, time::Month::January, 1)
+        .unwrap()
+        .midnight()
+        .assume_utc();
+    assert_eq!(OffsetDateTime::from(TaiDateTime::from(utc)), utc);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn alloc() {
+    use tai_stuff::leap_seconds::LeapTable;
+
+    let table = LeapTable::builtin();
+    assert_eq!(table.entries().len(), 27);
+    for (utc, diff) in LEAP_SECONDS {
+        assert!(table.entries().contains(&(utc.unix_timestamp(), diff)));
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn std() {
+    assert!(TaiDateTime::now() > TaiDateTime::from(datetime!(2024-01-01 0:00 UTC)));
+    assert!(tai_stuff::leap_seconds::expires_at() >= datetime!(2023-06-28 0:00 UTC));
+}
+
+#[test]
+#[cfg(all(feature = "std", feature = "no-os-leap-data", any(unix, windows)))]
+fn no_os_leap_data() {
+    use tai_stuff::leap_seconds::{compare_leap_sources, leap_source, LeapSource};
+
+    assert_eq!(leap_source(), LeapSource::BuiltIn);
+    assert_eq!(compare_leap_sources().os_source, None);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde() {
+    use serde::de::value::{Error, I128Deserializer};
+
+    let tai = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+    let nanos = datetime!(2017-01-01 0:00:37 UTC).unix_timestamp_nanos();
+    let deserialized =
+        tai_stuff::serde::tai_nanos::deserialize(I128Deserializer::<Error>::new(nanos));
+    assert_eq!(deserialized, Ok(tai));
+}
+
+#[test]
+#[cfg(feature = "jiff")]
+fn jiff() {
+    let utc = datetime!(2017-01-01 0:00 UTC);
+    let timestamp = jiff::Timestamp::from_second(utc.unix_timestamp()).unwrap();
+    let tai = TaiDateTime::from(timestamp);
+    assert_eq!(tai, TaiDateTime::from(utc));
+    assert_eq!(jiff::Timestamp::from(tai), timestamp);
+}
+
+#[test]
+#[cfg(feature = "hifitime")]
+fn hifitime() {
+    let tai = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+    let epoch = hifitime::Epoch::try_from(tai).unwrap();
+    assert_eq!(TaiDateTime::from(epoch), tai);
+}
+
+#[test]
+#[cfg(feature = "rkyv")]
+fn rkyv() {
+    fn archived_as<T: rkyv::Archive<Archived = tai_stuff::ArchivedTaiDateTime>>() {}
+
+    archived_as::<TaiDateTime>();
+}
+
+#[test]
+#[cfg(feature = "tokio")]
+fn tokio() {
+    let deadline = TaiDateTime::now() + Duration::SECOND;
+    assert!(deadline.to_tokio_instant().unwrap() > tokio::time::Instant::now());
+}
+
+#[test]
+#[cfg(feature = "test-util")]
+fn test_util() {
+    let start = TaiDateTime::from(datetime!(2017-01-01 0:00 UTC));
+    let clock = tai_stuff::MockTaiClock::new(start);
+    clock.install();
+    assert_eq!(TaiDateTime::now(), start);
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn tracing() {
+    use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+    let mut line = String::new();
+    tai_stuff::tracing::TaiTime::seconds()
+        .format_time(&mut Writer::new(&mut line))
+        .unwrap();
+    assert!(!line.is_empty());
+}
+
+#[test]
+#[cfg(feature = "fetch")]
+fn fetch() {
+    // Fetching needs the network, so this only checks that it's there.
+    let _ = tai_stuff::leap_seconds::fetch_leap_seconds as fn(Option<&str>) -> _;
+}
+
+#[test]
+#[cfg(all(feature = "phc", target_os = "linux"))]
+fn phc() {
+    assert!(tai_stuff::phc::list_phc_devices().is_ok());
+}
+
+#[test]
+#[cfg(all(feature = "windows", windows, not(feature = "no-os-leap-data")))]
+fn windows() {
+    use tai_stuff::leap_seconds::{LeapSecondSource, SourceError, WindowsRegistry};
+
+    // The key is missing on versions of Windows without leap second support.
+    assert_ne!(WindowsRegistry.load().err(), Some(SourceError::Invalid));
+}
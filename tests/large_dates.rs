@@ -0,0 +1,47 @@
+//! Conversions of years beyond ±9999, which `OffsetDateTime` can only represent
+//! with `time`'s `large-dates` feature. Run with `cargo test --features
+//! large-dates-tests`.
+#![cfg(feature = "large-dates-tests")]
+
+use tai_stuff::{TaiDateTime, SUPPORTED_UTC_RANGE};
+use time::{Date, Duration, Month, OffsetDateTime};
+
+fn start_of_year(year: i32) -> OffsetDateTime {
+    Date::from_calendar_date(year, Month::January, 1)
+        .unwrap()
+        .midnight()
+        .assume_utc()
+}
+
+#[test]
+fn years_99999() {
+    // Far from the UTC era, TAI is 10 seconds ahead before it and 37 seconds
+    // after the last leap second.
+    for (year, diff) in [(-99_999, 10), (99_999, 37)] {
+        let utc = start_of_year(year);
+        let tai = TaiDateTime::from(utc);
+        assert_eq!(
+            tai - TaiDateTime::from_utc_with_offset(utc, 0),
+            Duration::seconds(diff),
+        );
+        assert_eq!(OffsetDateTime::from(tai), utc);
+        assert_eq!(tai.to_utc_saturating(), utc);
+    }
+}
+
+#[test]
+fn supported_range() {
+    let (min, max) = (*SUPPORTED_UTC_RANGE.start(), *SUPPORTED_UTC_RANGE.end());
+    assert_eq!(min.year(), -999_999);
+    assert_eq!(max.year(), 999_999);
+    for utc in [min, max] {
+        assert_eq!(OffsetDateTime::from(TaiDateTime::from(utc)), utc);
+    }
+
+    // Beyond the range, only saturating conversions are possible.
+    assert_eq!(TaiDateTime::from(min).to_utc_saturating(), min);
+    assert_eq!(TaiDateTime::MIN.to_utc_saturating(), min);
+    assert_eq!(TaiDateTime::MAX.to_utc_saturating(), max);
+    let past_max = TaiDateTime::from(max) + Duration::minutes(1);
+    assert_eq!(past_max.to_utc_saturating(), max);
+}
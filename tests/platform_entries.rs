@@ -0,0 +1,60 @@
+//! The leap seconds of the platform get merged into the table used for
+//! conversions once per process, so they get a test binary of their own.
+#![cfg(all(unix, feature = "std", not(feature = "no-os-leap-data")))]
+
+use tai_stuff::{
+    leap_seconds::{expires_at, leap_source, LeapSource, LeapTable, TableSnapshot},
+    TaiDateTime,
+};
+use time::{macros::datetime, Duration, OffsetDateTime};
+
+#[test]
+fn platform_entries_before_and_after_expiry() {
+    // A leap second the built-in table lacks, both before and after it
+    // expires.
+    let builtin = LeapTable::builtin();
+    let before_expiry = datetime!(2020-01-01 0:00 UTC);
+    let after_expiry = datetime!(2027-01-01 0:00 UTC);
+    assert!(before_expiry < builtin.expires_at() && builtin.expires_at() < after_expiry);
+    let mut entries = builtin.entries().to_vec();
+    entries.push((before_expiry.unix_timestamp(), 38));
+    entries.push((after_expiry.unix_timestamp(), 39));
+    let table = LeapTable::from_entries(entries, datetime!(2027-07-01 0:00 UTC)).unwrap();
+    let list = TableSnapshot {
+        entries: table.iter().collect(),
+        expires_at: Some(table.expires_at()),
+        updated_at: None,
+    }
+    .to_leap_seconds_list();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("leap-seconds.list"), list).unwrap();
+    std::env::set_var("TZDIR", dir.path());
+
+    assert_eq!(leap_source(), LeapSource::UnixLeapFile);
+    assert_eq!(expires_at(), datetime!(2027-07-01 0:00 UTC));
+
+    let offset = |utc: OffsetDateTime| {
+        let tai = TaiDateTime::from(utc);
+        assert_eq!(OffsetDateTime::from(tai), utc);
+        (tai - TaiDateTime::from_utc_with_offset(utc, 0)).whole_seconds()
+    };
+    for (utc, diff) in [
+        (datetime!(2017-01-01 0:00 UTC), 37),
+        (before_expiry - Duration::SECOND, 37),
+        (before_expiry, 38),
+        (builtin.expires_at(), 38),
+        (after_expiry - Duration::SECOND, 38),
+        (after_expiry, 39),
+    ] {
+        assert_eq!(offset(utc), diff, "at {utc}");
+    }
+
+    // Both leap seconds were inserted, so there are two TAI seconds between
+    // the UTC ones around them.
+    for utc in [before_expiry, after_expiry] {
+        assert_eq!(
+            TaiDateTime::from(utc) - TaiDateTime::from(utc - Duration::SECOND),
+            Duration::seconds(2),
+        );
+    }
+}
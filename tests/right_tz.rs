@@ -0,0 +1,54 @@
+//! The `TZ` environment variable is process wide, so the detection of the
+//! `right/` time zones gets a test binary of its own.
+#![cfg(all(unix, feature = "std"))]
+
+use tai_stuff::{detect_right_tz, TaiDateTime};
+use time::{Duration, OffsetDateTime};
+
+// How far the coarse clock may lag behind the precise one, which is one clock
+// tick, with plenty of room to spare.
+const TICK: Duration = Duration::milliseconds(100);
+
+// Checks that reading the system's real time clock results in the TAI instant
+// it's expected to map to.
+fn check_system_clock(expected: impl Fn(OffsetDateTime) -> TaiDateTime) {
+    let before = OffsetDateTime::now_utc();
+    let now = TaiDateTime::now_coarse();
+    let after = OffsetDateTime::now_utc();
+    assert!(
+        expected(before - TICK) <= now && now <= expected(after),
+        "{now:?}",
+    );
+}
+
+#[test]
+fn right_utc() {
+    for (tz, right) in [
+        (None, false),
+        (Some("UTC"), false),
+        (Some("posix/UTC"), false),
+        (Some("Europe/Berlin"), false),
+        (Some("bright/UTC"), false),
+        (Some("right/UTC"), true),
+        (Some(":right/UTC"), true),
+        (Some("right/Europe/Berlin"), true),
+        (Some("/usr/share/zoneinfo/right/UTC"), true),
+    ] {
+        match tz {
+            Some(tz) => std::env::set_var("TZ", tz),
+            None => std::env::remove_var("TZ"),
+        }
+        assert_eq!(detect_right_tz(), right, "{tz:?}");
+    }
+
+    // The clock counts the leap seconds, so it's TAI - 10 seconds.
+    std::env::set_var("TZ", "right/UTC");
+    check_system_clock(|clock| {
+        let since_epoch = clock - OffsetDateTime::UNIX_EPOCH + Duration::seconds(10);
+        TaiDateTime::from_unix_tai_nanos(since_epoch.whole_nanoseconds()).unwrap()
+    });
+
+    // The clock is UTC, so it's converted with the leap second table.
+    std::env::set_var("TZ", "UTC");
+    check_system_clock(TaiDateTime::from);
+}
@@ -0,0 +1,50 @@
+//! The leap second table gets built once per process, so refusing to run on
+//! broken leap second data gets a test binary of its own.
+#![cfg(all(unix, feature = "std"))]
+
+use std::sync::Mutex;
+
+use tai_stuff::{
+    leap_seconds::{
+        expires_at, last_load_report, set_discovery, set_leap_load_hook, strict_load, Discovery,
+        LeapLoadError, LeapSource, LeapTable, SourceError, UnixLeapFile,
+    },
+    TaiDateTime,
+};
+use time::macros::datetime;
+
+// The outcomes the hook got called with.
+static HOOK_CALLS: Mutex<Vec<Result<(), LeapLoadError>>> = Mutex::new(Vec::new());
+
+#[test]
+fn strict_load_fails_on_a_missing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = UnixLeapFile::new(dir.path().join("leapseconds"));
+    set_discovery(Discovery::Custom(Box::new(missing))).unwrap();
+    set_leap_load_hook(|outcome| HOOK_CALLS.lock().unwrap().push(outcome));
+
+    let report = strict_load().unwrap_err();
+    assert_eq!(report.leap_source, LeapSource::Custom);
+    assert_eq!(report.outcome, Err(SourceError::Unavailable));
+    assert!(report.warnings.is_empty());
+    assert!(report.has_problems());
+    assert_eq!(last_load_report(), Some(report.clone()));
+    assert_eq!(
+        *HOOK_CALLS.lock().unwrap(),
+        [Err(LeapLoadError {
+            leap_source: LeapSource::Custom,
+            error: SourceError::Unavailable,
+        })],
+    );
+
+    // The table is still built leniently, from the built-in entries, and the
+    // sources are only loaded once.
+    assert_eq!(expires_at(), LeapTable::builtin().expires_at());
+    assert_eq!(
+        TaiDateTime::from(datetime!(2017-01-01 0:00 UTC))
+            - TaiDateTime::from(datetime!(2016-12-31 23:59:59 UTC)),
+        time::Duration::seconds(2),
+    );
+    assert_eq!(strict_load(), Err(report));
+    assert_eq!(HOOK_CALLS.lock().unwrap().len(), 1);
+}